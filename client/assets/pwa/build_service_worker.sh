@@ -0,0 +1,16 @@
+#!/bin/sh
+# Writes the service worker into the build, listing its files to precache.
+# Run by Trunk after the build, from `client/`, with the build in `TRUNK_STAGING_DIR`.
+set -e
+
+dist="${TRUNK_STAGING_DIR:-dist}"
+
+files=$(cd "$dist" && find . -type f \
+  ! -name service-worker.js ! -name '*.map' | sed 's|^\./|/|' | sort)
+
+# Any change to the files of the build gives a new version, and thus a new cache.
+version=$(cd "$dist" && echo "$files" | sed 's|^/||' | xargs cat | cksum | cut -d ' ' -f 1)
+urls=$(echo "/" "$files" | tr ' ' '\n' | sed 's|.*|"&"|' | paste -sd ',' -)
+
+sed -e "s|__VERSION__|$version|" -e "s|__PRECACHE_URLS__|$urls|" \
+  ./assets/pwa/service-worker.js > "$dist/service-worker.js"
@@ -0,0 +1,113 @@
+// Service worker of the Tasks Manager. `build_service_worker.sh` fills in
+// the version and the files of the build, so that every build gets a cache
+// of its own and the caches of older builds are dropped once it takes over.
+
+const VERSION = "__VERSION__";
+const PRECACHE = `precache-${VERSION}`;
+const RUNTIME = `runtime-${VERSION}`;
+// Responses of the API, kept only to be shown while offline.
+const API = "api";
+const PRECACHE_URLS = [__PRECACHE_URLS__];
+
+// Page shown for a navigation when neither the network nor the cached app can serve it.
+const OFFLINE_URL = "/offline.html";
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches
+      .open(PRECACHE)
+      .then((cache) => cache.addAll(PRECACHE_URLS))
+      .then(() => self.skipWaiting())
+  );
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((names) =>
+        Promise.all(
+          names
+            .filter((name) => name !== PRECACHE && name !== RUNTIME && name !== API)
+            .map((name) => caches.delete(name))
+        )
+      )
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  const url = new URL(request.url);
+  if (url.origin !== self.location.origin) {
+    return;
+  }
+
+  if (request.method !== "GET") {
+    return;
+  }
+
+  // The responses of the API belong to the user logged in, so they go with the session.
+  if (url.pathname.startsWith("/api/auth/")) {
+    if (url.pathname === "/api/auth/logout") {
+      event.waitUntil(caches.delete(API));
+    }
+    return;
+  }
+  // The live event stream never ends, so there is nothing to cache.
+  if (url.pathname === "/api/events") {
+    return;
+  }
+  if (url.pathname.startsWith("/api/")) {
+    event.respondWith(networkFirst(request));
+    return;
+  }
+
+  if (request.mode === "navigate") {
+    event.respondWith(navigation(request));
+  } else {
+    event.respondWith(cacheFirst(request));
+  }
+});
+
+// Every route of the app is served by `index.html`, which works offline on its own.
+async function navigation(request) {
+  try {
+    return await fetch(request);
+  } catch (error) {
+    const cache = await caches.open(PRECACHE);
+    return (await cache.match("/index.html")) || (await cache.match(OFFLINE_URL));
+  }
+}
+
+// The files of a build never change: they get a new name instead.
+async function cacheFirst(request) {
+  const cached = await caches.match(request);
+  if (cached) {
+    return cached;
+  }
+  const response = await fetch(request);
+  if (response.ok) {
+    const cache = await caches.open(RUNTIME);
+    cache.put(request, response.clone());
+  }
+  return response;
+}
+
+// The API must be fresh: its cached responses only stand in while offline.
+async function networkFirst(request) {
+  try {
+    const response = await fetch(request);
+    if (response.ok) {
+      const cache = await caches.open(API);
+      cache.put(request, response.clone());
+    }
+    return response;
+  } catch (error) {
+    const cached = await caches.match(request, { cacheName: API });
+    if (cached) {
+      return cached;
+    }
+    throw error;
+  }
+}
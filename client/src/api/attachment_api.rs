@@ -0,0 +1,104 @@
+use reqwasm::http;
+use web_sys::{File, FormData};
+
+use super::{
+    types::{Attachment, ErrorResponse, MultipleAttachmentResponse, SingleAttachmentResponse},
+    API_ROOT,
+};
+
+/// Address from which an attachment is downloaded; the browser sends the auth cookie along.
+pub fn attachment_url(task_id: &str, attachment_id: &str) -> String {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    format!(
+        "{}/api/tasks/{}/attachments/{}",
+        api_root, task_id, attachment_id
+    )
+}
+
+pub async fn api_get_attachments(task_id: &str) -> Result<Vec<Attachment>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response =
+        match http::Request::get(&format!("{}/api/tasks/{}/attachments", api_root, task_id))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleAttachmentResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.attachments),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_upload_attachment(task_id: &str, file: &File) -> Result<Attachment, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    // The browser sets the multipart Content-Type, boundary included.
+    let form_data = FormData::new().map_err(|_| "Failed to build the form".to_string())?;
+    form_data
+        .append_with_blob_and_filename("file", file, &file.name())
+        .map_err(|_| "Failed to build the form".to_string())?;
+
+    let response =
+        match http::Request::post(&format!("{}/api/tasks/{}/attachments", api_root, task_id))
+            .credentials(http::RequestCredentials::Include)
+            .body(form_data)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleAttachmentResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.attachment),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_delete_attachment(task_id: &str, attachment_id: &str) -> Result<(), String> {
+    let response = match http::Request::delete(&attachment_url(task_id, attachment_id))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 204 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    Ok(())
+}
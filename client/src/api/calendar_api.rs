@@ -0,0 +1,111 @@
+use reqwasm::http;
+
+use super::{
+    types::{CalendarTokenResponse, ErrorResponse},
+    API_ROOT,
+};
+
+/// Address of the calendar feed that calendar apps subscribe to; it needs no login.
+/// `project` limits it to a project, and `completed` adds the completed tasks.
+pub fn calendar_feed_url(token: &str, completed: bool, project: Option<&str>) -> String {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+    let mut url = format!("{}/api/calendar/{}.ics", api_root, token);
+
+    let mut params = Vec::new();
+    if completed {
+        params.push("completed=true".to_string());
+    }
+    if let Some(project) = project {
+        params.push(format!("project={}", project));
+    }
+    if !params.is_empty() {
+        url.push('?');
+        url.push_str(&params.join("&"));
+    }
+
+    url
+}
+
+pub async fn api_get_calendar_token() -> Result<Option<String>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/calendar/token", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<CalendarTokenResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.calendar_token),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+/// Creates a new token, so that the address with the old one stops working.
+pub async fn api_regenerate_calendar_token() -> Result<String, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/calendar/token", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<CalendarTokenResponse>().await;
+    match res_json {
+        Ok(data) => data
+            .data
+            .calendar_token
+            .ok_or_else(|| "Failed to parse response".to_string()),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_delete_calendar_token() -> Result<(), String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::delete(&format!("{}/api/calendar/token", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 204 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use reqwasm::http;
+
+use super::{
+    types::{Comment, CommentsPage, ErrorResponse, MultipleCommentResponse, SingleCommentResponse},
+    API_ROOT,
+};
+
+pub async fn api_get_comments(task_id: &str, page: usize) -> Result<CommentsPage, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!(
+        "{}/api/tasks/{}/comments?page={}",
+        api_root, task_id, page
+    ))
+    .credentials(http::RequestCredentials::Include)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleCommentResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_create_comment(task_id: &str, comment_data: &str) -> Result<Comment, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response =
+        match http::Request::post(&format!("{}/api/tasks/{}/comments", api_root, task_id))
+            .header("Content-Type", "application/json")
+            .credentials(http::RequestCredentials::Include)
+            .body(comment_data)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleCommentResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.comment),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_update_comment(
+    task_id: &str,
+    comment_id: &str,
+    comment_data: &str,
+) -> Result<Comment, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::patch(&format!(
+        "{}/api/tasks/{}/comments/{}",
+        api_root, task_id, comment_id
+    ))
+    .header("Content-Type", "application/json")
+    .credentials(http::RequestCredentials::Include)
+    .body(comment_data)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleCommentResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.comment),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_delete_comment(task_id: &str, comment_id: &str) -> Result<(), String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::delete(&format!(
+        "{}/api/tasks/{}/comments/{}",
+        api_root, task_id, comment_id
+    ))
+    .credentials(http::RequestCredentials::Include)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 204 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    Ok(())
+}
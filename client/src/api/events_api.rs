@@ -0,0 +1,14 @@
+use web_sys::{EventSource, EventSourceInit};
+
+use super::API_ROOT;
+
+/// Opens the stream of live task events of the logged-in user;
+/// the browser sends the auth cookie along and reconnects by itself when it drops.
+pub fn open_event_source() -> Result<EventSource, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+    let mut init = EventSourceInit::new();
+    init.with_credentials(true);
+
+    EventSource::new_with_event_source_init_dict(&format!("{}/api/events", api_root), &init)
+        .map_err(|_| "Failed to open the live updates".to_string())
+}
@@ -1,6 +1,20 @@
+pub mod attachment_api;
+pub mod calendar_api;
+pub mod comment_api;
+pub mod events_api;
+pub mod project_api;
+pub mod revision_api;
+pub mod share_api;
+pub mod status_api;
+pub mod tag_api;
+pub mod time_api;
 pub mod task_api;
+pub mod task_item_api;
 pub mod types;
 pub mod user_api;
 
+/// Error of the API calls when the server could not be reached at all.
+pub const REQUEST_FAILED: &str = "Failed to make request";
+
 #[allow(unused)]
 const API_ROOT: Option<&'static str> = std::option_env!("API_ROOT");
@@ -0,0 +1,64 @@
+use reqwasm::http;
+
+use super::{
+    types::{ErrorResponse, MultipleProjectResponse, Project, SingleProjectResponse},
+    API_ROOT,
+};
+
+pub async fn api_get_projects() -> Result<Vec<Project>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/projects", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleProjectResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.projects),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_create_project(project_data: &str) -> Result<Project, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/projects", api_root))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(project_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleProjectResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.project),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
@@ -0,0 +1,70 @@
+use reqwasm::http;
+
+use super::{
+    types::{ErrorResponse, MultipleRevisionResponse, Revision, SingleTaskResponse, Task},
+    API_ROOT,
+};
+
+pub async fn api_get_history(task_id: &str) -> Result<Vec<Revision>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/tasks/{}/history", api_root, task_id))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleRevisionResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.revisions),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+/// Returns the reverted task along with its new `ETag`.
+pub async fn api_revert_revision(
+    task_id: &str,
+    revision_id: &str,
+) -> Result<(Task, Option<String>), String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!(
+        "{}/api/tasks/{}/history/{}/revert",
+        api_root, task_id, revision_id
+    ))
+    .credentials(http::RequestCredentials::Include)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let etag = response.headers().get("ETag");
+    let res_json = response.json::<SingleTaskResponse>().await;
+    match res_json {
+        Ok(data) => Ok((data.data.task, etag)),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
@@ -0,0 +1,150 @@
+use reqwasm::http;
+
+use super::{
+    types::{
+        ErrorResponse, MultipleShareResponse, Share, SharedWithMeData, SharedWithMeResponse,
+        SingleShareResponse,
+    },
+    API_ROOT,
+};
+
+pub async fn api_get_shared_with_me() -> Result<SharedWithMeData, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/shares", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SharedWithMeResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+/// `kind` is the collection of the shared resource: `tasks` or `projects`.
+pub async fn api_get_shares(kind: &str, id: &str) -> Result<Vec<Share>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/{}/{}/shares", api_root, kind, id))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleShareResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.shares),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_create_share(kind: &str, id: &str, share_data: &str) -> Result<Share, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/{}/{}/shares", api_root, kind, id))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(share_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleShareResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.share),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_update_share(id: &str, share_data: &str) -> Result<Share, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::patch(&format!("{}/api/shares/{}", api_root, id))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(share_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleShareResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.share),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_delete_share(id: &str) -> Result<(), String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::delete(&format!("{}/api/shares/{}", api_root, id))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 204 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use reqwasm::http;
+
+use super::{
+    types::{ErrorResponse, MultipleStatusResponse, SingleStatusResponse, Status},
+    API_ROOT,
+};
+
+pub async fn api_get_statuses(project: Option<String>) -> Result<Vec<Status>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+    let query = match project {
+        Some(project) => format!("?project={}", project),
+        None => String::new(),
+    };
+
+    let response = match http::Request::get(&format!("{}/api/statuses{}", api_root, query))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleStatusResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.statuses),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_create_status(status_data: &str) -> Result<Status, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/statuses", api_root))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(status_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleStatusResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.status),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_delete_status(id: &str) -> Result<(), String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::delete(&format!("{}/api/statuses/{}", api_root, id))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 204 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    Ok(())
+}
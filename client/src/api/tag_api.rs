@@ -0,0 +1,64 @@
+use reqwasm::http;
+
+use super::{
+    types::{ErrorResponse, MultipleTagResponse, SingleTagResponse, Tag},
+    API_ROOT,
+};
+
+pub async fn api_get_tags() -> Result<Vec<Tag>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/tags", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleTagResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.tags),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_create_tag(tag_data: &str) -> Result<Tag, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/tags", api_root))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(tag_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleTagResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.tag),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
@@ -1,14 +1,32 @@
+use chrono::{DateTime, Local, SecondsFormat, Utc};
 use reqwasm::http;
+use uuid::Uuid;
 
 use super::{
-    types::{ErrorResponse, MultipleTaskResponse, SingleTaskResponse, Task},
-    API_ROOT,
+    types::{
+        BulkResponse, BulkResult, EmptyTrashResponse, ErrorResponse, MultipleTaskResponse,
+        RecurrencePreview, RecurrencePreviewResponse, SingleTaskResponse, Task, TaskChanges,
+        TaskChangesResponse,
+    },
+    API_ROOT, REQUEST_FAILED,
 };
 
-pub async fn api_get_tasks() -> Result<Vec<Task>, String> {
+/// Address from which the tasks are exported as a file in `format` (`json`, `csv`, `md` or `ics`);
+/// the browser sends the auth cookie along.
+pub fn export_tasks_url(format: &str) -> String {
     let api_root = API_ROOT.unwrap_or("http://localhost:8080");
 
-    let response = match http::Request::get(&format!("{}/api/tasks", api_root))
+    format!("{}/api/tasks/export?format={}", api_root, format)
+}
+
+pub async fn api_get_tasks(project: Option<String>) -> Result<Vec<Task>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+    let query = match project {
+        Some(project) => format!("?project={}", project),
+        None => String::new(),
+    };
+
+    let response = match http::Request::get(&format!("{}/api/tasks{}", api_root, query))
         // .header("Content-Type", "application/json")
         .credentials(http::RequestCredentials::Include)
         // .body(task_data)
@@ -16,7 +34,7 @@ pub async fn api_get_tasks() -> Result<Vec<Task>, String> {
         .await
     {
         Ok(res) => res,
-        Err(_) => return Err("Failed to make request".to_string()),
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
     };
 
     if response.status() != 200 {
@@ -35,7 +53,55 @@ pub async fn api_get_tasks() -> Result<Vec<Task>, String> {
     }
 }
 
-pub async fn api_get_single_task(id: String) -> Result<Task, String> {
+/// The tasks of the list of `project` changed after `since`, or after the change of the task
+/// `after` at that moment, oldest change first; deleted tasks come with `deleted_at` set.
+pub async fn api_get_task_changes(
+    since: DateTime<Local>,
+    after: Option<Uuid>,
+    project: Option<String>,
+) -> Result<TaskChanges, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+    // In UTC, since a `+` would have to be escaped in the query string.
+    let mut query = format!(
+        "?since={}",
+        since
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Micros, true)
+    );
+    if let Some(after) = after {
+        query.push_str(&format!("&after={}", after));
+    }
+    if let Some(project) = project {
+        query.push_str(&format!("&project={}", project));
+    }
+
+    let response = match http::Request::get(&format!("{}/api/tasks/changes{}", api_root, query))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<TaskChangesResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+/// Returns the task along with its `ETag`, to be sent back in the `If-Match` header of an update.
+pub async fn api_get_single_task(id: String) -> Result<(Task, Option<String>), String> {
     let api_root = API_ROOT.unwrap_or("http://localhost:8080");
 
     let response = match http::Request::get(&format!("{}/api/tasks/{}", api_root, id))
@@ -46,7 +112,7 @@ pub async fn api_get_single_task(id: String) -> Result<Task, String> {
         .await
     {
         Ok(res) => res,
-        Err(_) => return Err("Failed to make request".to_string()),
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
     };
 
     if response.status() != 200 {
@@ -58,9 +124,10 @@ pub async fn api_get_single_task(id: String) -> Result<Task, String> {
         }
     }
 
+    let etag = response.headers().get("ETag");
     let res_json = response.json::<SingleTaskResponse>().await;
     match res_json {
-        Ok(data) => Ok(data.data.task),
+        Ok(data) => Ok((data.data.task, etag)),
         Err(_) => Err("Failed to parse response".to_string()),
     }
 }
@@ -76,7 +143,7 @@ pub async fn api_create_task(task_data: &str) -> Result<Task, String> {
         .await
     {
         Ok(res) => res,
-        Err(_) => return Err("Failed to make request".to_string()),
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
     };
 
     if response.status() != 201 {
@@ -95,33 +162,54 @@ pub async fn api_create_task(task_data: &str) -> Result<Task, String> {
     }
 }
 
-pub async fn api_update_task(id: String, task_data: &str) -> Result<Task, String> {
+/// Why an update of a task was not saved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpdateTaskError {
+    /// The task was changed by someone else since its `ETag` was read.
+    Conflict,
+    Other(String),
+}
+
+pub async fn api_update_task(
+    id: String,
+    task_data: &str,
+    etag: Option<String>,
+) -> Result<Task, UpdateTaskError> {
     let api_root = API_ROOT.unwrap_or("http://localhost:8080");
 
-    let response = match http::Request::patch(&format!("{}/api/tasks/{}", api_root, id))
+    let mut request = http::Request::patch(&format!("{}/api/tasks/{}", api_root, id))
         .header("Content-Type", "application/json")
-        .credentials(http::RequestCredentials::Include)
-        .body(task_data)
-        .send()
-        .await
-    {
+        .credentials(http::RequestCredentials::Include);
+    if let Some(etag) = etag {
+        request = request.header("If-Match", &etag);
+    }
+
+    let response = match request.body(task_data).send().await {
         Ok(res) => res,
-        Err(_) => return Err("Failed to make request".to_string()),
+        Err(_) => return Err(UpdateTaskError::Other(REQUEST_FAILED.to_string())),
     };
 
+    if response.status() == 412 {
+        return Err(UpdateTaskError::Conflict);
+    }
     if response.status() != 200 {
         let error_response = response.json::<ErrorResponse>().await;
         if let Ok(error_response) = error_response {
-            return Err(error_response.message);
+            return Err(UpdateTaskError::Other(error_response.message));
         } else {
-            return Err(format!("API error: {}", response.status()));
+            return Err(UpdateTaskError::Other(format!(
+                "API error: {}",
+                response.status()
+            )));
         }
     }
 
     let res_json = response.json::<SingleTaskResponse>().await;
     match res_json {
         Ok(data) => Ok(data.data.task),
-        Err(_) => Err("Failed to parse response".to_string()),
+        Err(_) => Err(UpdateTaskError::Other(
+            "Failed to parse response".to_string(),
+        )),
     }
 }
 
@@ -136,7 +224,7 @@ pub async fn api_delete_task(id: String) -> Result<(), String> {
         .await
     {
         Ok(res) => res,
-        Err(_) => return Err("Failed to make request".to_string()),
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
     };
 
     if response.status() != 204 {
@@ -155,3 +243,235 @@ pub async fn api_delete_task(id: String) -> Result<(), String> {
     //     Err(_) => Err("Failed to parse response".to_string()),
     // }
 }
+
+pub async fn api_preview_recurrence(preview_data: &str) -> Result<RecurrencePreview, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/recurrence/preview", api_root))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(preview_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<RecurrencePreviewResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_get_trash() -> Result<Vec<Task>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/tasks/trash", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleTaskResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.tasks),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_restore_task(id: String) -> Result<Task, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/tasks/{}/restore", api_root, id))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleTaskResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.task),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_delete_task_permanently(id: String) -> Result<(), String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::delete(&format!("{}/api/tasks/{}/permanent", api_root, id))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
+    };
+
+    if response.status() != 204 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn api_empty_trash() -> Result<usize, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::delete(&format!("{}/api/tasks/trash", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<EmptyTrashResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.deleted),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_move_task(id: String, move_data: &str) -> Result<Task, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/tasks/{}/move", api_root, id))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(move_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleTaskResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.task),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_bulk_tasks(bulk_data: &str) -> Result<BulkResult, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/tasks/bulk", api_root))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(bulk_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err(REQUEST_FAILED.to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<BulkResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_clear_completed(project: Option<String>) -> Result<usize, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+    let query = match project {
+        Some(project) => format!("?project={}", project),
+        None => String::new(),
+    };
+
+    let response =
+        match http::Request::delete(&format!("{}/api/tasks/completed{}", api_root, query))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err(REQUEST_FAILED.to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    // The body has the same shape as the one of emptying the trash.
+    let res_json = response.json::<EmptyTrashResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.deleted),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
@@ -0,0 +1,162 @@
+use reqwasm::http;
+
+use super::{
+    types::{ErrorResponse, MultipleTaskItemResponse, SingleTaskItemResponse, TaskItem},
+    API_ROOT,
+};
+
+pub async fn api_get_task_items(task_id: &str) -> Result<Vec<TaskItem>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/tasks/{}/items", api_root, task_id))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleTaskItemResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.items),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_create_task_item(task_id: &str, item_data: &str) -> Result<TaskItem, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/tasks/{}/items", api_root, task_id))
+        .header("Content-Type", "application/json")
+        .credentials(http::RequestCredentials::Include)
+        .body(item_data)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleTaskItemResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.item),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_update_task_item(
+    task_id: &str,
+    item_id: &str,
+    item_data: &str,
+) -> Result<TaskItem, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::patch(&format!(
+        "{}/api/tasks/{}/items/{}",
+        api_root, task_id, item_id
+    ))
+    .header("Content-Type", "application/json")
+    .credentials(http::RequestCredentials::Include)
+    .body(item_data)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<SingleTaskItemResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.item),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_delete_task_item(task_id: &str, item_id: &str) -> Result<(), String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::delete(&format!(
+        "{}/api/tasks/{}/items/{}",
+        api_root, task_id, item_id
+    ))
+    .credentials(http::RequestCredentials::Include)
+    .send()
+    .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 204 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn api_reorder_task_items(
+    task_id: &str,
+    order_data: &str,
+) -> Result<Vec<TaskItem>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response =
+        match http::Request::post(&format!("{}/api/tasks/{}/items/reorder", api_root, task_id))
+            .header("Content-Type", "application/json")
+            .credentials(http::RequestCredentials::Include)
+            .body(order_data)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<MultipleTaskItemResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.items),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
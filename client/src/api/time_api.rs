@@ -0,0 +1,95 @@
+use reqwasm::http;
+
+use super::{
+    types::{ErrorResponse, TimeEntry, TimeEntryData, TimeEntryResponse},
+    API_ROOT,
+};
+
+pub async fn api_get_timer() -> Result<Option<TimeEntry>, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::get(&format!("{}/api/timer", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<TimeEntryResponse>().await;
+    match res_json {
+        Ok(data) => Ok(data.data.time_entry),
+        Err(_) => Err("Failed to parse response".to_string()),
+    }
+}
+
+/// Starts a timer on a task, returning it along with the timer it stopped, if any.
+pub async fn api_start_timer(task_id: &str) -> Result<(TimeEntry, Option<TimeEntry>), String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response =
+        match http::Request::post(&format!("{}/api/tasks/{}/timer/start", api_root, task_id))
+            .credentials(http::RequestCredentials::Include)
+            .send()
+            .await
+        {
+            Ok(res) => res,
+            Err(_) => return Err("Failed to make request".to_string()),
+        };
+
+    if response.status() != 201 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<TimeEntryResponse>().await;
+    match res_json.map(|data| data.data) {
+        Ok(TimeEntryData {
+            time_entry: Some(time_entry),
+            stopped,
+        }) => Ok((time_entry, stopped)),
+        _ => Err("Failed to parse response".to_string()),
+    }
+}
+
+pub async fn api_stop_timer() -> Result<TimeEntry, String> {
+    let api_root = API_ROOT.unwrap_or("http://localhost:8080");
+
+    let response = match http::Request::post(&format!("{}/api/timer/stop", api_root))
+        .credentials(http::RequestCredentials::Include)
+        .send()
+        .await
+    {
+        Ok(res) => res,
+        Err(_) => return Err("Failed to make request".to_string()),
+    };
+
+    if response.status() != 200 {
+        let error_response = response.json::<ErrorResponse>().await;
+        if let Ok(error_response) = error_response {
+            return Err(error_response.message);
+        } else {
+            return Err(format!("API error: {}", response.status()));
+        }
+    }
+
+    let res_json = response.json::<TimeEntryResponse>().await;
+    match res_json.map(|data| data.data.time_entry) {
+        Ok(Some(time_entry)) => Ok(time_entry),
+        _ => Err("Failed to parse response".to_string()),
+    }
+}
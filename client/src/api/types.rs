@@ -1,5 +1,8 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
 /****** Types for authentication ******/
@@ -32,7 +35,37 @@ pub struct Task {
     pub description: String,
     pub completed: bool,
     pub user_id: Uuid,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub auto_complete: bool,
+    #[serde(default)]
+    pub due_date: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub recurrence: Option<String>,
     pub created_at: DateTime<Local>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub version: i32,
+    #[serde(default)]
+    pub position: f64,
+    #[serde(default)]
+    pub status_id: Option<Uuid>,
+    #[serde(default)]
+    pub tag_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub progress: ChecklistProgress,
+    #[serde(default)]
+    pub tracked_seconds: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct ChecklistProgress {
+    pub done: usize,
+    pub total: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -57,6 +90,434 @@ pub struct MultipleTaskResponse {
     pub data: TasksData,
 }
 
+/// The tasks changed since a moment, deleted ones included; see `api_get_task_changes`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TaskChanges {
+    pub tasks: Vec<Task>,
+    pub cursor: DateTime<Local>,
+    /// The task whose change at `cursor` was the last one read, if any.
+    pub after: Option<Uuid>,
+    pub has_more: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TaskChangesResponse {
+    pub status: String,
+    pub data: TaskChanges,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EmptyTrashData {
+    pub deleted: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EmptyTrashResponse {
+    pub status: String,
+    pub data: EmptyTrashData,
+}
+
+/// The tasks that end up right above (`before`) and below (`after`) a moved one.
+#[derive(Serialize, Debug)]
+pub struct MoveTaskData {
+    pub before: Option<Uuid>,
+    pub after: Option<Uuid>,
+}
+
+/// The action of a bulk request, serialized the way the API expects it.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkAction {
+    Complete,
+    Uncomplete,
+    Delete,
+    Move { project_id: Option<Uuid> },
+    AddTag { tag_id: Uuid },
+    RemoveTag { tag_id: Uuid },
+}
+
+#[derive(Serialize, Debug)]
+pub struct BulkTaskData {
+    pub ids: Vec<Uuid>,
+    #[serde(flatten)]
+    pub action: BulkAction,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BulkItemResult {
+    pub id: Uuid,
+    pub status: String,
+    pub message: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BulkResult {
+    pub results: Vec<BulkItemResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BulkResponse {
+    pub status: String,
+    pub data: BulkResult,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecurrencePreview {
+    pub rule: String,
+    pub occurrences: Vec<DateTime<Local>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecurrencePreviewResponse {
+    pub status: String,
+    pub data: RecurrencePreview,
+}
+
+/****** Checklist Item Model ******/
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct TaskItem {
+    pub id: Uuid,
+    pub text: String,
+    pub done: bool,
+    pub position: i32,
+    pub task_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TaskItemData {
+    pub item: TaskItem,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TaskItemsData {
+    pub items: Vec<TaskItem>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SingleTaskItemResponse {
+    pub status: String,
+    pub data: TaskItemData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleTaskItemResponse {
+    pub status: String,
+    pub data: TaskItemsData,
+}
+
+/****** Comment Model ******/
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct Comment {
+    pub id: Uuid,
+    pub body: String,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+    pub updated_at: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub author: Option<User>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentData {
+    pub comment: Comment,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentsPage {
+    pub comments: Vec<Comment>,
+    pub page: usize,
+    pub limit: usize,
+    pub has_more: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SingleCommentResponse {
+    pub status: String,
+    pub data: CommentData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleCommentResponse {
+    pub status: String,
+    pub data: CommentsPage,
+}
+
+/****** Attachment Model ******/
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct Attachment {
+    pub id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AttachmentData {
+    pub attachment: Attachment,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AttachmentsData {
+    pub attachments: Vec<Attachment>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SingleAttachmentResponse {
+    pub status: String,
+    pub data: AttachmentData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleAttachmentResponse {
+    pub status: String,
+    pub data: AttachmentsData,
+}
+
+/****** Revision Model ******/
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct Revision {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+    pub changes: BTreeMap<String, FieldChange>,
+    #[serde(default)]
+    pub editor: Option<User>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct FieldChange {
+    pub old: Value,
+    pub new: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevisionsData {
+    pub revisions: Vec<Revision>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleRevisionResponse {
+    pub status: String,
+    pub data: RevisionsData,
+}
+
+/****** Status Model ******/
+
+/// A column of a board; the tasks in a `done` one are the completed ones.
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct Status {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub done: bool,
+    pub sort_order: i32,
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatusData {
+    pub status: Status,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StatusesData {
+    pub statuses: Vec<Status>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SingleStatusResponse {
+    pub status: String,
+    pub data: StatusData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleStatusResponse {
+    pub status: String,
+    pub data: StatusesData,
+}
+
+/****** Time Entry Model ******/
+
+/// Time spent on a task; `ended_at` is `None` while its timer runs.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct TimeEntry {
+    pub id: Uuid,
+    pub started_at: DateTime<Local>,
+    pub ended_at: Option<DateTime<Local>>,
+    pub note: Option<String>,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+    #[serde(default)]
+    pub task: Option<TimeEntryTask>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct TimeEntryTask {
+    pub title: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TimeEntryData {
+    pub time_entry: Option<TimeEntry>,
+    /// The timer that starting a new one stopped.
+    #[serde(default)]
+    pub stopped: Option<TimeEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TimeEntryResponse {
+    pub status: String,
+    pub data: TimeEntryData,
+}
+
+/****** Tag Model ******/
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct Tag {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TagData {
+    pub tag: Tag,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TagsData {
+    pub tags: Vec<Tag>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SingleTagResponse {
+    pub status: String,
+    pub data: TagData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleTagResponse {
+    pub status: String,
+    pub data: TagsData,
+}
+
+/****** Project Model ******/
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct Project {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub icon: String,
+    pub archived: bool,
+    pub sort_order: i32,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectData {
+    pub project: Project,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProjectsData {
+    pub projects: Vec<Project>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SingleProjectResponse {
+    pub status: String,
+    pub data: ProjectData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleProjectResponse {
+    pub status: String,
+    pub data: ProjectsData,
+}
+
+/****** Share Model ******/
+
+#[derive(Debug, Deserialize, Serialize, Default, PartialEq, Clone)]
+pub struct Share {
+    pub id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub role: String,
+    pub shared_by: Uuid,
+    pub created_at: DateTime<Local>,
+    #[serde(default)]
+    pub user: Option<User>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ShareData {
+    pub share: Share,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SharesData {
+    pub shares: Vec<Share>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SingleShareResponse {
+    pub status: String,
+    pub data: ShareData,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MultipleShareResponse {
+    pub status: String,
+    pub data: SharesData,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone)]
+pub struct SharedWithMeData {
+    pub shares: Vec<Share>,
+    pub tasks: Vec<Task>,
+    pub projects: Vec<Project>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SharedWithMeResponse {
+    pub status: String,
+    pub data: SharedWithMeData,
+}
+
+/****** Calendar feed ******/
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CalendarTokenData {
+    /// `None` while the feed is turned off.
+    pub calendar_token: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CalendarTokenResponse {
+    pub status: String,
+    pub data: CalendarTokenData,
+}
+
 /****** Error messages ******/
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -1,2 +1,3 @@
+pub mod shares;
 pub mod tasks;
 pub mod ui;
@@ -0,0 +1,231 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Event, Html, MouseEvent,
+    Properties,
+};
+use yewdux::functional::use_store;
+
+use crate::{
+    api::{
+        share_api::{api_create_share, api_delete_share, api_get_shares, api_update_share},
+        types::Share,
+    },
+    store::{set_show_alert, Store},
+};
+
+const ROLES: [&str; 3] = ["viewer", "editor", "owner"];
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct SharePanelProps {
+    /// Collection of the shared resource: `tasks` or `projects`.
+    pub kind: String,
+    pub id: String,
+}
+
+/// Lets the owners of a task or project share it by email and manage its shares.
+/// Nothing is rendered for users who cannot manage them.
+#[function_component(SharePanel)]
+pub fn share_panel(props: &SharePanelProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let shares = use_state(|| None::<Vec<Share>>);
+    let email = use_state(String::new);
+    let role = use_state(|| ROLES[0].to_string());
+
+    /****** Getting the shares when the component is mounted ******/
+
+    {
+        let shares = shares.clone();
+        let kind = props.kind.clone();
+        let id = props.id.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    // Only owners can list the shares, so an error just hides the panel.
+                    if let Ok(resource_shares) = api_get_shares(&kind, &id).await {
+                        shares.set(Some(resource_shares));
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    /****** Sharing, Changing and Revoking ******/
+
+    let onchange_email = {
+        let email = email.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            email.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let onchange_role = {
+        let role = role.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            role.set(target.unchecked_into::<HtmlSelectElement>().value());
+        })
+    };
+
+    let on_share = {
+        let shares = shares.clone();
+        let email = email.clone();
+        let role = role.clone();
+        let dispatch = dispatch.clone();
+        let kind = props.kind.clone();
+        let id = props.id.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let address = email.trim().to_string();
+            if address.is_empty() {
+                return;
+            }
+
+            let shares = shares.clone();
+            let email = email.clone();
+            let dispatch = dispatch.clone();
+            let kind = kind.clone();
+            let id = id.clone();
+            let share_json = serde_json::json!({ "email": address, "role": *role }).to_string();
+
+            spawn_local(async move {
+                match api_create_share(&kind, &id, &share_json).await {
+                    Ok(share) => {
+                        email.set(String::new());
+                        let mut resource_shares = (*shares).clone().unwrap_or_default();
+                        resource_shares.push(share);
+                        shares.set(Some(resource_shares));
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_change_role = {
+        let shares = shares.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |(share_id, new_role): (String, String)| {
+            let shares = shares.clone();
+            let dispatch = dispatch.clone();
+            let share_json = serde_json::json!({ "role": new_role }).to_string();
+
+            spawn_local(async move {
+                match api_update_share(&share_id, &share_json).await {
+                    Ok(share) => {
+                        let resource_shares = (*shares)
+                            .clone()
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(|current| {
+                                if current.id == share.id {
+                                    share.clone()
+                                } else {
+                                    current
+                                }
+                            })
+                            .collect();
+                        shares.set(Some(resource_shares));
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_revoke = {
+        let shares = shares.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |share_id: String| {
+            let shares = shares.clone();
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                match api_delete_share(&share_id).await {
+                    Ok(_) => {
+                        let mut resource_shares = (*shares).clone().unwrap_or_default();
+                        resource_shares.retain(|share| share.id.to_string() != share_id);
+                        shares.set(Some(resource_shares));
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let Some(resource_shares) = (*shares).clone() else {
+        return html!();
+    };
+
+    html! {
+        <div>
+          <span class="block text-ct-blue-600 mb-3">{"Sharing"}</span>
+          <ul class="space-y-2 mb-3">
+            {
+              resource_shares.into_iter().map(|share| {
+                let share_id = share.id.to_string();
+                let on_change_role = on_change_role.clone();
+                let on_revoke = on_revoke.clone();
+                let revoke_id = share_id.clone();
+                let name = share.user.map(|user| user.email).unwrap_or_default();
+
+                html! {
+                  <li key={share_id.clone()} class="flex items-center gap-2 text-sm">
+                    <span class="flex-1 text-slate-300">{name}</span>
+                    <select
+                      class="rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-2 text-xs"
+                      onchange={Callback::from(move |event: Event| {
+                        let target = event.target().unwrap();
+                        let value = target.unchecked_into::<HtmlSelectElement>().value();
+                        on_change_role.emit((share_id.clone(), value));
+                      })}
+                    >
+                      {
+                        ROLES.iter().map(|role| html! {
+                          <option value={*role} selected={&share.role == role}>{*role}</option>
+                        }).collect::<Html>()
+                      }
+                    </select>
+                    <button type="button" title="Revoke" class="text-red-500"
+                      onclick={Callback::from(move |_: MouseEvent| on_revoke.emit(revoke_id.clone()))}
+                    >
+                      {"✕"}
+                    </button>
+                  </li>
+                }
+              }).collect::<Html>()
+            }
+          </ul>
+          <div class="flex gap-2">
+            <input
+              type="email"
+              placeholder="Email"
+              class="rounded-2xl appearance-none text-ct-dark-600 focus:outline-none py-1 px-4 text-sm"
+              value={(*email).clone()}
+              onchange={onchange_email}
+            />
+            <select
+              class="rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-2 text-sm"
+              onchange={onchange_role}
+            >
+              {
+                ROLES.iter().map(|option| html! {
+                  <option value={*option} selected={&*role == option}>{*option}</option>
+                }).collect::<Html>()
+              }
+            </select>
+            <button type="button" onclick={on_share} class="bg-amber-600 px-3 rounded-md text-sm">
+              {"Share"}
+            </button>
+          </div>
+        </div>
+    }
+}
@@ -0,0 +1,159 @@
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Event, Html, MouseEvent,
+    Properties,
+};
+use yewdux::functional::use_store;
+
+use crate::{
+    api::{
+        attachment_api::{
+            api_delete_attachment, api_get_attachments, api_upload_attachment, attachment_url,
+        },
+        types::Attachment,
+    },
+    store::{set_show_alert, Store},
+};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct AttachmentListProps {
+    pub task_id: String,
+}
+
+#[function_component(AttachmentList)]
+pub fn attachment_list(props: &AttachmentListProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let attachments = use_state(Vec::<Attachment>::new);
+    let uploading = use_state(|| false);
+
+    /****** Getting the attachments when the component is mounted ******/
+
+    {
+        let attachments = attachments.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match api_get_attachments(&task_id).await {
+                        Ok(task_attachments) => attachments.set(task_attachments),
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    /****** Uploading and Deleting ******/
+
+    let onchange_file = {
+        let attachments = attachments.clone();
+        let uploading = uploading.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |event: Event| {
+            let input = event.target().unwrap().unchecked_into::<HtmlInputElement>();
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // Clearing the input lets the same file be picked again.
+            input.set_value("");
+
+            let attachments = attachments.clone();
+            let uploading = uploading.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            uploading.set(true);
+            spawn_local(async move {
+                match api_upload_attachment(&task_id, &file).await {
+                    Ok(attachment) => {
+                        let mut task_attachments = (*attachments).clone();
+                        task_attachments.insert(0, attachment);
+                        attachments.set(task_attachments);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+                uploading.set(false);
+            });
+        })
+    };
+
+    let on_delete = {
+        let attachments = attachments.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |attachment_id: Uuid| {
+            let attachments = attachments.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                match api_delete_attachment(&task_id, &attachment_id.to_string()).await {
+                    Ok(_) => {
+                        let mut task_attachments = (*attachments).clone();
+                        task_attachments.retain(|attachment| attachment.id != attachment_id);
+                        attachments.set(task_attachments);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div>
+          <span class="block text-ct-blue-600 mb-3">{"Attachments"}</span>
+          <ul class="space-y-2 mb-3">
+            {
+              attachments.iter().map(|attachment| {
+                let attachment_id = attachment.id;
+                let on_delete = on_delete.clone();
+
+                html! {
+                  <li key={attachment.id.to_string()} class="flex items-center gap-2 text-sm">
+                    <a
+                      href={attachment_url(&props.task_id, &attachment.id.to_string())}
+                      target="_blank"
+                      class="flex-1 text-sky-300 hover:text-sky-400 truncate"
+                    >
+                      {&attachment.file_name}
+                    </a>
+                    <span class="text-xs text-slate-400">{format_size(attachment.size)}</span>
+                    <button type="button" title="Delete" class="text-red-500"
+                      onclick={Callback::from(move |_: MouseEvent| on_delete.emit(attachment_id))}
+                    >
+                      {"✕"}
+                    </button>
+                  </li>
+                }
+              }).collect::<Html>()
+            }
+          </ul>
+          <input
+            type="file"
+            class="text-sm text-slate-300"
+            disabled={*uploading}
+            onchange={onchange_file}
+          />
+          if *uploading {
+            <span class="text-xs text-slate-400 ml-2">{"Uploading..."}</span>
+          }
+        </div>
+    }
+}
+
+fn format_size(size: i64) -> String {
+    match size {
+        size if size < 1024 => format!("{} B", size),
+        size if size < 1024 * 1024 => format!("{:.1} KB", size as f64 / 1024.0),
+        size => format!("{:.1} MB", size as f64 / (1024.0 * 1024.0)),
+    }
+}
@@ -0,0 +1,112 @@
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlSelectElement;
+use yew::{function_component, html, Callback, Event, Html, MouseEvent, Properties};
+use yewdux::functional::use_store;
+
+use crate::{api::types::BulkAction, store::Store};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct BulkActionBarProps {
+    pub selected: usize,
+    pub on_action: Callback<BulkAction>,
+    pub on_clear_selection: Callback<MouseEvent>,
+}
+
+/// Actions applied at once to every selected task.
+#[function_component(BulkActionBar)]
+pub fn bulk_action_bar(props: &BulkActionBarProps) -> Html {
+    let (store, _) = use_store::<Store>();
+    let tags = store.tags_user.clone().unwrap_or_default();
+    let projects: Vec<_> = store
+        .projects_user
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .chain(store.shared_projects.clone().unwrap_or_default())
+        .collect();
+
+    let on_button = |action: BulkAction| {
+        let on_action = props.on_action.clone();
+
+        Callback::from(move |_: MouseEvent| on_action.emit(action.clone()))
+    };
+
+    // The selects work as menus: picking an option runs the action and resets them.
+    let on_select = |to_action: fn(&str) -> Option<BulkAction>| {
+        let on_action = props.on_action.clone();
+
+        Callback::from(move |event: Event| {
+            let select = event
+                .target()
+                .unwrap()
+                .unchecked_into::<HtmlSelectElement>();
+            if let Some(action) = to_action(&select.value()) {
+                on_action.emit(action);
+            }
+            select.set_value("");
+        })
+    };
+
+    let on_move = on_select(|value| match value {
+        "" => None,
+        "inbox" => Some(BulkAction::Move { project_id: None }),
+        id => Uuid::parse_str(id).ok().map(|project_id| BulkAction::Move {
+            project_id: Some(project_id),
+        }),
+    });
+    let on_add_tag = on_select(|value| {
+        Uuid::parse_str(value)
+            .ok()
+            .map(|tag_id| BulkAction::AddTag { tag_id })
+    });
+    let on_remove_tag = on_select(|value| {
+        Uuid::parse_str(value)
+            .ok()
+            .map(|tag_id| BulkAction::RemoveTag { tag_id })
+    });
+
+    let tag_options = tags
+        .iter()
+        .map(|tag| html! { <option value={tag.id.to_string()}>{&tag.name}</option> })
+        .collect::<Html>();
+
+    html! {
+        <div class="sticky top-2 z-20 flex flex-wrap items-center gap-2 bg-ct-dark-100 rounded-xl px-4 py-3 mb-4 text-sm">
+          <span class="text-sky-300 mr-2">{format!("{} selected", props.selected)}</span>
+          <button type="button" onclick={on_button(BulkAction::Complete)}
+            class="bg-emerald-600 hover:bg-emerald-500 px-3 py-1 rounded-md">
+            {"Complete"}
+          </button>
+          <button type="button" onclick={on_button(BulkAction::Uncomplete)}
+            class="bg-sky-600 hover:bg-sky-500 px-3 py-1 rounded-md">
+            {"Uncomplete"}
+          </button>
+          <select class="rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-2" onchange={on_move}>
+            <option value="" selected=true>{"Move to..."}</option>
+            <option value="inbox">{"Inbox"}</option>
+            {
+              projects.iter().map(|project| html! {
+                <option value={project.id.to_string()}>{format!("{} {}", project.icon, project.name)}</option>
+              }).collect::<Html>()
+            }
+          </select>
+          <select class="rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-2" onchange={on_add_tag}>
+            <option value="" selected=true>{"Add tag..."}</option>
+            {tag_options.clone()}
+          </select>
+          <select class="rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-2" onchange={on_remove_tag}>
+            <option value="" selected=true>{"Remove tag..."}</option>
+            {tag_options}
+          </select>
+          <button type="button" onclick={on_button(BulkAction::Delete)}
+            class="bg-red-600 hover:bg-red-500 px-3 py-1 rounded-md">
+            {"Delete"}
+          </button>
+          <button type="button" onclick={props.on_clear_selection.clone()}
+            class="ml-auto text-slate-400 hover:text-slate-300">
+            {"Clear selection"}
+          </button>
+        </div>
+    }
+}
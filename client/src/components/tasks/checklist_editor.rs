@@ -0,0 +1,242 @@
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Event, Html, MouseEvent,
+    Properties,
+};
+use yewdux::functional::use_store;
+
+use crate::{
+    api::task_item_api::{
+        api_create_task_item, api_delete_task_item, api_get_task_items, api_reorder_task_items,
+        api_update_task_item,
+    },
+    store::{set_show_alert, Store},
+};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct ChecklistEditorProps {
+    pub task_id: String,
+}
+
+#[function_component(ChecklistEditor)]
+pub fn checklist_editor(props: &ChecklistEditorProps) -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let items = use_state(Vec::new);
+    let new_item = use_state(String::new);
+
+    /****** Getting the checklist when the component is mounted ******/
+
+    {
+        let items = items.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match api_get_task_items(&task_id).await {
+                        Ok(task_items) => items.set(task_items),
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    /****** Adding, Checking, Deleting and Moving Items ******/
+
+    let onchange_new_item = {
+        let new_item = new_item.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            new_item.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let on_add_item = {
+        let items = items.clone();
+        let new_item = new_item.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let text = new_item.trim().to_string();
+            if text.is_empty() {
+                return;
+            }
+
+            let items = items.clone();
+            let new_item = new_item.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                let item_json = serde_json::json!({ "text": text }).to_string();
+                match api_create_task_item(&task_id, &item_json).await {
+                    Ok(item) => {
+                        new_item.set(String::new());
+                        let mut task_items = (*items).clone();
+                        task_items.push(item);
+                        items.set(task_items);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_toggle_item = {
+        let items = items.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |(item_id, done): (Uuid, bool)| {
+            let items = items.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                let item_json = serde_json::json!({ "done": done }).to_string();
+                match api_update_task_item(&task_id, &item_id.to_string(), &item_json).await {
+                    Ok(item) => {
+                        let task_items = items
+                            .iter()
+                            .cloned()
+                            .map(|current| {
+                                if current.id == item.id {
+                                    item.clone()
+                                } else {
+                                    current
+                                }
+                            })
+                            .collect();
+                        items.set(task_items);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_delete_item = {
+        let items = items.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |item_id: Uuid| {
+            let items = items.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                match api_delete_task_item(&task_id, &item_id.to_string()).await {
+                    Ok(_) => {
+                        let mut task_items = (*items).clone();
+                        task_items.retain(|item| item.id != item_id);
+                        items.set(task_items);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    // Swaps the item at `index` with the one at `index + 1`.
+    let on_swap_items = {
+        let items = items.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |index: usize| {
+            if index + 1 >= items.len() {
+                return;
+            }
+
+            let items = items.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                let mut item_ids: Vec<Uuid> = items.iter().map(|item| item.id).collect();
+                item_ids.swap(index, index + 1);
+
+                let order_json = serde_json::json!({ "item_ids": item_ids }).to_string();
+                match api_reorder_task_items(&task_id, &order_json).await {
+                    Ok(task_items) => items.set(task_items),
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let done = items.iter().filter(|item| item.done).count();
+
+    html! {
+        <div>
+          <span class="block text-ct-blue-600 mb-3">
+            {"Checklist"}
+            if !items.is_empty() {
+              <span class="ml-2 text-sm text-slate-400">{format!("{}/{}", done, items.len())}</span>
+            }
+          </span>
+          <ul class="space-y-2 mb-3">
+            {
+              items.iter().enumerate().map(|(index, item)| {
+                let item_id = item.id;
+                let done = item.done;
+                let on_toggle_item = on_toggle_item.clone();
+                let on_delete_item = on_delete_item.clone();
+                let on_move_up = on_swap_items.clone();
+                let on_move_down = on_swap_items.clone();
+
+                html! {
+                  <li key={item.id.to_string()} class="flex items-center gap-2">
+                    <input
+                      type="checkbox"
+                      checked={item.done}
+                      class="rounded w-4 h-4 bg-transparent border-gray-300 border-2 checked:accent-emerald-500"
+                      onchange={Callback::from(move |_: Event| on_toggle_item.emit((item_id, !done)))}
+                    />
+                    <span class={format!("flex-1 text-sm {}", if item.done { "line-through text-slate-500" } else { "" })}>
+                      {&item.text}
+                    </span>
+                    <button type="button" title="Move up" disabled={index == 0} class="text-slate-400 disabled:opacity-30"
+                      onclick={Callback::from(move |_: MouseEvent| on_move_up.emit(index.saturating_sub(1)))}
+                    >
+                      {"▲"}
+                    </button>
+                    <button type="button" title="Move down" disabled={index + 1 == items.len()} class="text-slate-400 disabled:opacity-30"
+                      onclick={Callback::from(move |_: MouseEvent| on_move_down.emit(index))}
+                    >
+                      {"▼"}
+                    </button>
+                    <button type="button" title="Delete" class="text-red-500"
+                      onclick={Callback::from(move |_: MouseEvent| on_delete_item.emit(item_id))}
+                    >
+                      {"✕"}
+                    </button>
+                  </li>
+                }
+              }).collect::<Html>()
+            }
+          </ul>
+          <div class="flex gap-2">
+            <input
+              type="text"
+              placeholder="New item"
+              class="rounded-2xl appearance-none text-ct-dark-600 focus:outline-none py-1 px-4 text-sm"
+              value={(*new_item).clone()}
+              onchange={onchange_new_item}
+            />
+            <button type="button" onclick={on_add_item} class="bg-amber-600 px-3 rounded-md text-sm">
+              {"Add"}
+            </button>
+          </div>
+        </div>
+    }
+}
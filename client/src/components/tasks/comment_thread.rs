@@ -0,0 +1,280 @@
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Event, Html, MouseEvent,
+    Properties,
+};
+use yewdux::functional::use_store;
+
+use crate::{
+    api::{
+        comment_api::{
+            api_create_comment, api_delete_comment, api_get_comments, api_update_comment,
+        },
+        types::Comment,
+    },
+    helpers::date_convert,
+    store::{set_show_alert, Store},
+};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct CommentThreadProps {
+    pub task_id: String,
+}
+
+#[function_component(CommentThread)]
+pub fn comment_thread(props: &CommentThreadProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let user_id = store.auth_user.as_ref().map(|user| user.id);
+    let comments = use_state(Vec::<Comment>::new);
+    let page = use_state(|| 0_usize);
+    let has_more = use_state(|| false);
+    let new_comment = use_state(String::new);
+    // The comment being edited and its new text.
+    let editing = use_state(|| None::<(Uuid, String)>);
+
+    /****** Loading the thread page by page ******/
+
+    let load_page = {
+        let comments = comments.clone();
+        let page = page.clone();
+        let has_more = has_more.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |next_page: usize| {
+            let comments = comments.clone();
+            let page = page.clone();
+            let has_more = has_more.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                match api_get_comments(&task_id, next_page).await {
+                    Ok(comments_page) => {
+                        let mut thread = if next_page == 1 {
+                            Vec::new()
+                        } else {
+                            (*comments).clone()
+                        };
+                        thread.extend(comments_page.comments);
+                        comments.set(thread);
+                        page.set(comments_page.page);
+                        has_more.set(comments_page.has_more);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    {
+        let load_page = load_page.clone();
+
+        use_effect_with_deps(move |_| load_page.emit(1), ());
+    }
+
+    let on_load_more = {
+        let load_page = load_page.clone();
+        let page = page.clone();
+
+        Callback::from(move |_: MouseEvent| load_page.emit(*page + 1))
+    };
+
+    /****** Posting, Editing and Deleting Comments ******/
+
+    let onchange_new_comment = {
+        let new_comment = new_comment.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            new_comment.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let on_post = {
+        let comments = comments.clone();
+        let new_comment = new_comment.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let body = new_comment.trim().to_string();
+            if body.is_empty() {
+                return;
+            }
+
+            let comments = comments.clone();
+            let new_comment = new_comment.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                let comment_json = serde_json::json!({ "body": body }).to_string();
+                match api_create_comment(&task_id, &comment_json).await {
+                    Ok(comment) => {
+                        new_comment.set(String::new());
+                        let mut thread = (*comments).clone();
+                        thread.push(comment);
+                        comments.set(thread);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let onchange_editing = {
+        let editing = editing.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            let value = target.unchecked_into::<HtmlInputElement>().value();
+            if let Some((comment_id, _)) = *editing {
+                editing.set(Some((comment_id, value)));
+            }
+        })
+    };
+
+    let on_save_edit = {
+        let comments = comments.clone();
+        let editing = editing.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let Some((comment_id, body)) = (*editing).clone() else {
+                return;
+            };
+
+            let comments = comments.clone();
+            let editing = editing.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                let comment_json = serde_json::json!({ "body": body }).to_string();
+                match api_update_comment(&task_id, &comment_id.to_string(), &comment_json).await {
+                    Ok(comment) => {
+                        editing.set(None);
+                        let thread = comments
+                            .iter()
+                            .cloned()
+                            .map(|current| {
+                                if current.id == comment.id {
+                                    comment.clone()
+                                } else {
+                                    current
+                                }
+                            })
+                            .collect();
+                        comments.set(thread);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_delete = {
+        let comments = comments.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |comment_id: Uuid| {
+            let comments = comments.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                match api_delete_comment(&task_id, &comment_id.to_string()).await {
+                    Ok(_) => {
+                        let mut thread = (*comments).clone();
+                        thread.retain(|comment| comment.id != comment_id);
+                        comments.set(thread);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div>
+          <span class="block text-ct-blue-600 mb-3">{"Comments"}</span>
+          <ul class="space-y-3 mb-3">
+            {
+              comments.iter().map(|comment| {
+                let comment_id = comment.id;
+                let is_own = Some(comment.user_id) == user_id;
+                let author = comment.author.as_ref().map(|author| author.username.clone()).unwrap_or_default();
+                let is_editing = matches!(*editing, Some((id, _)) if id == comment_id);
+                let start_edit = {
+                    let editing = editing.clone();
+                    let body = comment.body.clone();
+                    Callback::from(move |_: MouseEvent| editing.set(Some((comment_id, body.clone()))))
+                };
+                let cancel_edit = {
+                    let editing = editing.clone();
+                    Callback::from(move |_: MouseEvent| editing.set(None))
+                };
+                let on_delete = on_delete.clone();
+
+                html! {
+                  <li key={comment.id.to_string()} class="bg-ct-dark-100 rounded-md p-3 text-sm">
+                    <header class="flex justify-between text-xs text-slate-400 mb-1">
+                      <span class="text-sky-300">{author}</span>
+                      <span>
+                        {date_convert(comment.created_at)}
+                        if comment.updated_at.is_some() {
+                          {" (edited)"}
+                        }
+                      </span>
+                    </header>
+                    if is_editing {
+                      <textarea
+                        class="w-full rounded-md text-ct-dark-600 focus:outline-none p-2"
+                        value={editing.as_ref().map(|(_, body)| body.clone()).unwrap_or_default()}
+                        onchange={onchange_editing.clone()}
+                      />
+                      <div class="flex gap-2 mt-1 text-xs">
+                        <button type="button" class="text-lime-500" onclick={on_save_edit.clone()}>{"Save"}</button>
+                        <button type="button" class="text-slate-400" onclick={cancel_edit}>{"Cancel"}</button>
+                      </div>
+                    } else {
+                      <p class="whitespace-pre-wrap">{&comment.body}</p>
+                      if is_own {
+                        <div class="flex gap-2 mt-1 text-xs">
+                          <button type="button" class="text-sky-400" onclick={start_edit}>{"Edit"}</button>
+                          <button type="button" class="text-red-500"
+                            onclick={Callback::from(move |_: MouseEvent| on_delete.emit(comment_id))}
+                          >
+                            {"Delete"}
+                          </button>
+                        </div>
+                      }
+                    }
+                  </li>
+                }
+              }).collect::<Html>()
+            }
+          </ul>
+          if *has_more {
+            <button type="button" class="text-sm text-sky-400 mb-3" onclick={on_load_more}>
+              {"Load more comments"}
+            </button>
+          }
+          <textarea
+            placeholder="Write a comment"
+            class="w-full rounded-md text-ct-dark-600 focus:outline-none p-2 text-sm"
+            value={(*new_comment).clone()}
+            onchange={onchange_new_comment}
+          />
+          <button type="button" onclick={on_post} class="bg-amber-600 px-3 py-1 rounded-md text-sm mt-1">
+            {"Comment"}
+          </button>
+        </div>
+    }
+}
@@ -0,0 +1,41 @@
+use yew::{function_component, html, Callback, Html, MouseEvent, Properties};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct ConflictDialogProps {
+    /// Drops the changes of the form and loads the task as it is now.
+    pub on_reload: Callback<MouseEvent>,
+    /// Saves the changes of the form over the ones made meanwhile.
+    pub on_overwrite: Callback<MouseEvent>,
+    pub on_cancel: Callback<MouseEvent>,
+}
+
+/// Shown when saving a task that someone else changed after it was loaded.
+#[function_component(ConflictDialog)]
+pub fn conflict_dialog(props: &ConflictDialogProps) -> Html {
+    html! {
+        <div class="fixed inset-0 z-50 grid place-items-center bg-black/60">
+          <div role="alertdialog" aria-modal="true"
+            class="w-11/12 max-w-md bg-ct-dark-100 rounded-2xl p-6 space-y-4">
+            <h2 class="text-lg font-bold text-amber-600">{"This task was changed meanwhile"}</h2>
+            <p class="text-sm text-slate-300">
+              {"Someone else saved this task after you opened it. You can load their version, \
+                losing your changes, or save yours over it."}
+            </p>
+            <div class="flex flex-wrap justify-end gap-2 text-sm">
+              <button type="button" onclick={props.on_cancel.clone()}
+                class="px-3 py-1 rounded-md text-slate-400 hover:text-slate-300">
+                {"Cancel"}
+              </button>
+              <button type="button" onclick={props.on_reload.clone()}
+                class="bg-sky-600 hover:bg-sky-500 px-3 py-1 rounded-md">
+                {"Load their version"}
+              </button>
+              <button type="button" onclick={props.on_overwrite.clone()}
+                class="bg-red-700 hover:bg-red-600 px-3 py-1 rounded-md">
+                {"Keep my changes"}
+              </button>
+            </div>
+          </div>
+        </div>
+    }
+}
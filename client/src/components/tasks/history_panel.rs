@@ -0,0 +1,195 @@
+use chrono::{DateTime, Local};
+use serde_json::Value;
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Html, MouseEvent,
+    Properties,
+};
+use yewdux::functional::use_store;
+
+use crate::{
+    api::{
+        revision_api::{api_get_history, api_revert_revision},
+        types::{Revision, Task},
+    },
+    helpers::date_convert,
+    store::{set_show_alert, Store},
+};
+
+const FIELD_LABELS: [(&str, &str); 7] = [
+    ("title", "Title"),
+    ("description", "Description"),
+    ("completed", "Completed"),
+    ("project_id", "Project"),
+    ("auto_complete", "Auto-complete"),
+    ("due_date", "Due date"),
+    ("recurrence", "Repeat"),
+];
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct HistoryPanelProps {
+    pub task_id: String,
+    /// Called with the task as it is after a revert, and its new `ETag`.
+    pub on_revert: Callback<(Task, Option<String>)>,
+}
+
+/// Lists the revisions of a task with the fields each one changed,
+/// and lets the task be reverted to any of them.
+#[function_component(HistoryPanel)]
+pub fn history_panel(props: &HistoryPanelProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let revisions = use_state(|| None::<Vec<Revision>>);
+    let is_open = use_state(|| false);
+
+    /****** Loading the history every time the panel is opened ******/
+
+    let load_history = {
+        let revisions = revisions.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+
+        Callback::from(move |_: ()| {
+            let revisions = revisions.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+
+            spawn_local(async move {
+                match api_get_history(&task_id).await {
+                    Ok(task_revisions) => revisions.set(Some(task_revisions)),
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    {
+        let load_history = load_history.clone();
+
+        use_effect_with_deps(
+            move |is_open| {
+                if *is_open {
+                    load_history.emit(());
+                }
+            },
+            *is_open,
+        );
+    }
+
+    let on_toggle = {
+        let is_open = is_open.clone();
+
+        Callback::from(move |_: MouseEvent| is_open.set(!*is_open))
+    };
+
+    /****** Reverting ******/
+
+    let on_revert = {
+        let load_history = load_history.clone();
+        let dispatch = dispatch.clone();
+        let task_id = props.task_id.clone();
+        let on_revert = props.on_revert.clone();
+
+        Callback::from(move |revision_id: Uuid| {
+            let load_history = load_history.clone();
+            let dispatch = dispatch.clone();
+            let task_id = task_id.clone();
+            let on_revert = on_revert.clone();
+
+            spawn_local(async move {
+                match api_revert_revision(&task_id, &revision_id.to_string()).await {
+                    Ok(reverted) => {
+                        on_revert.emit(reverted);
+                        load_history.emit(());
+                        set_show_alert("Task reverted".to_string(), dispatch);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    // Project ids are shown by name when the project is known.
+    let projects: Vec<_> = store
+        .projects_user
+        .clone()
+        .unwrap_or_default()
+        .into_iter()
+        .chain(store.shared_projects.clone().unwrap_or_default())
+        .collect();
+    let format_value = |field: &str, value: &Value| -> String {
+        match (field, value) {
+            (_, Value::Null) => "—".to_string(),
+            (_, Value::Bool(value)) => if *value { "Yes" } else { "No" }.to_string(),
+            ("project_id", Value::String(id)) => projects
+                .iter()
+                .find(|project| &project.id.to_string() == id)
+                .map_or_else(|| id.clone(), |project| project.name.clone()),
+            ("due_date", Value::String(date)) => date
+                .parse::<DateTime<Local>>()
+                .map_or_else(|_| date.clone(), date_convert),
+            (_, Value::String(value)) => value.clone(),
+            (_, value) => value.to_string(),
+        }
+    };
+
+    html! {
+        <div>
+          <button type="button" class="text-ct-blue-600 hover:text-sky-400 mb-3" onclick={on_toggle}>
+            {if *is_open { "▾ History" } else { "▸ History" }}
+          </button>
+          if *is_open {
+            if let Some(task_revisions) = (*revisions).clone() {
+              if task_revisions.is_empty() {
+                <p class="text-sm text-slate-400">{"This task has not been edited yet."}</p>
+              } else {
+                <ul class="space-y-3">
+                  {
+                    task_revisions.iter().enumerate().map(|(index, revision)| {
+                      let revision_id = revision.id;
+                      let on_revert = on_revert.clone();
+                      let editor = revision.editor.as_ref().map(|editor| editor.username.clone()).unwrap_or_default();
+
+                      html! {
+                        <li key={revision.id.to_string()} class="bg-ct-dark-100 rounded-md p-3 text-sm">
+                          <header class="flex justify-between items-center text-xs text-slate-400 mb-2">
+                            <span class="text-sky-300">{editor}</span>
+                            <span>{date_convert(revision.created_at)}</span>
+                          </header>
+                          <dl class="space-y-1">
+                            {
+                              FIELD_LABELS.iter().filter_map(|(field, label)| {
+                                let change = revision.changes.get(*field)?;
+                                Some(html! {
+                                  <div class="grid grid-cols-[7rem_1fr] gap-2">
+                                    <dt class="text-slate-400">{*label}</dt>
+                                    <dd class="break-words">
+                                      <del class="text-red-400 mr-2">{format_value(field, &change.old)}</del>
+                                      <ins class="text-lime-400 no-underline">{format_value(field, &change.new)}</ins>
+                                    </dd>
+                                  </div>
+                                })
+                              }).collect::<Html>()
+                            }
+                          </dl>
+                          // The newest revision is the current state of the task.
+                          if index > 0 {
+                            <button type="button" class="text-xs text-amber-500 hover:text-amber-400 mt-2"
+                              onclick={Callback::from(move |_: MouseEvent| on_revert.emit(revision_id))}
+                            >
+                              {"Revert to this version"}
+                            </button>
+                          }
+                        </li>
+                      }
+                    }).collect::<Html>()
+                  }
+                </ul>
+              }
+            } else {
+              <p class="text-sm text-slate-400">{"Loading..."}</p>
+            }
+          }
+        </div>
+    }
+}
@@ -0,0 +1,91 @@
+use uuid::Uuid;
+use yew::{function_component, html, Callback, DragEvent, Html, MouseEvent, Properties};
+
+use super::task_card::TaskCard;
+use crate::api::types::{Status, Task};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct KanbanColumnProps {
+    pub status: Status,
+    pub tasks: Vec<Task>,
+    /// Called with the id of a task when it starts being dragged.
+    pub on_drag_start: Callback<Uuid>,
+    /// Called with the id of the column when a task is dropped on it.
+    pub on_drop: Callback<Uuid>,
+    pub on_delete: Callback<Uuid>,
+}
+
+/// A column of the board with the cards of its tasks.
+#[function_component(KanbanColumn)]
+pub fn kanban_column(props: &KanbanColumnProps) -> Html {
+    let status_id = props.status.id;
+
+    let on_drop = {
+        let on_drop = props.on_drop.clone();
+
+        Callback::from(move |event: DragEvent| {
+            event.prevent_default();
+            on_drop.emit(status_id);
+        })
+    };
+
+    let on_delete = {
+        let on_delete = props.on_delete.clone();
+
+        Callback::from(move |_: MouseEvent| on_delete.emit(status_id))
+    };
+
+    html! {
+        <section
+          class="flex flex-col shrink-0 w-72 bg-zinc-800 rounded-xl p-3"
+          // Dropping is only allowed where the default of `dragover` is prevented.
+          ondragover={Callback::from(|event: DragEvent| event.prevent_default())}
+          ondrop={on_drop}
+        >
+          <header class="flex items-center gap-2 mb-3 text-sm">
+            <span class="w-3 h-3 rounded-full" style={format!("background-color: {};", props.status.color)} />
+            <h2 class="font-semibold">{&props.status.name}</h2>
+            if props.status.done {
+              <span title="Tasks in this column are completed" class="text-emerald-500">{"✓"}</span>
+            }
+            <span class="text-slate-400">{props.tasks.len()}</span>
+            <button type="button" title="Delete column" class="ml-auto text-slate-500 hover:text-red-500" onclick={on_delete}>
+              {"✕"}
+            </button>
+          </header>
+          <ul class="flex flex-col gap-2 min-h-[4rem]">
+            {
+              props.tasks.iter().map(|task| {
+                let task_id = task.id;
+                let on_drag_start = props.on_drag_start.clone();
+
+                html! {
+                  <li
+                    key={task.id.to_string()}
+                    draggable="true"
+                    ondragstart={Callback::from(move |event: DragEvent| {
+                      // Firefox does not start a drag without data.
+                      if let Some(data_transfer) = event.data_transfer() {
+                        let _ = data_transfer.set_data("text/plain", &task_id.to_string());
+                      }
+                      on_drag_start.emit(task_id);
+                    })}
+                  >
+                    <TaskCard
+                      id={task.id}
+                      title={task.title.clone()}
+                      description={task.description.clone()}
+                      completed={task.completed}
+                      due_date={task.due_date}
+                      repeats={task.recurrence.is_some()}
+                      progress={task.progress.clone()}
+                      tracked_seconds={task.tracked_seconds}
+                    />
+                  </li>
+                }
+              }).collect::<Html>()
+            }
+          </ul>
+        </section>
+    }
+}
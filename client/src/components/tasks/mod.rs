@@ -1 +1,12 @@
+pub mod attachment_list;
+pub mod bulk_action_bar;
+pub mod checklist_editor;
+pub mod comment_thread;
+pub mod conflict_dialog;
+pub mod history_panel;
+pub mod kanban_column;
+pub mod recurrence_editor;
+pub mod tag_chip;
+pub mod tag_picker;
 pub mod task_card;
+pub mod timer_button;
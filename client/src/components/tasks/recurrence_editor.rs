@@ -0,0 +1,296 @@
+use chrono::{DateTime, Local};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Event, Html, MouseEvent,
+    Properties,
+};
+
+use crate::{api::task_api::api_preview_recurrence, helpers::date_convert};
+
+const FREQUENCIES: [(&str, &str); 5] = [
+    ("", "Does not repeat"),
+    ("DAILY", "Daily"),
+    ("WEEKLY", "Weekly"),
+    ("MONTHLY", "Monthly"),
+    ("YEARLY", "Yearly"),
+];
+const WEEKDAYS: [(&str, &str); 7] = [
+    ("MO", "Mon"),
+    ("TU", "Tue"),
+    ("WE", "Wed"),
+    ("TH", "Thu"),
+    ("FR", "Fri"),
+    ("SA", "Sat"),
+    ("SU", "Sun"),
+];
+/// Number of upcoming occurrences shown under the editor.
+const PREVIEW_COUNT: usize = 5;
+
+/// The parts of a rule that the simple editor can show. Rules using anything
+/// else, such as `UNTIL` or several days of the month, are edited as text.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct RuleParts {
+    frequency: String,
+    interval: u32,
+    weekdays: Vec<String>,
+    month_day: Option<i32>,
+}
+
+impl RuleParts {
+    fn parse(rule: &str) -> Option<RuleParts> {
+        let mut parts = RuleParts {
+            interval: 1,
+            ..RuleParts::default()
+        };
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part.split_once('=')?;
+            match key {
+                "FREQ" => parts.frequency = value.to_string(),
+                "INTERVAL" => parts.interval = value.parse().ok()?,
+                "BYDAY" => parts.weekdays = value.split(',').map(str::to_string).collect(),
+                "BYMONTHDAY" => parts.month_day = Some(value.parse().ok()?),
+                _ => return None,
+            }
+        }
+
+        Some(parts)
+    }
+
+    fn to_rule(&self) -> Option<String> {
+        if self.frequency.is_empty() {
+            return None;
+        }
+
+        let mut rule = format!("FREQ={}", self.frequency);
+        if self.interval > 1 {
+            rule.push_str(&format!(";INTERVAL={}", self.interval));
+        }
+        if self.frequency == "WEEKLY" && !self.weekdays.is_empty() {
+            // Always in calendar order, whatever the order they were picked in.
+            let weekdays: Vec<&str> = WEEKDAYS
+                .iter()
+                .map(|(code, _)| *code)
+                .filter(|code| self.weekdays.iter().any(|day| day == code))
+                .collect();
+            rule.push_str(&format!(";BYDAY={}", weekdays.join(",")));
+        }
+        if self.frequency == "MONTHLY" {
+            if let Some(month_day) = self.month_day {
+                rule.push_str(&format!(";BYMONTHDAY={}", month_day));
+            }
+        }
+
+        Some(rule)
+    }
+}
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct RecurrenceEditorProps {
+    pub recurrence: Option<String>,
+    /// Due date of the task, from which the occurrences are counted.
+    pub due_date: Option<DateTime<Local>>,
+    pub on_change: Callback<Option<String>>,
+}
+
+/// Edits the recurrence rule of a task and previews its next occurrences.
+#[function_component(RecurrenceEditor)]
+pub fn recurrence_editor(props: &RecurrenceEditorProps) -> Html {
+    let occurrences = use_state(|| Ok::<Vec<DateTime<Local>>, String>(Vec::new()));
+    let rule = props.recurrence.clone().unwrap_or_default();
+    let parts = RuleParts::parse(&rule);
+
+    /****** Previewing the next occurrences whenever the rule or the due date change ******/
+
+    {
+        let occurrences = occurrences.clone();
+
+        use_effect_with_deps(
+            move |(recurrence, due_date)| match (recurrence.clone(), *due_date) {
+                (Some(rule), Some(start)) => {
+                    let preview_json = serde_json::json!({
+                        "rule": rule,
+                        "start": start,
+                        "count": PREVIEW_COUNT
+                    })
+                    .to_string();
+
+                    spawn_local(async move {
+                        let preview = api_preview_recurrence(&preview_json).await;
+                        occurrences.set(preview.map(|preview| preview.occurrences));
+                    });
+                }
+                _ => occurrences.set(Ok(Vec::new())),
+            },
+            (props.recurrence.clone(), props.due_date),
+        );
+    }
+
+    /****** Editing the rule ******/
+
+    let emit_parts = {
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |parts: RuleParts| on_change.emit(parts.to_rule()))
+    };
+
+    let onchange_frequency = {
+        let emit_parts = emit_parts.clone();
+        let parts = parts.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            let frequency = target.unchecked_into::<HtmlSelectElement>().value();
+            emit_parts.emit(RuleParts {
+                frequency,
+                interval: parts.as_ref().map_or(1, |parts| parts.interval),
+                ..RuleParts::default()
+            });
+        })
+    };
+
+    let onchange_interval = {
+        let emit_parts = emit_parts.clone();
+        let parts = parts.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            let value = target.unchecked_into::<HtmlInputElement>().value();
+            if let (Some(mut parts), Ok(interval)) = (parts.clone(), value.parse::<u32>()) {
+                parts.interval = interval.clamp(1, 999);
+                emit_parts.emit(parts);
+            }
+        })
+    };
+
+    let onchange_month_day = {
+        let emit_parts = emit_parts.clone();
+        let parts = parts.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+            if let Some(mut parts) = parts.clone() {
+                parts.month_day = value.parse::<i32>().ok();
+                emit_parts.emit(parts);
+            }
+        })
+    };
+
+    let onchange_custom = {
+        let on_change = props.on_change.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            let value = target.unchecked_into::<HtmlInputElement>().value();
+            let value = value.trim().to_uppercase();
+            on_change.emit((!value.is_empty()).then_some(value));
+        })
+    };
+
+    let occurrences_preview = match &*occurrences {
+        Ok(dates) if !dates.is_empty() => html! {
+          <ul class="text-xs text-slate-400 mt-2 space-y-1">
+            <li class="text-slate-300">{"Next occurrences:"}</li>
+            { dates.iter().map(|date| html! { <li>{date_convert(*date)}</li> }).collect::<Html>() }
+          </ul>
+        },
+        Ok(_) => html!(),
+        Err(e) => html! { <p class="text-xs text-red-400 mt-2">{e}</p> },
+    };
+
+    html! {
+        <div>
+          <span class="block text-ct-blue-600 mb-3">{"Repeat"}</span>
+          if let Some(parts) = parts {
+            <div class="flex flex-wrap items-center gap-2 text-sm">
+              <select
+                class="rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-2"
+                onchange={onchange_frequency}
+              >
+                {
+                  FREQUENCIES.iter().map(|(value, label)| html! {
+                    <option value={*value} selected={parts.frequency == *value}>{*label}</option>
+                  }).collect::<Html>()
+                }
+              </select>
+
+              if !parts.frequency.is_empty() {
+                <label class="flex items-center gap-1 text-slate-300">
+                  {"every"}
+                  <input
+                    type="number"
+                    min="1"
+                    max="999"
+                    class="w-16 rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-2"
+                    value={parts.interval.to_string()}
+                    onchange={onchange_interval}
+                  />
+                </label>
+              }
+
+              if parts.frequency == "MONTHLY" {
+                <select
+                  class="rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-2"
+                  onchange={onchange_month_day}
+                >
+                  <option value="" selected={parts.month_day.is_none()}>{"on the due day"}</option>
+                  {
+                    (1..=31).map(|day| html! {
+                      <option value={day.to_string()} selected={parts.month_day == Some(day)}>
+                        {format!("on day {}", day)}
+                      </option>
+                    }).collect::<Html>()
+                  }
+                  <option value="-1" selected={parts.month_day == Some(-1)}>{"on the last day"}</option>
+                </select>
+              }
+            </div>
+
+            if parts.frequency == "WEEKLY" {
+              <div class="flex flex-wrap gap-1 mt-2">
+                {
+                  WEEKDAYS.iter().map(|(code, label)| {
+                    let is_selected = parts.weekdays.iter().any(|day| day == code);
+                    let on_toggle = {
+                      let emit_parts = emit_parts.clone();
+                      let mut toggled = parts.clone();
+                      if is_selected {
+                        toggled.weekdays.retain(|day| day != code);
+                      } else {
+                        toggled.weekdays.push(code.to_string());
+                      }
+                      Callback::from(move |_: MouseEvent| emit_parts.emit(toggled.clone()))
+                    };
+
+                    html! {
+                      <button type="button" onclick={on_toggle}
+                        class={format!("px-2 py-1 rounded-md text-xs {}",
+                          if is_selected { "bg-amber-600" } else { "bg-ct-dark-100 text-slate-400" })}
+                      >
+                        {*label}
+                      </button>
+                    }
+                  }).collect::<Html>()
+                }
+              </div>
+            }
+          }
+
+          <input
+            type="text"
+            placeholder="Custom rule, e.g. FREQ=MONTHLY;BYMONTHDAY=1,15"
+            class="w-full rounded-2xl text-ct-dark-600 focus:outline-none py-1 px-4 text-xs mt-2"
+            value={rule}
+            onchange={onchange_custom}
+          />
+
+          if props.recurrence.is_some() && props.due_date.is_none() {
+            <p class="text-xs text-amber-400 mt-2">{"Set a due date for the task to repeat."}</p>
+          }
+          {occurrences_preview}
+        </div>
+    }
+}
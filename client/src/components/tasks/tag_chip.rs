@@ -0,0 +1,34 @@
+use yew::{function_component, html, Callback, Html, MouseEvent, Properties};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct TagChipProps {
+    pub name: String,
+    pub color: String,
+    #[prop_or(true)]
+    pub active: bool,
+    #[prop_or_default]
+    pub onclick: Option<Callback<MouseEvent>>,
+}
+
+#[function_component(TagChip)]
+pub fn tag_chip(props: &TagChipProps) -> Html {
+    // The tag color is a user-defined value, so it goes in
+    // an inline style instead of a Tailwind class.
+    let style = if props.active {
+        format!("background-color: {0}; border-color: {0};", props.color)
+    } else {
+        format!("border-color: {};", props.color)
+    };
+
+    html! {
+        <span
+          {style}
+          onclick={props.onclick.clone()}
+          class={format!("inline-block px-2 rounded-full border text-xs text-cyan-50 whitespace-nowrap {}",
+            if props.onclick.is_some() { "cursor-pointer select-none" } else { "" }
+          )}
+        >
+          {&props.name}
+        </span>
+    }
+}
@@ -0,0 +1,99 @@
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
+use yew::{function_component, html, use_state, Callback, Event, Html, MouseEvent, Properties};
+use yewdux::functional::use_store;
+
+use super::tag_chip::TagChip;
+use crate::{
+    api::tag_api::api_create_tag,
+    store::{set_show_alert, set_tags_user, Store},
+};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct TagPickerProps {
+    pub selected: Vec<Uuid>,
+    pub on_toggle: Callback<Uuid>,
+}
+
+#[function_component(TagPicker)]
+pub fn tag_picker(props: &TagPickerProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let tags = store.tags_user.clone().unwrap_or_default();
+    let new_tag = use_state(String::new);
+
+    let onchange_new_tag = {
+        let new_tag = new_tag.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            new_tag.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let on_add_tag = {
+        let new_tag = new_tag.clone();
+        let on_toggle = props.on_toggle.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let name = new_tag.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+
+            let dispatch = dispatch.clone();
+            let new_tag = new_tag.clone();
+            let on_toggle = on_toggle.clone();
+            let mut tags = tags.clone();
+
+            spawn_local(async move {
+                let tag_json = serde_json::json!({ "name": name }).to_string();
+                match api_create_tag(&tag_json).await {
+                    Ok(tag) => {
+                        new_tag.set(String::new());
+                        on_toggle.emit(tag.id);
+                        tags.push(tag);
+                        set_tags_user(Some(tags), dispatch);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <div>
+          <span class="block text-ct-blue-600 mb-3">{"Tags"}</span>
+          <div class="flex flex-wrap gap-2 mb-3">
+            {
+              store.tags_user.clone().unwrap_or_default().into_iter().map(|tag| {
+                let on_toggle = props.on_toggle.clone();
+                let tag_id = tag.id;
+                html! {
+                  <TagChip
+                    key={tag.id.to_string()}
+                    name={tag.name}
+                    color={tag.color}
+                    active={props.selected.contains(&tag.id)}
+                    onclick={Callback::from(move |_: MouseEvent| on_toggle.emit(tag_id))}
+                  />
+                }
+              }).collect::<Html>()
+            }
+          </div>
+          <div class="flex gap-2">
+            <input
+              type="text"
+              placeholder="New tag"
+              class="rounded-2xl appearance-none text-ct-dark-600 focus:outline-none py-1 px-4 text-sm"
+              value={(*new_tag).clone()}
+              onchange={onchange_new_tag}
+            />
+            <button type="button" onclick={on_add_tag} class="bg-amber-600 px-3 rounded-md text-sm">
+              {"Add"}
+            </button>
+          </div>
+        </div>
+    }
+}
@@ -1,8 +1,14 @@
+use chrono::{DateTime, Local};
 use uuid::Uuid;
 use yew::{function_component, html, Html, Properties};
 use yew_router::components::Link;
 
-use crate::router::TasksRoute;
+use super::{tag_chip::TagChip, timer_button::TimerButton};
+use crate::{
+    api::types::{ChecklistProgress, Tag},
+    helpers::duration_convert,
+    router::TasksRoute,
+};
 
 #[derive(Debug, Properties, PartialEq)]
 pub struct TaskCardProps {
@@ -10,6 +16,16 @@ pub struct TaskCardProps {
     pub title: String,
     pub description: String,
     pub completed: bool,
+    #[prop_or_default]
+    pub tags: Vec<Tag>,
+    #[prop_or_default]
+    pub progress: ChecklistProgress,
+    #[prop_or_default]
+    pub due_date: Option<DateTime<Local>>,
+    #[prop_or_default]
+    pub repeats: bool,
+    #[prop_or_default]
+    pub tracked_seconds: i64,
 }
 
 #[function_component(TaskCard)]
@@ -23,6 +39,25 @@ pub fn task_card(props: &TaskCardProps) -> Html {
             <h3 title={props.description.clone()} class="text-base md:text-lg text-amber-600">
               {&props.title}
             </h3>
+            if props.progress.total > 0 {
+              <span class="text-xs text-slate-400 mr-2">
+                {format!("☑ {}/{}", props.progress.done, props.progress.total)}
+              </span>
+            }
+            if let Some(due_date) = props.due_date {
+              <span class="text-xs text-slate-400">
+                {format!("📅 {}", due_date.format("%d-%m-%Y %H:%M"))}
+                if props.repeats {
+                  {" 🔁"}
+                }
+              </span>
+            }
+            <span class="text-xs text-slate-400 ml-2">
+              if props.tracked_seconds > 0 {
+                {format!("⏱ {}", duration_convert(props.tracked_seconds, false))}
+              }
+              <TimerButton task_id={props.id} />
+            </span>
           </header>
 
           <main class="grid grid-cols-6 gap-4">
@@ -40,6 +75,16 @@ pub fn task_card(props: &TaskCardProps) -> Html {
              }
            />
           </main>
+
+          if !props.tags.is_empty() {
+            <footer class="flex flex-wrap gap-1 mt-2">
+              {
+                props.tags.iter().map(|tag| html! {
+                  <TagChip key={tag.id.to_string()} name={tag.name.clone()} color={tag.color.clone()} />
+                }).collect::<Html>()
+              }
+            </footer>
+          }
         </Link<TasksRoute>>
     }
 }
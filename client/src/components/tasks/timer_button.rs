@@ -0,0 +1,65 @@
+use uuid::Uuid;
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, Callback, Html, MouseEvent, Properties};
+use yewdux::functional::use_store;
+
+use crate::{
+    api::time_api::{api_start_timer, api_stop_timer},
+    store::{add_stopped_timer, set_running_timer, set_show_alert, Store},
+};
+
+#[derive(Debug, Properties, PartialEq)]
+pub struct TimerButtonProps {
+    pub task_id: Uuid,
+}
+
+/// Starts a timer on a task, or stops it when it is the one running.
+#[function_component(TimerButton)]
+pub fn timer_button(props: &TimerButtonProps) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let running = store
+        .running_timer
+        .as_ref()
+        .is_some_and(|timer| timer.task_id == props.task_id);
+
+    let onclick = {
+        let task_id = props.task_id;
+
+        Callback::from(move |event: MouseEvent| {
+            // The button sits inside the link of a task card.
+            event.prevent_default();
+            event.stop_propagation();
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                if running {
+                    match api_stop_timer().await {
+                        Ok(time_entry) => add_stopped_timer(time_entry, dispatch),
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                } else {
+                    match api_start_timer(&task_id.to_string()).await {
+                        Ok((time_entry, stopped)) => {
+                            if let Some(stopped) = stopped {
+                                add_stopped_timer(stopped, dispatch.clone());
+                            }
+                            set_running_timer(Some(time_entry), dispatch);
+                        }
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                }
+            });
+        })
+    };
+
+    html! {
+        <button
+          type="button"
+          title={if running { "Stop timer" } else { "Start timer" }}
+          class={format!("text-xs px-1 {}", if running { "text-red-500" } else { "text-slate-400 hover:text-emerald-500" })}
+          {onclick}
+        >
+          {if running { "■" } else { "▶" }}
+        </button>
+    }
+}
@@ -1,8 +1,12 @@
 use gloo::timers::callback::Timeout;
-use yew::{function_component, html, use_effect_with_deps, Html, Properties};
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with_deps, Callback, Html, MouseEvent, Properties};
 use yewdux::functional::use_store;
 
-use crate::store::{set_hide_alert, Store};
+use crate::{
+    api::task_api::api_restore_task,
+    store::{set_hide_alert, set_show_alert, set_tasks_user, Store},
+};
 
 #[derive(Debug, PartialEq, Properties)]
 pub struct AlertProps {
@@ -14,6 +18,7 @@ pub struct AlertProps {
 pub fn alert_component(props: &AlertProps) -> Html {
     let (store, dispatch) = use_store::<Store>();
     let show_alert = store.alert_input.show_alert;
+    let undo_task_id = store.alert_input.undo_task_id;
 
     use_effect_with_deps(
         move |(show_alert, dispatch, delayms)| {
@@ -36,6 +41,34 @@ pub fn alert_component(props: &AlertProps) -> Html {
         (show_alert, dispatch.clone(), props.delayms),
     );
 
+    /****** Bringing a deleted task back from the trash ******/
+
+    let on_undo = {
+        let store = store.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let Some(task_id) = undo_task_id else {
+                return;
+            };
+            let store = store.clone();
+            let dispatch = dispatch.clone();
+
+            set_hide_alert(dispatch.clone());
+            spawn_local(async move {
+                match api_restore_task(task_id.to_string()).await {
+                    Ok(task) => {
+                        let mut tasks = store.tasks_user.clone().unwrap_or_default();
+                        tasks.retain(|current| current.id != task.id);
+                        tasks.insert(0, task);
+                        set_tasks_user(Some(tasks), dispatch);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
     html! {
         <div id="myToast"
           class={format!("fixed top-[5.5rem] right-10 px-5 py-4 border-r-8 border-orange-500 bg-white drop-shadow-lg {}",
@@ -43,6 +76,12 @@ pub fn alert_component(props: &AlertProps) -> Html {
           <p class="text-sm text-ct-dark-600">
             <span class="mr-2 inline-block px-3 py-1 rounded-full bg-blue-500 text-white font-extrabold">{"i"}</span>
             {props.message.clone()}
+            if undo_task_id.is_some() {
+              <button type="button" onclick={on_undo}
+                class="ml-4 font-bold text-orange-500 hover:text-orange-400">
+                {"Undo"}
+              </button>
+            }
           </p>
         </div>
     }
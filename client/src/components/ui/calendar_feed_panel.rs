@@ -0,0 +1,152 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Event, Html, MouseEvent,
+};
+use yewdux::functional::use_store;
+
+use crate::{
+    api::calendar_api::{
+        api_delete_calendar_token, api_get_calendar_token, api_regenerate_calendar_token,
+        calendar_feed_url,
+    },
+    store::{set_show_alert, Store},
+};
+
+/// Address of the calendar feed of the user, to subscribe to in a calendar app,
+/// with the buttons to create a new one or to turn the feed off.
+#[function_component(CalendarFeedPanel)]
+pub fn calendar_feed_panel() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    // `None` while loading, `Some(None)` while the feed is turned off.
+    let token = use_state(|| None::<Option<String>>);
+    let completed = use_state(|| false);
+    let project = use_state(|| None::<String>);
+
+    {
+        let token = token.clone();
+        let dispatch = dispatch.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match api_get_calendar_token().await {
+                        Ok(calendar_token) => token.set(Some(calendar_token)),
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    let on_regenerate = {
+        let token = token.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let token = token.clone();
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                match api_regenerate_calendar_token().await {
+                    Ok(calendar_token) => token.set(Some(Some(calendar_token))),
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_turn_off = {
+        let token = token.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let token = token.clone();
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                match api_delete_calendar_token().await {
+                    Ok(()) => token.set(Some(None)),
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_change_completed = {
+        let completed = completed.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            completed.set(target.unchecked_into::<HtmlInputElement>().checked());
+        })
+    };
+
+    let on_change_project = {
+        let project = project.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            let value = target.unchecked_into::<HtmlSelectElement>().value();
+            project.set(Some(value).filter(|value| !value.is_empty()));
+        })
+    };
+
+    let projects = store.projects_user.clone().unwrap_or_default();
+
+    html! {
+        <div class="mt-8 border-t border-zinc-700 pt-6">
+          <p class="text-amber-600 mb-2">{"▷ Calendar feed: "}</p>
+          {
+            match (*token).clone() {
+              None => html! { <p>{"Loading..."}</p> },
+              Some(None) => html! {
+                <div class="flex flex-wrap items-center gap-2">
+                  <span class="font-light">{"Subscribe to your due tasks from a calendar app."}</span>
+                  <button type="button" onclick={on_regenerate} class="bg-amber-600 text-white px-3 py-1 rounded-md hover:bg-amber-700">
+                    {"Turn on"}
+                  </button>
+                </div>
+              },
+              Some(Some(calendar_token)) => html! {
+                <div class="flex flex-col gap-2">
+                  <input
+                    type="text"
+                    readonly=true
+                    class="w-full rounded-md bg-ct-dark-100 text-slate-300 font-mono text-xs py-1 px-2"
+                    value={calendar_feed_url(&calendar_token, *completed, (*project).as_deref())}
+                  />
+                  <div class="flex flex-wrap items-center gap-4 text-sm">
+                    <label class="flex items-center gap-2">
+                      <input type="checkbox" checked={*completed} onchange={on_change_completed} />
+                      {"Include completed tasks"}
+                    </label>
+                    <select onchange={on_change_project} class="rounded-md text-ct-dark-600 py-1 px-2">
+                      <option value="" selected={project.is_none()}>{"All my tasks"}</option>
+                      <option value="inbox">{"Inbox only"}</option>
+                      {
+                        projects.iter().map(|project| html! {
+                          <option value={project.id.to_string()}>{format!("{} {}", project.icon, project.name)}</option>
+                        }).collect::<Html>()
+                      }
+                    </select>
+                  </div>
+                  <p class="text-xs">
+                    {"Anyone with this address can see your tasks. Regenerate it to stop the old one from working."}
+                  </p>
+                  <div class="flex gap-2">
+                    <button type="button" onclick={on_regenerate} class="bg-amber-600 text-white px-3 py-1 rounded-md hover:bg-amber-700">
+                      {"Regenerate"}
+                    </button>
+                    <button type="button" onclick={on_turn_off} class="bg-zinc-700 text-white px-3 py-1 rounded-md hover:bg-red-600">
+                      {"Turn off"}
+                    </button>
+                  </div>
+                </div>
+              },
+            }
+          }
+        </div>
+    }
+}
@@ -8,8 +8,10 @@ use yewdux::functional::use_store;
 
 use crate::{
     api::user_api::api_logout_user,
+    components::ui::{live_events::LiveEvents, sync_status::SyncStatus, timer_widget::TimerWidget},
     router::{self, MainRoute},
     store::{set_auth_user, set_page_loading, set_show_alert, Store},
+    sync,
 };
 
 #[derive(Properties, PartialEq)]
@@ -38,6 +40,7 @@ pub fn header_component(props: &HeaderProps) -> Html {
                     Ok(_) => {
                         set_page_loading(false, dispatch.clone());
                         set_auth_user(None, dispatch.clone());
+                        sync::clear();
                         set_show_alert("Logged out successfully".to_string(), dispatch);
                         navigator.push(&router::MainRoute::LoginPage);
                     }
@@ -85,6 +88,14 @@ pub fn header_component(props: &HeaderProps) -> Html {
               }
             </div>
 
+            if user.is_some() {
+              <div class="flex items-center gap-2">
+                <LiveEvents />
+                <SyncStatus />
+                <TimerWidget />
+              </div>
+            }
+
             <ul class="hidden md:flex items-center gap-4">
               if user.is_some() {
                  <>
@@ -0,0 +1,135 @@
+use std::{cell::Cell, rc::Rc};
+
+use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::MessageEvent;
+use yew::{function_component, html, use_effect_with_deps, use_state, Event, Html};
+use yewdux::{dispatch::Dispatch, functional::use_store};
+
+use crate::{
+    api::{events_api::open_event_source, task_api::api_get_tasks, types::TaskData},
+    store::{apply_live_task, remove_live_task, set_show_alert, set_tasks_user, Store},
+    sync,
+};
+
+/// Events of the stream that carry a task.
+const TASK_EVENTS: [&str; 3] = ["task.created", "task.updated", "task.deleted"];
+
+/// Fetches the tasks shown again, after events may have been missed.
+fn resync_tasks(dispatch: Dispatch<Store>) {
+    let store = dispatch.get();
+    if store.tasks_user.is_none() {
+        return;
+    }
+    let project = store.tasks_project.clone();
+
+    spawn_local(async move {
+        match api_get_tasks(project).await {
+            Ok(tasks) => set_tasks_user(Some(tasks), dispatch),
+            Err(e) => set_show_alert(e, dispatch),
+        }
+    });
+}
+
+/// Keeps the tasks shown up to date with the changes made in other tabs
+/// and devices, and shows whether it is connected.
+#[function_component(LiveEvents)]
+pub fn live_events() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let connected = use_state(|| false);
+
+    {
+        let connected = connected.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                let mut listeners = Vec::new();
+                let source = match open_event_source() {
+                    Ok(source) => Some(source),
+                    Err(e) => {
+                        set_show_alert(e, dispatch.clone());
+                        None
+                    }
+                };
+
+                if let Some(source) = &source {
+                    for name in TASK_EVENTS {
+                        let dispatch = dispatch.clone();
+                        listeners.push(Closure::<dyn Fn(Event)>::new(move |event: Event| {
+                            let Some(data) = event
+                                .dyn_ref::<MessageEvent>()
+                                .and_then(|event| event.data().as_string())
+                            else {
+                                return;
+                            };
+                            let Ok(task_data) = serde_json::from_str::<TaskData>(&data) else {
+                                return;
+                            };
+
+                            if name == "task.deleted" {
+                                remove_live_task(task_data.task.id, dispatch.clone());
+                            } else {
+                                apply_live_task(task_data.task, dispatch.clone());
+                            }
+                        }));
+                    }
+
+                    // The server asks for a resync when this stream fell behind.
+                    let resync = {
+                        let dispatch = dispatch.clone();
+                        Closure::<dyn Fn(Event)>::new(move |_: Event| {
+                            resync_tasks(dispatch.clone())
+                        })
+                    };
+
+                    // Events sent while the stream was reconnecting are lost, and the
+                    // changes made offline meanwhile can be sent.
+                    let opened_before = Rc::new(Cell::new(false));
+                    let open = {
+                        let connected = connected.clone();
+                        let dispatch = dispatch.clone();
+                        Closure::<dyn Fn(Event)>::new(move |_: Event| {
+                            if opened_before.replace(true) {
+                                spawn_local(sync::synchronize(dispatch.clone()));
+                            }
+                            connected.set(true);
+                        })
+                    };
+                    let error = {
+                        let connected = connected.clone();
+                        Closure::<dyn Fn(Event)>::new(move |_: Event| connected.set(false))
+                    };
+
+                    let names = TASK_EVENTS.into_iter().chain(["resync", "open", "error"]);
+                    listeners.extend([resync, open, error]);
+                    for (name, listener) in names.zip(&listeners) {
+                        source
+                            .add_event_listener_with_callback(
+                                name,
+                                listener.as_ref().unchecked_ref(),
+                            )
+                            .unwrap();
+                    }
+                }
+
+                move || {
+                    if let Some(source) = source {
+                        source.close();
+                    }
+                    drop(listeners)
+                }
+            },
+            (),
+        );
+    }
+
+    let (color, title) = if *connected {
+        ("text-green-500", "Live updates on")
+    } else {
+        ("text-gray-400", "Live updates reconnecting...")
+    };
+
+    html! {
+      <span class={format!("text-xs {}", color)} title={title}>{"●"}</span>
+    }
+}
@@ -1,7 +1,11 @@
 pub mod alert;
+pub mod calendar_feed_panel;
 pub mod form_input;
 pub mod header_component;
+pub mod live_events;
 pub mod loading_button;
 pub mod sidebar_component;
 pub mod spinner;
+pub mod sync_status;
 pub mod textarea_input;
+pub mod timer_widget;
@@ -1,6 +1,9 @@
+use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
+use web_sys::HtmlInputElement;
 use yew::{
-    classes, function_component, html, Callback, Html, MouseEvent, Properties, UseStateHandle,
+    classes, function_component, html, use_state, Callback, Event, Html, MouseEvent, Properties,
+    UseStateHandle,
 };
 use yew_router::{
     components::Link,
@@ -9,9 +12,9 @@ use yew_router::{
 use yewdux::functional::use_store;
 
 use crate::{
-    api::user_api::api_logout_user,
+    api::{project_api::api_create_project, user_api::api_logout_user},
     router::{self, MainRoute},
-    store::{set_auth_user, set_page_loading, set_show_alert, Store},
+    store::{set_auth_user, set_page_loading, set_projects_user, set_show_alert, Store},
 };
 
 #[derive(Properties, PartialEq)]
@@ -23,8 +26,10 @@ pub struct SidebarProps {
 pub fn sidebar(props: &SidebarProps) -> Html {
     let (store, dispatch) = use_store::<Store>();
     let user = store.auth_user.clone();
+    let projects = store.projects_user.clone().unwrap_or_default();
     let navigator = use_navigator().unwrap();
     let location = use_location().unwrap().path().to_string();
+    let new_project = use_state(String::new);
 
     let handle_logout = {
         let store_dispatch = dispatch.clone();
@@ -52,6 +57,44 @@ pub fn sidebar(props: &SidebarProps) -> Html {
         })
     };
 
+    let onchange_new_project = {
+        let new_project = new_project.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            new_project.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let on_add_project = {
+        let store_dispatch = dispatch.clone();
+        let new_project = new_project.clone();
+        let projects = projects.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let name = new_project.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+
+            let dispatch = store_dispatch.clone();
+            let new_project = new_project.clone();
+            let mut projects = projects.clone();
+
+            spawn_local(async move {
+                let project_json = serde_json::json!({ "name": name }).to_string();
+                match api_create_project(&project_json).await {
+                    Ok(project) => {
+                        new_project.set(String::new());
+                        projects.push(project);
+                        set_projects_user(Some(projects), dispatch);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
     let close_on_pressed = {
         let activesidebar = props.activesidebar.clone();
 
@@ -177,6 +220,72 @@ pub fn sidebar(props: &SidebarProps) -> Html {
                     </Link<MainRoute>>
                   </div>
 
+                  <div class="flex flex-col gap-2 pl-6">
+                    {
+                      projects.into_iter().map(|project| {
+                        let project_path = format!("/projects/{}", project.id);
+                        html! {
+                          <Link<MainRoute> key={project.id.to_string()} to={MainRoute::ProjectPage { id: project.id.to_string() }}>
+                            <span
+                              class={classes!(if location == project_path { "border-b-2 border-amber-600" } else { "" }, link_style)}
+                              onclick={close_on_pressed.clone()}
+                            >
+                              <span class="mr-2">{project.icon}</span>
+                              <span style={format!("color: {};", project.color)}>{project.name}</span>
+                            </span>
+                          </Link<MainRoute>>
+                        }
+                      }).collect::<Html>()
+                    }
+                    <div class="flex gap-2">
+                      <input
+                        type="text"
+                        placeholder="New project"
+                        class="w-full rounded-2xl appearance-none text-ct-dark-600 focus:outline-none py-1 px-3 text-xs"
+                        value={(*new_project).clone()}
+                        onchange={onchange_new_project}
+                      />
+                      <button type="button" onclick={on_add_project} class="bg-amber-600 px-2 rounded-md text-xs">
+                        {"+"}
+                      </button>
+                    </div>
+                  </div>
+
+                  <div>
+                    <Link<MainRoute> to={MainRoute::SharedPage}>
+                      <span
+                        class={classes!(if &location == "/shared" { "border-b-2 border-amber-600" } else { "" }, link_style)}
+                        onclick={close_on_pressed.clone()}
+                      >
+                        <svg fill="currentColor" class="w-4 inline mr-2" viewBox="0 0 16 16">
+                          <path d="M7 14s-1 0-1-1 1-4 5-4 5 3 5 4-1 1-1 1H7zm4-6a3 3 0 1 0 0-6 3 3 0 0 0 0 6z" />
+                          <path fill-rule="evenodd" d="M5.216 14A2.238 2.238 0 0 1 5 13c0-1.355.68-2.75 \
+                          1.936-3.72A6.325 6.325 0 0 0 5 9c-4 0-5 3-5 4s1 1 1 1h4.216z" />
+                          <path d="M4.5 8a2.5 2.5 0 1 0 0-5 2.5 2.5 0 0 0 0 5z" />
+                        </svg>
+                        {"Shared with me"}
+                      </span>
+                    </Link<MainRoute>>
+                  </div>
+
+                  <div>
+                    <Link<MainRoute> to={MainRoute::TrashPage}>
+                      <span
+                        class={classes!(if &location == "/trash" { "border-b-2 border-amber-600" } else { "" }, link_style)}
+                        onclick={close_on_pressed.clone()}
+                      >
+                        <svg fill="currentColor" class="w-4 inline mr-2" viewBox="0 0 16 16">
+                          <path d="M5.5 5.5A.5.5 0 0 1 6 6v6a.5.5 0 0 1-1 0V6a.5.5 0 0 1 .5-.5zm2.5 0a.5.5 0 0 1 \
+                          .5.5v6a.5.5 0 0 1-1 0V6a.5.5 0 0 1 .5-.5zm3 .5a.5.5 0 0 0-1 0v6a.5.5 0 0 0 1 0V6z" />
+                          <path fill-rule="evenodd" d="M14.5 3a1 1 0 0 1-1 1H13v9a2 2 0 0 1-2 2H5a2 2 0 0 1-2-2V4h-.5a1 \
+                          1 0 0 1-1-1V2a1 1 0 0 1 1-1H6a1 1 0 0 1 1-1h2a1 1 0 0 1 1 1h3.5a1 1 0 0 1 1 1v1zM4.118 \
+                          4 4 4.059V13a1 1 0 0 0 1 1h6a1 1 0 0 0 1-1V4.059L11.882 4H4.118zM2.5 3V2h11v1h-11z" />
+                        </svg>
+                        {"Trash"}
+                      </span>
+                    </Link<MainRoute>>
+                  </div>
+
                   <div>
                     <Link<MainRoute> to={MainRoute::AddTask}>
                       <span
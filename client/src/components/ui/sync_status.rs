@@ -0,0 +1,111 @@
+use gloo::timers::callback::Interval;
+use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with_deps, Callback, Html, MouseEvent};
+use yewdux::functional::use_store;
+
+use crate::{
+    store::{set_offline, set_sync_pending, Store},
+    sync,
+};
+
+/// How often the changes made offline are tried to be sent again, in milliseconds.
+const RETRY_INTERVAL: u32 = 30_000;
+
+/// Shows whether the server can be reached and how many changes made offline
+/// wait to be sent, and sends them as soon as it is back.
+#[function_component(SyncStatus)]
+pub fn sync_status() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let status = store.sync.clone();
+
+    {
+        let dispatch = dispatch.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                // Changes left from a previous visit are sent right away.
+                let pending = sync::pending_count();
+                set_sync_pending(pending, dispatch.clone());
+                if pending > 0 {
+                    spawn_local(sync::synchronize(dispatch.clone()));
+                }
+
+                let online = {
+                    let dispatch = dispatch.clone();
+                    Closure::<dyn Fn()>::new(move || {
+                        spawn_local(sync::synchronize(dispatch.clone()))
+                    })
+                };
+                let offline = {
+                    let dispatch = dispatch.clone();
+                    Closure::<dyn Fn()>::new(move || set_offline(true, dispatch.clone()))
+                };
+
+                let window = web_sys::window();
+                if let Some(window) = &window {
+                    for (name, listener) in [("online", &online), ("offline", &offline)] {
+                        window
+                            .add_event_listener_with_callback(
+                                name,
+                                listener.as_ref().unchecked_ref(),
+                            )
+                            .unwrap();
+                    }
+                }
+
+                // The browser does not always notice when the server comes back.
+                let retry = Interval::new(RETRY_INTERVAL, move || {
+                    let status = dispatch.get().sync.clone();
+                    if status.offline || status.pending > 0 {
+                        spawn_local(sync::synchronize(dispatch.clone()));
+                    }
+                });
+
+                move || {
+                    if let Some(window) = window {
+                        for (name, listener) in [("online", &online), ("offline", &offline)] {
+                            let _ = window.remove_event_listener_with_callback(
+                                name,
+                                listener.as_ref().unchecked_ref(),
+                            );
+                        }
+                    }
+                    drop(retry)
+                }
+            },
+            (),
+        );
+    }
+
+    let on_click = {
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |_: MouseEvent| spawn_local(sync::synchronize(dispatch.clone())))
+    };
+
+    let label = if status.syncing {
+        "⟳ Syncing...".to_string()
+    } else if status.offline {
+        format!("Offline · {} pending", status.pending)
+    } else if status.pending > 0 {
+        format!("{} pending", status.pending)
+    } else {
+        return html! {};
+    };
+    let color = if status.offline {
+        "text-amber-500"
+    } else {
+        "text-gray-300"
+    };
+
+    html! {
+      <button
+        class={format!("text-xs whitespace-nowrap hover:text-amber-600 {}", color)}
+        title="Changes made offline are sent when the server can be reached"
+        onclick={on_click}
+      >
+        {label}
+      </button>
+    }
+}
@@ -0,0 +1,88 @@
+use chrono::Local;
+use gloo::timers::callback::Interval;
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with_deps, use_state, Callback, Html, MouseEvent};
+use yew_router::components::Link;
+use yewdux::functional::use_store;
+
+use crate::{
+    api::time_api::{api_get_timer, api_stop_timer},
+    helpers::duration_convert,
+    router::TasksRoute,
+    store::{add_stopped_timer, set_running_timer, set_show_alert, Store},
+};
+
+/// Shows the running timer of the user, ticking every second, and lets it be stopped.
+#[function_component(TimerWidget)]
+pub fn timer_widget() -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let now = use_state(Local::now);
+    let timer = store.running_timer.clone();
+
+    {
+        let dispatch = dispatch.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match api_get_timer().await {
+                        Ok(time_entry) => set_running_timer(time_entry, dispatch),
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    {
+        let now = now.clone();
+
+        use_effect_with_deps(
+            move |timer_id| {
+                let interval =
+                    timer_id.map(|_| Interval::new(1_000, move || now.set(Local::now())));
+
+                move || drop(interval)
+            },
+            timer.as_ref().map(|timer| timer.id),
+        );
+    }
+
+    let on_stop = {
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                match api_stop_timer().await {
+                    Ok(time_entry) => add_stopped_timer(time_entry, dispatch),
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let Some(timer) = timer else {
+        return html! {};
+    };
+    let elapsed = (*now - timer.started_at).num_seconds().max(0);
+    let title = timer.task.map(|task| task.title).unwrap_or_default();
+
+    html! {
+        <div class="flex items-center gap-2 bg-ct-dark-100 rounded-full px-3 py-1 text-sm">
+          <span class="text-red-500 animate-pulse">{"●"}</span>
+          <Link<TasksRoute>
+            to={TasksRoute::TaskEditPage { id: timer.task_id.to_string() }}
+            classes="hidden md:inline max-w-[12rem] truncate hover:text-amber-600"
+          >
+            {title}
+          </Link<TasksRoute>>
+          <span class="font-mono">{duration_convert(elapsed, true)}</span>
+          <button type="button" title="Stop timer" class="hover:text-red-500" onclick={on_stop}>
+            {"■"}
+          </button>
+        </div>
+    }
+}
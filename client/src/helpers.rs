@@ -17,6 +17,18 @@ pub fn date_convert(date: DateTime<Local>) -> String {
     .join(" ")
 }
 
+/// Formats a duration as hours and minutes, e.g. `2h 05m`,
+/// or with seconds as well (`0:12:34`) for a running timer.
+pub fn duration_convert(seconds: i64, with_seconds: bool) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds % 3600 / 60, seconds % 60);
+
+    if with_seconds {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}h {:02}m", hours, minutes)
+    }
+}
+
 /*
  * DEFINITIVE SOLUTION TO THE PROBLEM OF UTC TIME:
  * https://stackoverflow.com/questions/41158999/getting-the-current-time-in-specified-timezone
@@ -16,9 +16,9 @@ use yewdux::functional::use_store;
 use crate::components::ui::{header_component::Header, sidebar_component::Sidebar};
 
 use crate::{
-    api::user_api::api_user_info,
+    api::{project_api::api_get_projects, user_api::api_user_info},
     router,
-    store::{set_auth_user, set_page_loading, set_show_alert, Store},
+    store::{set_auth_user, set_page_loading, set_projects_user, set_show_alert, Store},
 };
 
 #[derive(Debug, Properties, PartialEq)]
@@ -48,7 +48,13 @@ pub fn layout(LayoutProp { children }: &LayoutProp) -> Html {
                 match response {
                     Ok(user) => {
                         set_page_loading(false, dispatch.clone());
-                        set_auth_user(Some(user), dispatch);
+                        set_auth_user(Some(user), dispatch.clone());
+
+                        // The sidebar lists the projects as navigation.
+                        match api_get_projects().await {
+                            Ok(projects) => set_projects_user(Some(projects), dispatch),
+                            Err(e) => set_show_alert(e.to_string(), dispatch),
+                        }
                     }
                     Err(e) => {
                         // log(&location);
@@ -8,6 +8,7 @@ mod layout;
 mod pages;
 mod router;
 mod store;
+mod sync;
 
 fn main() {
     yew::Renderer::<app::App>::new().render();
@@ -0,0 +1,322 @@
+use uuid::Uuid;
+use wasm_bindgen::JsCast;
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, Event, Html, MouseEvent,
+    Properties,
+};
+use yew_router::components::Link;
+use yewdux::functional::use_store;
+
+use crate::{
+    api::{
+        status_api::{api_create_status, api_delete_status, api_get_statuses},
+        task_api::UpdateTaskError,
+        types::{Status, Task},
+    },
+    components::tasks::kanban_column::KanbanColumn,
+    layout::Layout,
+    router::MainRoute,
+    store::{set_show_alert, set_tasks_project, set_tasks_user, Store},
+    sync::{self, Saved},
+};
+
+/// The column a task is shown in: the one it was moved to, or else
+/// the first one that matches whether it is completed.
+fn column_of(task: &Task, statuses: &[Status]) -> Option<Uuid> {
+    task.status_id
+        .filter(|status_id| statuses.iter().any(|status| &status.id == status_id))
+        .or_else(|| {
+            statuses
+                .iter()
+                .find(|status| status.done == task.completed)
+                .map(|status| status.id)
+        })
+        .or_else(|| statuses.first().map(|status| status.id))
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct BoardPageProp {
+    pub project_id: Option<String>,
+}
+
+/// Kanban view of the tasks of a project, or of the inbox when there is no project.
+#[function_component(BoardPage)]
+pub fn board_page(BoardPageProp { project_id }: &BoardPageProp) -> Html {
+    let (store, dispatch) = use_store::<Store>();
+    let statuses = use_state(|| None::<Vec<Status>>);
+    let dragged = use_state(|| None::<Uuid>);
+    let new_status = use_state(String::new);
+    let new_status_done = use_state(|| false);
+    let project = project_id.as_ref().and_then(|project_id| {
+        store
+            .projects_user
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .chain(store.shared_projects.clone().unwrap_or_default())
+            .find(|project| &project.id.to_string() == project_id)
+    });
+
+    {
+        let statuses = statuses.clone();
+        let dispatch = dispatch.clone();
+
+        use_effect_with_deps(
+            move |project_id| {
+                let project_id = project_id.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    match api_get_statuses(project_id.clone()).await {
+                        Ok(board_statuses) => statuses.set(Some(board_statuses)),
+                        Err(e) => set_show_alert(e, dispatch.clone()),
+                    }
+
+                    // The personal board holds the tasks that are not in any project.
+                    let tasks_filter = project_id.or_else(|| Some("inbox".to_string()));
+                    set_tasks_project(tasks_filter.clone(), dispatch.clone());
+                    match sync::fetch_tasks(tasks_filter, dispatch.clone()).await {
+                        Ok(tasks) => set_tasks_user(Some(tasks), dispatch),
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                });
+            },
+            project_id.clone(),
+        );
+    }
+
+    /****** Moving cards between columns ******/
+
+    let on_drag_start = {
+        let dragged = dragged.clone();
+
+        Callback::from(move |task_id: Uuid| dragged.set(Some(task_id)))
+    };
+
+    let on_drop = {
+        let dragged = dragged.clone();
+        let statuses = statuses.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |status_id: Uuid| {
+            let Some(task_id) = *dragged else {
+                return;
+            };
+            dragged.set(None);
+
+            let board_statuses = (*statuses).clone().unwrap_or_default();
+            let Some(status) = board_statuses.iter().find(|status| status.id == status_id) else {
+                return;
+            };
+            let previous_tasks = dispatch.get().tasks_user.clone();
+            let Some(task) = previous_tasks
+                .iter()
+                .flatten()
+                .find(|task| task.id == task_id)
+            else {
+                return;
+            };
+            if column_of(task, &board_statuses) == Some(status_id) {
+                return;
+            }
+
+            // The card moves right away and goes back if the change is not saved.
+            let done = status.done;
+            dispatch.reduce_mut(move |store| {
+                if let Some(task) = store
+                    .tasks_user
+                    .iter_mut()
+                    .flatten()
+                    .find(|task| task.id == task_id)
+                {
+                    task.status_id = Some(status_id);
+                    task.completed = done;
+                }
+            });
+
+            let dispatch = dispatch.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let task_data = serde_json::json!({ "status_id": status_id }).to_string();
+                match sync::update_task(task_id, &task_data, None, dispatch.clone()).await {
+                    // Kept as moved until the change made offline is sent.
+                    Ok(Saved::Queued) => {}
+                    Ok(Saved::Sent(updated_task)) => dispatch.reduce_mut(move |store| {
+                        if let Some(task) = store
+                            .tasks_user
+                            .iter_mut()
+                            .flatten()
+                            .find(|task| task.id == updated_task.id)
+                        {
+                            *task = updated_task;
+                        }
+                    }),
+                    Err(e) => {
+                        set_tasks_user(previous_tasks, dispatch.clone());
+                        let message = match e {
+                            UpdateTaskError::Conflict => {
+                                "The task was changed by someone else".to_string()
+                            }
+                            UpdateTaskError::Other(message) => message,
+                        };
+                        set_show_alert(message, dispatch);
+                    }
+                }
+            });
+        })
+    };
+
+    /****** Configuring the columns ******/
+
+    let onchange_new_status = {
+        let new_status = new_status.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            new_status.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let onchange_new_status_done = {
+        let new_status_done = new_status_done.clone();
+
+        Callback::from(move |event: Event| {
+            let target = event.target().unwrap();
+            new_status_done.set(target.unchecked_into::<HtmlInputElement>().checked());
+        })
+    };
+
+    let on_add_status = {
+        let statuses = statuses.clone();
+        let new_status = new_status.clone();
+        let new_status_done = new_status_done.clone();
+        let dispatch = dispatch.clone();
+        let project_id = project_id.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let name = new_status.trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+
+            let statuses = statuses.clone();
+            let new_status = new_status.clone();
+            let dispatch = dispatch.clone();
+            let status_data = serde_json::json!({
+                "name": name,
+                "done": *new_status_done,
+                "project_id": project_id
+            })
+            .to_string();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_create_status(&status_data).await {
+                    Ok(status) => {
+                        let mut board_statuses = (*statuses).clone().unwrap_or_default();
+                        board_statuses.push(status);
+                        statuses.set(Some(board_statuses));
+                        new_status.set(String::new());
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_delete_status = {
+        let statuses = statuses.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |status_id: Uuid| {
+            let statuses = statuses.clone();
+            let dispatch = dispatch.clone();
+
+            wasm_bindgen_futures::spawn_local(async move {
+                match api_delete_status(&status_id.to_string()).await {
+                    Ok(()) => {
+                        let mut board_statuses = (*statuses).clone().unwrap_or_default();
+                        board_statuses.retain(|status| status.id != status_id);
+                        statuses.set(Some(board_statuses));
+
+                        // Its tasks fall back to the first column that matches them.
+                        dispatch.reduce_mut(move |store| {
+                            store
+                                .tasks_user
+                                .iter_mut()
+                                .flatten()
+                                .filter(|task| task.status_id == Some(status_id))
+                                .for_each(|task| task.status_id = None);
+                        });
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let list_route = match project_id {
+        Some(id) => MainRoute::ProjectPage { id: id.clone() },
+        None => MainRoute::TasksRoot,
+    };
+    let tasks = store.tasks_user.clone();
+
+    html! {
+        <Layout>
+          <section class="bg-ct-blue-600 px-8 py-12 rounded-2xl">
+            <div class="flex items-center justify-between my-8">
+              if let Some(project) = project {
+                <h1 class="text-xl md:text-3xl font-bold" style={format!("color: {};", project.color)}>
+                  {format!("{} {}", project.icon, project.name)}
+                </h1>
+              } else {
+                <h1 class="text-xl md:text-3xl font-bold text-amber-600">{"Inbox board"}</h1>
+              }
+              <Link<MainRoute> to={list_route} classes="text-sm text-slate-300 hover:text-amber-500">
+                {"List view"}
+              </Link<MainRoute>>
+            </div>
+
+            if let (Some(board_statuses), Some(board_tasks)) = ((*statuses).clone(), tasks) {
+              <div class="flex gap-4 overflow-x-auto pb-4">
+                {
+                  board_statuses.iter().map(|status| {
+                    let column_tasks = board_tasks
+                      .iter()
+                      .filter(|task| column_of(task, &board_statuses) == Some(status.id))
+                      .cloned()
+                      .collect::<Vec<_>>();
+
+                    html! {
+                      <KanbanColumn
+                        key={status.id.to_string()}
+                        status={status.clone()}
+                        tasks={column_tasks}
+                        on_drag_start={on_drag_start.clone()}
+                        on_drop={on_drop.clone()}
+                        on_delete={on_delete_status.clone()}
+                      />
+                    }
+                  }).collect::<Html>()
+                }
+                <div class="flex flex-col shrink-0 w-60 gap-2 text-sm">
+                  <input
+                    type="text"
+                    placeholder="New column"
+                    class="rounded-2xl appearance-none text-ct-dark-600 focus:outline-none py-1 px-4"
+                    value={(*new_status).clone()}
+                    onchange={onchange_new_status}
+                  />
+                  <label class="flex items-center gap-2 text-slate-300">
+                    <input type="checkbox" checked={*new_status_done} onchange={onchange_new_status_done} />
+                    {"Tasks here are completed"}
+                  </label>
+                  <button type="button" onclick={on_add_status} class="bg-amber-600 px-3 py-1 rounded-md">
+                    {"Add column"}
+                  </button>
+                </div>
+              </div>
+            } else {
+              <p class="mb-4">{"Loading..."}</p>
+            }
+          </section>
+        </Layout>
+    }
+}
@@ -1,7 +1,10 @@
+pub mod board_page;
 pub mod home_page;
 pub mod login_page;
 pub mod not_found;
 pub mod profile_page;
 pub mod register_page;
+pub mod shared_page;
 pub mod task_form_page;
 pub mod tasks_page;
+pub mod trash_page;
@@ -4,6 +4,8 @@ use yewdux::functional::use_store;
 
 use crate::{
     // api::user_api::api_user_info,
+    api::task_api::export_tasks_url,
+    components::ui::calendar_feed_panel::CalendarFeedPanel,
     helpers::date_convert,
     layout::Layout,
     // router,
@@ -59,6 +61,26 @@ pub fn profile_page() -> Html {
                         <span class="text-amber-600">{"▷ Created At: "}</span><span class="font-light">{date_convert(user.created_at)}</span>
                       </div>
                   </div>
+                  <div class="mt-8 border-t border-zinc-700 pt-6">
+                    <p class="text-amber-600 mb-2">{"▷ Export your tasks: "}</p>
+                    <div class="flex flex-wrap gap-2">
+                      {
+                        [("json", "JSON"), ("csv", "CSV"), ("md", "Markdown"), ("ics", "iCalendar")]
+                          .into_iter()
+                          .map(|(format, label)| html! {
+                            <a
+                              href={export_tasks_url(format)}
+                              download=""
+                              class="bg-amber-600 text-white px-3 py-1 rounded-md hover:bg-amber-700"
+                            >
+                              {label}
+                            </a>
+                          })
+                          .collect::<Html>()
+                      }
+                    </div>
+                  </div>
+                  <CalendarFeedPanel />
                 } else {
                   <p class="mb-4">{"Loading..."}</p>
                 }
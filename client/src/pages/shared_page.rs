@@ -0,0 +1,164 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with_deps, use_state, Callback, Html, MouseEvent};
+use yew_router::components::Link;
+use yewdux::functional::use_store;
+
+use crate::{
+    api::{
+        share_api::{api_delete_share, api_get_shared_with_me},
+        types::SharedWithMeData,
+    },
+    components::tasks::task_card::TaskCard,
+    layout::Layout,
+    router::MainRoute,
+    store::{set_shared_projects, set_show_alert, Store},
+};
+
+#[function_component(SharedPage)]
+pub fn shared_page() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let shared = use_state(|| None::<SharedWithMeData>);
+
+    /****** Getting what is shared with the user when the component is mounted ******/
+
+    {
+        let shared = shared.clone();
+        let dispatch = dispatch.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match api_get_shared_with_me().await {
+                        Ok(data) => {
+                            set_shared_projects(Some(data.projects.clone()), dispatch);
+                            shared.set(Some(data));
+                        }
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    /****** Leaving a shared task or project ******/
+
+    let on_leave = {
+        let shared = shared.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |share_id: String| {
+            let shared = shared.clone();
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                match api_delete_share(&share_id).await {
+                    Ok(_) => {
+                        let mut data = (*shared).clone().unwrap_or_default();
+                        if let Some(share) = data
+                            .shares
+                            .iter()
+                            .find(|share| share.id.to_string() == share_id)
+                            .cloned()
+                        {
+                            data.tasks.retain(|task| Some(task.id) != share.task_id);
+                            data.projects
+                                .retain(|project| Some(project.id) != share.project_id);
+                        }
+                        data.shares.retain(|share| share.id.to_string() != share_id);
+                        set_shared_projects(Some(data.projects.clone()), dispatch);
+                        shared.set(Some(data));
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let leave_button = |share_id: String| {
+        let on_leave = on_leave.clone();
+        html! {
+          <button type="button" class="text-xs text-red-400 hover:text-red-300"
+            onclick={Callback::from(move |_: MouseEvent| on_leave.emit(share_id.clone()))}
+          >
+            {"Leave"}
+          </button>
+        }
+    };
+
+    html! {
+        <Layout>
+          <section class="bg-ct-blue-600 px-8 py-12 rounded-2xl">
+            <div class="flex flex-col justify-center mx-auto">
+              <h1 class="text-xl md:text-3xl font-bold text-center my-8 text-amber-600">
+                {"Shared with me"}
+              </h1>
+
+              if let Some(data) = (*shared).clone() {
+                if data.shares.is_empty() {
+                  <p class="text-center text-slate-400">{"Nothing has been shared with you yet."}</p>
+                } else {
+                  <>
+                    if !data.projects.is_empty() {
+                      <h2 class="text-lg text-sky-300 mb-3">{"Projects"}</h2>
+                      <ul class="bg-zinc-800 p-6 rounded-xl flex flex-col gap-3 mb-6">
+                        {
+                          data.projects.iter().map(|project| {
+                            let share = data.shares.iter().find(|share| share.project_id == Some(project.id));
+                            html! {
+                              <li key={project.id.to_string()} class="flex items-center gap-3">
+                                <Link<MainRoute> to={MainRoute::ProjectPage { id: project.id.to_string() }}>
+                                  <span class="mr-2">{&project.icon}</span>
+                                  <span style={format!("color: {};", project.color)}>{&project.name}</span>
+                                </Link<MainRoute>>
+                                if let Some(share) = share {
+                                  <span class="text-xs text-slate-400">{&share.role}</span>
+                                  {leave_button(share.id.to_string())}
+                                }
+                              </li>
+                            }
+                          }).collect::<Html>()
+                        }
+                      </ul>
+                    }
+
+                    if !data.tasks.is_empty() {
+                      <h2 class="text-lg text-sky-300 mb-3">{"Tasks"}</h2>
+                      <ul class="bg-zinc-800 p-8 rounded-xl grid md:grid-cols-2 lg:grid-cols-3 gap-2">
+                        {
+                          data.tasks.iter().map(|task| {
+                            let share = data.shares.iter().find(|share| share.task_id == Some(task.id));
+                            html! {
+                              <li key={task.id.to_string()}>
+                                <TaskCard
+                                  id={task.id}
+                                  title={task.title.clone()}
+                                  description={task.description.clone()}
+                                  completed={task.completed}
+                                  progress={task.progress.clone()}
+                                  due_date={task.due_date}
+                                  repeats={task.recurrence.is_some()}
+                                  tracked_seconds={task.tracked_seconds}
+                                />
+                                if let Some(share) = share {
+                                  <div class="flex justify-between mt-1 px-2">
+                                    <span class="text-xs text-slate-400">{&share.role}</span>
+                                    {leave_button(share.id.to_string())}
+                                  </div>
+                                }
+                              </li>
+                            }
+                          }).collect::<Html>()
+                        }
+                      </ul>
+                    }
+                  </>
+                }
+              } else {
+                <p class="text-center">{"Loading..."}</p>
+              }
+            </div>
+          </section>
+        </Layout>
+    }
+}
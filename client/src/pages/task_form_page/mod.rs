@@ -6,11 +6,13 @@ pub mod task_form_page;
 
 use std::{cell::RefCell, ops::Deref, rc::Rc};
 
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use validator::{Validate, ValidationErrors};
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::spawn_local;
-use web_sys::HtmlInputElement;
+use web_sys::{HtmlInputElement, HtmlSelectElement};
 use yew::{Callback, Event, MouseEvent, NodeRef, SubmitEvent, UseStateHandle};
 use yew_router::{
     history::{BrowserHistory, History},
@@ -19,11 +21,18 @@ use yew_router::{
 use yewdux::prelude::Dispatch;
 
 use crate::{
-    api::task_api::{api_create_task, api_delete_task, api_update_task},
+    api::{
+        task_api::{api_get_single_task, api_update_task, UpdateTaskError},
+        types::Task,
+    },
     router,
-    store::{set_page_loading, set_show_alert, Store},
+    store::{set_page_loading, set_show_alert, set_show_undo_alert, Store},
+    sync::{self, Saved},
 };
 
+/// Told when a change is kept to be sent once the server can be reached.
+const SAVED_OFFLINE: &str = "You are offline: the change will be saved when you are back online";
+
 // use wasm_bindgen::prelude::*;
 //
 // #[wasm_bindgen]
@@ -51,6 +60,10 @@ pub struct CreateTaskSchema {
         )
     )]
     description: String,
+    tag_ids: Vec<Uuid>,
+    project_id: Option<Uuid>,
+    due_date: Option<DateTime<Local>>,
+    recurrence: Option<String>,
 }
 
 fn get_input_callback(
@@ -68,6 +81,74 @@ fn get_input_callback(
     })
 }
 
+// Selecting an already selected tag deselects it.
+fn toggle_tag(tag_ids: &mut Vec<Uuid>, tag_id: Uuid) {
+    if tag_ids.contains(&tag_id) {
+        tag_ids.retain(|id| id != &tag_id);
+    } else {
+        tag_ids.push(tag_id);
+    }
+}
+
+fn get_toggle_tag_callback(cloned_form: UseStateHandle<CreateTaskSchema>) -> Callback<Uuid> {
+    Callback::from(move |tag_id| {
+        let mut data = cloned_form.deref().clone();
+        toggle_tag(&mut data.tag_ids, tag_id);
+        cloned_form.set(data);
+    })
+}
+
+// An empty value is the "Inbox" option, i.e. no project.
+fn get_select_project_callback(cloned_handle_project: Callback<Option<Uuid>>) -> Callback<Event> {
+    Callback::from(move |event: Event| {
+        let target = event.target().unwrap();
+        let value = target.unchecked_into::<HtmlSelectElement>().value();
+        cloned_handle_project.emit(Uuid::parse_str(&value).ok());
+    })
+}
+
+fn get_project_callback(cloned_form: UseStateHandle<CreateTaskSchema>) -> Callback<Option<Uuid>> {
+    Callback::from(move |project_id| {
+        let mut data = cloned_form.deref().clone();
+        data.project_id = project_id;
+        cloned_form.set(data);
+    })
+}
+
+// The datetime-local input gives a local time without offset; an empty value clears the date.
+fn get_select_due_date_callback(
+    cloned_handle_due_date: Callback<Option<DateTime<Local>>>,
+) -> Callback<Event> {
+    Callback::from(move |event: Event| {
+        let target = event.target().unwrap();
+        let value = target.unchecked_into::<HtmlInputElement>().value();
+        let due_date = NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M")
+            .ok()
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest());
+        cloned_handle_due_date.emit(due_date);
+    })
+}
+
+fn get_due_date_callback(
+    cloned_form: UseStateHandle<CreateTaskSchema>,
+) -> Callback<Option<DateTime<Local>>> {
+    Callback::from(move |due_date| {
+        let mut data = cloned_form.deref().clone();
+        data.due_date = due_date;
+        cloned_form.set(data);
+    })
+}
+
+fn get_recurrence_callback(
+    cloned_form: UseStateHandle<CreateTaskSchema>,
+) -> Callback<Option<String>> {
+    Callback::from(move |recurrence| {
+        let mut data = cloned_form.deref().clone();
+        data.recurrence = recurrence;
+        cloned_form.set(data);
+    })
+}
+
 fn get_validate_input_callback(
     cloned_form: UseStateHandle<CreateTaskSchema>,
     cloned_validation_errors: UseStateHandle<Rc<RefCell<ValidationErrors>>>,
@@ -142,12 +223,17 @@ pub fn get_submit_callback(
                     description_input.set_value("");
 
                     let form_json = serde_json::to_string(&form_data).unwrap();
-                    let res = api_create_task(&form_json).await;
+                    let res = sync::create_task(&form_json, dispatch.clone()).await;
                     match res {
-                        Ok(_) => {
+                        Ok(Saved::Sent(_)) => {
                             set_page_loading(false, dispatch);
                             navigator.push(&router::MainRoute::TasksRoot);
                         }
+                        Ok(Saved::Queued) => {
+                            set_page_loading(false, dispatch.clone());
+                            set_show_alert(SAVED_OFFLINE.to_string(), dispatch);
+                            navigator.push(&router::MainRoute::TasksRoot);
+                        }
                         Err(e) => {
                             set_page_loading(false, dispatch.clone());
                             set_show_alert(e.to_string(), dispatch);
@@ -169,6 +255,26 @@ struct UpdateTaskSchema {
     title: String,
     description: String,
     completed: bool,
+    tag_ids: Vec<Uuid>,
+    project_id: Option<Uuid>,
+    auto_complete: bool,
+    due_date: Option<DateTime<Local>>,
+    recurrence: Option<String>,
+}
+
+impl From<Task> for UpdateTaskSchema {
+    fn from(task: Task) -> Self {
+        UpdateTaskSchema {
+            title: task.title,
+            description: task.description,
+            completed: task.completed,
+            tag_ids: task.tag_ids,
+            project_id: task.project_id,
+            auto_complete: task.auto_complete,
+            due_date: task.due_date,
+            recurrence: task.recurrence,
+        }
+    }
 }
 
 fn get_update_title_description_callback(
@@ -210,14 +316,68 @@ fn get_update_checkbox_callback(
     })
 }
 
+fn get_update_auto_complete_callback(
+    cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+) -> Callback<bool> {
+    Callback::from(move |value| {
+        let mut data = cloned_update_task_form.deref().clone();
+        data.auto_complete = value;
+        cloned_update_task_form.set(data);
+    })
+}
+
+fn get_update_toggle_tag_callback(
+    cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+) -> Callback<Uuid> {
+    Callback::from(move |tag_id| {
+        let mut data = cloned_update_task_form.deref().clone();
+        toggle_tag(&mut data.tag_ids, tag_id);
+        cloned_update_task_form.set(data);
+    })
+}
+
+fn get_update_project_callback(
+    cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+) -> Callback<Option<Uuid>> {
+    Callback::from(move |project_id| {
+        let mut data = cloned_update_task_form.deref().clone();
+        data.project_id = project_id;
+        cloned_update_task_form.set(data);
+    })
+}
+
+fn get_update_due_date_callback(
+    cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+) -> Callback<Option<DateTime<Local>>> {
+    Callback::from(move |due_date| {
+        let mut data = cloned_update_task_form.deref().clone();
+        data.due_date = due_date;
+        cloned_update_task_form.set(data);
+    })
+}
+
+fn get_update_recurrence_callback(
+    cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+) -> Callback<Option<String>> {
+    Callback::from(move |recurrence| {
+        let mut data = cloned_update_task_form.deref().clone();
+        data.recurrence = recurrence;
+        cloned_update_task_form.set(data);
+    })
+}
+
 fn get_on_update_callback(
     cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+    cloned_etag: UseStateHandle<Option<String>>,
+    cloned_show_conflict: UseStateHandle<bool>,
     cloned_id: Option<String>,
     store_dispatch: Dispatch<Store>,
     cloned_navigator: Navigator,
 ) -> Callback<MouseEvent> {
     Callback::from(move |_event: MouseEvent| {
         let update_task_form = cloned_update_task_form.clone();
+        let etag = cloned_etag.deref().clone();
+        let show_conflict = cloned_show_conflict.clone();
         let id = cloned_id.clone();
         let dispatch = store_dispatch.clone();
         let navigator = cloned_navigator.clone();
@@ -230,16 +390,34 @@ fn get_on_update_callback(
                     title: update_task_form.title.to_owned(),
                     description: update_task_form.description.to_owned(),
                     completed: update_task_form.completed,
+                    tag_ids: update_task_form.tag_ids.to_owned(),
+                    project_id: update_task_form.project_id,
+                    auto_complete: update_task_form.auto_complete,
+                    due_date: update_task_form.due_date,
+                    recurrence: update_task_form.recurrence.to_owned(),
                 };
 
+                let Ok(task_id) = Uuid::parse_str(&id_params) else {
+                    return set_page_loading(false, dispatch);
+                };
                 let update_task_json = serde_json::to_string(&update_task).unwrap();
-                let response = api_update_task(id_params, &update_task_json).await;
+                let response =
+                    sync::update_task(task_id, &update_task_json, etag, dispatch.clone()).await;
                 match response {
-                    Ok(_) => {
+                    Ok(Saved::Sent(_)) => {
                         set_page_loading(false, dispatch.clone());
                         navigator.push(&router::MainRoute::TasksRoot);
                     }
-                    Err(e) => {
+                    Ok(Saved::Queued) => {
+                        set_page_loading(false, dispatch.clone());
+                        set_show_alert(SAVED_OFFLINE.to_string(), dispatch);
+                        navigator.push(&router::MainRoute::TasksRoot);
+                    }
+                    Err(UpdateTaskError::Conflict) => {
+                        set_page_loading(false, dispatch.clone());
+                        show_conflict.set(true);
+                    }
+                    Err(UpdateTaskError::Other(e)) => {
                         set_page_loading(false, dispatch.clone());
                         set_show_alert(e.to_string(), dispatch);
                     }
@@ -249,6 +427,100 @@ fn get_on_update_callback(
     })
 }
 
+/****** Edit Conflicts ******/
+
+// Someone else saved the task after it was loaded: the form either takes
+// their version or is saved over it with the ETag of their version.
+fn get_on_reload_callback(
+    cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+    cloned_etag: UseStateHandle<Option<String>>,
+    cloned_show_conflict: UseStateHandle<bool>,
+    cloned_id: Option<String>,
+    store_dispatch: Dispatch<Store>,
+) -> Callback<MouseEvent> {
+    Callback::from(move |_event: MouseEvent| {
+        let update_task_form = cloned_update_task_form.clone();
+        let etag = cloned_etag.clone();
+        let show_conflict = cloned_show_conflict.clone();
+        let id = cloned_id.clone();
+        let dispatch = store_dispatch.clone();
+
+        spawn_local(async move {
+            if let Some(id_params) = id {
+                match api_get_single_task(id_params).await {
+                    Ok((task, task_etag)) => {
+                        update_task_form.set(task.into());
+                        etag.set(task_etag);
+                        show_conflict.set(false);
+                    }
+                    Err(e) => set_show_alert(e.to_string(), dispatch),
+                }
+            }
+        })
+    })
+}
+
+fn get_on_overwrite_callback(
+    cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+    cloned_etag: UseStateHandle<Option<String>>,
+    cloned_show_conflict: UseStateHandle<bool>,
+    cloned_id: Option<String>,
+    store_dispatch: Dispatch<Store>,
+    cloned_navigator: Navigator,
+) -> Callback<MouseEvent> {
+    Callback::from(move |_event: MouseEvent| {
+        let update_task_form = cloned_update_task_form.clone();
+        let etag = cloned_etag.clone();
+        let show_conflict = cloned_show_conflict.clone();
+        let id = cloned_id.clone();
+        let dispatch = store_dispatch.clone();
+        let navigator = cloned_navigator.clone();
+
+        spawn_local(async move {
+            if let Some(id_params) = id {
+                let latest_etag = match api_get_single_task(id_params.clone()).await {
+                    Ok((_, latest_etag)) => latest_etag,
+                    Err(e) => return set_show_alert(e.to_string(), dispatch),
+                };
+                etag.set(latest_etag.clone());
+
+                let update_task_json = serde_json::to_string(update_task_form.deref()).unwrap();
+                match api_update_task(id_params, &update_task_json, latest_etag).await {
+                    Ok(_) => {
+                        show_conflict.set(false);
+                        navigator.push(&router::MainRoute::TasksRoot);
+                    }
+                    // Changed once again in the meantime: the dialog stays open.
+                    Err(UpdateTaskError::Conflict) => (),
+                    Err(UpdateTaskError::Other(e)) => {
+                        show_conflict.set(false);
+                        set_show_alert(e.to_string(), dispatch);
+                    }
+                }
+            }
+        })
+    })
+}
+
+fn get_on_cancel_conflict_callback(
+    cloned_show_conflict: UseStateHandle<bool>,
+) -> Callback<MouseEvent> {
+    Callback::from(move |_event: MouseEvent| cloned_show_conflict.set(false))
+}
+
+/****** Reverting Tasks ******/
+
+// The form shows the task as the revert left it.
+fn get_on_revert_callback(
+    cloned_update_task_form: UseStateHandle<UpdateTaskSchema>,
+    cloned_etag: UseStateHandle<Option<String>>,
+) -> Callback<(Task, Option<String>)> {
+    Callback::from(move |(task, etag): (Task, Option<String>)| {
+        cloned_update_task_form.set(task.into());
+        cloned_etag.set(etag);
+    })
+}
+
 /****** Deletion of Tasks ******/
 
 fn get_on_delete_calllback(
@@ -262,12 +534,21 @@ fn get_on_delete_calllback(
         let navigator = cloned_navigator.clone();
 
         spawn_local(async move {
-            if let Some(id_params) = id {
-                let response = api_delete_task(id_params).await;
+            if let Some(task_id) = id.and_then(|id_params| Uuid::parse_str(&id_params).ok()) {
+                let response = sync::delete_task(task_id, dispatch.clone()).await;
                 match response {
-                    Ok(_) => {
+                    Ok(Saved::Sent(_)) => {
                         // set_page_loading(false, dispatch.clone());
                         navigator.push(&router::MainRoute::TasksRoot);
+                        set_show_undo_alert(
+                            "Task moved to the trash".to_string(),
+                            task_id,
+                            dispatch,
+                        );
+                    }
+                    Ok(Saved::Queued) => {
+                        navigator.push(&router::MainRoute::TasksRoot);
+                        set_show_alert(SAVED_OFFLINE.to_string(), dispatch);
                     }
                     Err(e) => {
                         // set_page_loading(false, dispatch.clone());
@@ -1,6 +1,7 @@
 use std::{cell::RefCell, rc::Rc};
 
 use chrono::{DateTime, Local};
+use uuid::Uuid;
 use validator::ValidationErrors;
 use yew::{
     function_component, html, use_effect_with_deps, use_state, virtual_dom::AttrValue, Html,
@@ -10,15 +11,25 @@ use yew_router::hooks::use_navigator;
 use yewdux::functional::use_store;
 
 use crate::{
-    api::task_api::api_get_single_task,
-    components::ui::{
-        form_input::FormInput, loading_button::LoadingButton, spinner::Spinner,
-        textarea_input::TextareaInput,
+    api::tag_api::api_get_tags,
+    components::{
+        shares::share_panel::SharePanel,
+        tasks::{
+            attachment_list::AttachmentList, checklist_editor::ChecklistEditor,
+            comment_thread::CommentThread, conflict_dialog::ConflictDialog,
+            history_panel::HistoryPanel, recurrence_editor::RecurrenceEditor,
+            tag_picker::TagPicker,
+        },
+        ui::{
+            form_input::FormInput, loading_button::LoadingButton, spinner::Spinner,
+            textarea_input::TextareaInput,
+        },
     },
     helpers::date_convert,
     layout::Layout,
     router,
-    store::{set_show_alert, Store},
+    store::{set_show_alert, set_tags_user, Store},
+    sync,
 };
 
 #[derive(Debug, PartialEq, Properties)]
@@ -33,6 +44,9 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
     let form = use_state(|| super::CreateTaskSchema::default());
     let update_task_form = use_state(|| super::UpdateTaskSchema::default());
     let date = use_state(|| DateTime::<Local>::default());
+    // ETag of the loaded task, so that saving it does not overwrite changes made meanwhile.
+    let etag = use_state(|| None::<String>);
+    let show_conflict = use_state(|| false);
     let validation_errors = use_state(|| Rc::new(RefCell::new(ValidationErrors::new())));
 
     let navigator = use_navigator().unwrap();
@@ -44,6 +58,12 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
 
     let handle_title_input = super::get_input_callback("title", form.clone());
     let handle_description_input = super::get_input_callback("description", form.clone());
+    let handle_toggle_tag = super::get_toggle_tag_callback(form.clone());
+    let onchange_project =
+        super::get_select_project_callback(super::get_project_callback(form.clone()));
+    let onchange_due_date =
+        super::get_select_due_date_callback(super::get_due_date_callback(form.clone()));
+    let handle_recurrence = super::get_recurrence_callback(form.clone());
 
     let validate_input_on_blur =
         super::get_validate_input_callback(form.clone(), validation_errors.clone(), id.clone());
@@ -63,6 +83,14 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
         super::get_update_title_description_callback("title", update_task_form.clone());
     let handle_update_description =
         super::get_update_title_description_callback("description", update_task_form.clone());
+    let handle_update_toggle_tag = super::get_update_toggle_tag_callback(update_task_form.clone());
+    let onchange_update_project = super::get_select_project_callback(
+        super::get_update_project_callback(update_task_form.clone()),
+    );
+    let onchange_update_due_date = super::get_select_due_date_callback(
+        super::get_update_due_date_callback(update_task_form.clone()),
+    );
+    let handle_update_recurrence = super::get_update_recurrence_callback(update_task_form.clone());
 
     /* ========= */
     // Since the Input Checkbox does not have a custom component,
@@ -73,14 +101,43 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
     let onchange_checkbox =
         super::get_onchange_checkbox_callback(handle_completed_checkbox.clone());
 
+    let handle_auto_complete_checkbox =
+        super::get_update_auto_complete_callback(update_task_form.clone());
+
+    let onchange_auto_complete =
+        super::get_onchange_checkbox_callback(handle_auto_complete_checkbox.clone());
+
     /* ========= */
     let on_update = super::get_on_update_callback(
         update_task_form.clone(),
+        etag.clone(),
+        show_conflict.clone(),
         id.clone(),
         dispatch.clone(),
         navigator.clone(),
     );
 
+    /* ========= */
+    let on_reload = super::get_on_reload_callback(
+        update_task_form.clone(),
+        etag.clone(),
+        show_conflict.clone(),
+        id.clone(),
+        dispatch.clone(),
+    );
+    let on_overwrite = super::get_on_overwrite_callback(
+        update_task_form.clone(),
+        etag.clone(),
+        show_conflict.clone(),
+        id.clone(),
+        dispatch.clone(),
+        navigator.clone(),
+    );
+    let on_cancel_conflict = super::get_on_cancel_conflict_callback(show_conflict.clone());
+
+    /* ========= */
+    let on_revert = super::get_on_revert_callback(update_task_form.clone(), etag.clone());
+
     /* ========= */
     let on_delete = super::get_on_delete_calllback(id.clone(), dispatch.clone(), navigator.clone());
 
@@ -93,29 +150,36 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
     let cloned_dispatch = dispatch.clone();
     let cloned_update_task_form = update_task_form.clone();
     let cloned_date = date.clone();
+    let cloned_etag = etag.clone();
     let cloned_navigator = navigator.clone();
+    let tags_loaded = store.tags_user.is_some();
 
     use_effect_with_deps(
         move |_| {
             let dispatch = cloned_dispatch.clone();
             let update_task_form = cloned_update_task_form.clone();
             let date = cloned_date.clone();
+            let etag = cloned_etag.clone();
             let navigator = cloned_navigator.clone();
             wasm_bindgen_futures::spawn_local(async move {
+                if !tags_loaded {
+                    match api_get_tags().await {
+                        Ok(tags) => set_tags_user(Some(tags), dispatch.clone()),
+                        Err(e) => set_show_alert(e.to_string(), dispatch.clone()),
+                    }
+                }
+
                 // set_page_loading(true, dispatch.clone());
-                if let Some(id_params) = cloned_id {
-                    let response = api_get_single_task(id_params).await;
+                if let Some(task_id) =
+                    cloned_id.and_then(|id_params| Uuid::parse_str(&id_params).ok())
+                {
+                    let response = sync::fetch_task(task_id, dispatch.clone()).await;
                     match response {
-                        Ok(task) => {
+                        Ok((task, task_etag)) => {
                             // set_page_loading(false, dispatch.clone());
-                            let single_task = super::UpdateTaskSchema {
-                                title: task.title,
-                                description: task.description,
-                                completed: task.completed,
-                            };
-
-                            update_task_form.set(single_task);
                             date.set(task.created_at);
+                            update_task_form.set(task.into());
+                            etag.set(task_etag);
                         }
                         Err(e) => {
                             // set_page_loading(false, dispatch.clone());
@@ -129,6 +193,20 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
         (),
     );
 
+    let selected_project = if id.is_some() {
+        update_task_form.project_id
+    } else {
+        form.project_id
+    };
+    let (due_date, recurrence) = if id.is_some() {
+        (
+            update_task_form.due_date,
+            update_task_form.recurrence.clone(),
+        )
+    } else {
+        (form.due_date, form.recurrence.clone())
+    };
+
     html! {
         <Layout>
           <section class="bg-ct-blue-600 py-6 md:py-12 rounded-2xl grid place-items-center">
@@ -194,6 +272,59 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
                   }
                 />
 
+                <div>
+                  <label for="project" class="block text-ct-blue-600 hover:text-sky-400 mb-3 cursor-pointer">
+                    {"Project"}
+                  </label>
+                  <select
+                    id="project"
+                    class="block w-full rounded-2xl text-ct-dark-600 focus:outline-none py-2 px-4"
+                    onchange={if id.is_some() { onchange_update_project } else { onchange_project }}
+                  >
+                    <option value="" selected={selected_project.is_none()}>{"📥 Inbox"}</option>
+                    {
+                      store.projects_user.clone().unwrap_or_default().into_iter().map(|project| html! {
+                        <option
+                          key={project.id.to_string()}
+                          value={project.id.to_string()}
+                          selected={selected_project == Some(project.id)}
+                        >
+                          {format!("{} {}", project.icon, project.name)}
+                        </option>
+                      }).collect::<Html>()
+                    }
+                  </select>
+                </div>
+
+                <div>
+                  <label for="due_date" class="block text-ct-blue-600 hover:text-sky-400 mb-3 cursor-pointer">
+                    {"Due date"}
+                  </label>
+                  <input
+                    type="datetime-local"
+                    id="due_date"
+                    class="block w-full rounded-2xl text-ct-dark-600 focus:outline-none py-2 px-4"
+                    value={due_date.map(|date| date.format("%Y-%m-%dT%H:%M").to_string()).unwrap_or_default()}
+                    onchange={if id.is_some() { onchange_update_due_date } else { onchange_due_date }}
+                  />
+                </div>
+
+                <RecurrenceEditor
+                  recurrence={recurrence}
+                  due_date={due_date}
+                  on_change={if id.is_some() { handle_update_recurrence } else { handle_recurrence }}
+                />
+
+                <TagPicker
+                  selected={if id.is_some() {
+                      update_task_form.tag_ids.clone()
+                    } else {
+                      form.tag_ids.clone()
+                    }
+                  }
+                  on_toggle={if id.is_some() { handle_update_toggle_tag } else { handle_toggle_tag }}
+                />
+
                 if id.is_some() {
                   <>
                     <label for="completed" class="block text-ct-blue-600 hover:text-sky-400 mb-3 cursor-pointer">
@@ -207,6 +338,27 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
                       onchange={onchange_checkbox}
                     />
 
+                    <ChecklistEditor task_id={id.clone().unwrap()} />
+
+                    <label for="auto_complete" class="flex items-center gap-2 text-ct-blue-600 hover:text-sky-400 cursor-pointer">
+                      <input
+                        type="checkbox"
+                        id="auto_complete"
+                        checked={update_task_form.auto_complete}
+                        class="rounded w-5 h-5 bg-transparent border-gray-300 border-2 checked:accent-emerald-500"
+                        onchange={onchange_auto_complete}
+                      />
+                      {"Complete the task when every item is done"}
+                    </label>
+
+                    <AttachmentList task_id={id.clone().unwrap()} />
+
+                    <SharePanel kind="tasks" id={id.clone().unwrap()} />
+
+                    <CommentThread task_id={id.clone().unwrap()} />
+
+                    <HistoryPanel task_id={id.clone().unwrap()} {on_revert} />
+
                     <div class="mt-3 md:mt-6 grid grid-cols-2 md:grid-cols-3 gap-1 md:gap-2 auto-rows-fr text-xs md:text-base">
                       <button type="button" onclick={go_back}
                         class="bg-purple-600 px-2 py-1 md:px-4 md:py-2 rounded-md flex justify-center items-center gap-2"
@@ -240,6 +392,9 @@ pub fn task_form_page(TaskFormPageProp { id }: &TaskFormPageProp) -> Html {
               </form>
             </div>
           </section>
+          if *show_conflict {
+            <ConflictDialog {on_reload} {on_overwrite} on_cancel={on_cancel_conflict} />
+          }
         </Layout>
     }
 }
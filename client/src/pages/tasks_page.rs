@@ -1,12 +1,27 @@
-use yew::{function_component, html, use_effect_with_deps, Html};
-use yewdux::functional::use_store;
+use std::collections::HashSet;
+
+use uuid::Uuid;
+use yew::{
+    function_component, html, use_effect_with_deps, use_state, Callback, DragEvent, Html,
+    MouseEvent, Properties,
+};
+use yew_router::components::Link;
+use yewdux::{dispatch::Dispatch, functional::use_store};
 
 use crate::{
-    api::task_api::api_get_tasks,
-    components::tasks::task_card::TaskCard,
+    api::{
+        tag_api::api_get_tags,
+        task_api::{api_bulk_tasks, api_clear_completed, api_move_task},
+        types::{BulkAction, BulkTaskData, MoveTaskData},
+    },
+    components::{
+        shares::share_panel::SharePanel,
+        tasks::{bulk_action_bar::BulkActionBar, task_card::TaskCard},
+    },
     layout::Layout,
-    // router,
-    store::{set_show_alert, set_tasks_user, Store},
+    router::MainRoute,
+    store::{set_show_alert, set_tags_user, set_tasks_project, set_tasks_user, Store},
+    sync,
 };
 
 fn capitalize(username: String) -> String {
@@ -15,40 +30,248 @@ fn capitalize(username: String) -> String {
     v.into_iter().collect::<String>()
 }
 
+/// Fetches the tasks of the page again after changing several of them at once.
+async fn reload_tasks(project_id: Option<String>, dispatch: Dispatch<Store>) {
+    match sync::fetch_tasks(project_id, dispatch.clone()).await {
+        Ok(tasks) => set_tasks_user(Some(tasks), dispatch),
+        Err(e) => set_show_alert(e, dispatch),
+    }
+}
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct TasksPageProp {
+    pub project_id: Option<String>,
+}
+
 #[function_component(TasksPage)]
-pub fn tasks_page() -> Html {
+pub fn tasks_page(TasksPageProp { project_id }: &TasksPageProp) -> Html {
     let (store, dispatch) = use_store::<Store>();
     let tasks = store.tasks_user.clone();
+    let tags = store.tags_user.clone().unwrap_or_default();
     let user = store.auth_user.clone();
+    // The project is either one of the user or one shared with them.
+    let project = project_id.as_ref().and_then(|project_id| {
+        store
+            .projects_user
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .chain(store.shared_projects.clone().unwrap_or_default())
+            .find(|project| &project.id.to_string() == project_id)
+    });
     // let navigator = use_navigator().unwrap();
+    let selected = use_state(HashSet::<Uuid>::new);
+    let dragged = use_state(|| None::<Uuid>);
+
+    {
+        let dispatch = dispatch.clone();
+        let selected = selected.clone();
+
+        use_effect_with_deps(
+            move |project_id| {
+                selected.set(HashSet::new());
+                let dispatch = dispatch.clone();
+                let project_id = project_id.clone();
+                set_tasks_project(project_id.clone(), dispatch.clone());
+                wasm_bindgen_futures::spawn_local(async move {
+                    // set_page_loading(true, dispatch.clone());
+                    let response = sync::fetch_tasks(project_id, dispatch.clone()).await;
+                    match response {
+                        Ok(tasks) => {
+                            // set_page_loading(false, dispatch.clone());
+                            set_tasks_user(Some(tasks), dispatch.clone());
+                        }
+                        Err(e) => {
+                            // set_page_loading(false, dispatch.clone());
+                            set_show_alert(e.to_string(), dispatch.clone());
+                            // navigator.push(&router::MainRoute::LoginPage);
+                        }
+                    }
+
+                    match api_get_tags().await {
+                        Ok(tags) => set_tags_user(Some(tags), dispatch),
+                        Err(e) => set_show_alert(e.to_string(), dispatch),
+                    }
+                });
+            },
+            project_id.clone(),
+        );
+    }
+
+    /****** Selection and bulk actions ******/
+
+    let on_toggle_selected = {
+        let selected = selected.clone();
+
+        Callback::from(move |id: Uuid| {
+            let mut ids = (*selected).clone();
+            if !ids.remove(&id) {
+                ids.insert(id);
+            }
+            selected.set(ids);
+        })
+    };
+
+    let on_clear_selection = {
+        let selected = selected.clone();
+
+        Callback::from(move |_: MouseEvent| selected.set(HashSet::new()))
+    };
+
+    let on_bulk_action = {
+        let selected = selected.clone();
+        let dispatch = dispatch.clone();
+        let project_id = project_id.clone();
+
+        Callback::from(move |action: BulkAction| {
+            let selected = selected.clone();
+            let dispatch = dispatch.clone();
+            let project_id = project_id.clone();
+            let bulk_data = BulkTaskData {
+                ids: selected.iter().copied().collect(),
+                action,
+            };
+
+            wasm_bindgen_futures::spawn_local(async move {
+                let bulk_data = serde_json::to_string(&bulk_data).unwrap();
+                match api_bulk_tasks(&bulk_data).await {
+                    Ok(result) => {
+                        reload_tasks(project_id, dispatch.clone()).await;
+                        selected.set(HashSet::new());
+
+                        let message =
+                            match result.results.iter().find_map(|item| item.message.clone()) {
+                                Some(error) => format!(
+                                    "{} tasks updated, {} failed: {}",
+                                    result.succeeded, result.failed, error
+                                ),
+                                None => format!("{} tasks updated", result.succeeded),
+                            };
+                        set_show_alert(message, dispatch);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_clear_completed = {
+        let dispatch = dispatch.clone();
+        let project_id = project_id.clone();
 
-    use_effect_with_deps(
-        move |_| {
+        Callback::from(move |_: MouseEvent| {
             let dispatch = dispatch.clone();
+            let project_id = project_id.clone();
+
             wasm_bindgen_futures::spawn_local(async move {
-                // set_page_loading(true, dispatch.clone());
-                let response = api_get_tasks().await;
-                match response {
-                    Ok(tasks) => {
-                        // set_page_loading(false, dispatch.clone());
-                        set_tasks_user(Some(tasks), dispatch);
+                match api_clear_completed(project_id.clone()).await {
+                    Ok(deleted) => {
+                        reload_tasks(project_id, dispatch.clone()).await;
+                        set_show_alert(
+                            format!("{} completed tasks moved to the trash", deleted),
+                            dispatch,
+                        );
                     }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    /****** Reordering by drag and drop ******/
+
+    let on_drag_start = {
+        let dragged = dragged.clone();
+
+        Callback::from(move |(event, id): (DragEvent, Uuid)| {
+            // Firefox does not start a drag without data.
+            if let Some(data_transfer) = event.data_transfer() {
+                let _ = data_transfer.set_data("text/plain", &id.to_string());
+            }
+            dragged.set(Some(id));
+        })
+    };
+
+    let on_drag_end = {
+        let dragged = dragged.clone();
+
+        Callback::from(move |_: DragEvent| dragged.set(None))
+    };
+
+    let on_drop = {
+        let dragged = dragged.clone();
+        let dispatch = dispatch.clone();
+        let project_id = project_id.clone();
+
+        Callback::from(move |target_id: Uuid| {
+            let Some(dragged_id) = *dragged else {
+                return;
+            };
+            dragged.set(None);
+
+            let mut user_tasks = dispatch.get().tasks_user.clone().unwrap_or_default();
+            let (Some(from), Some(to)) = (
+                user_tasks.iter().position(|task| task.id == dragged_id),
+                user_tasks.iter().position(|task| task.id == target_id),
+            ) else {
+                return;
+            };
+            if from == to {
+                return;
+            }
+
+            // The dragged task takes the place of the one it is dropped on,
+            // which shifts towards where the dragged one came from.
+            let task = user_tasks.remove(from);
+            user_tasks.insert(to, task);
+            let move_data = MoveTaskData {
+                before: to.checked_sub(1).map(|index| user_tasks[index].id),
+                after: user_tasks.get(to + 1).map(|task| task.id),
+            };
+            set_tasks_user(Some(user_tasks), dispatch.clone());
+
+            let dispatch = dispatch.clone();
+            let project_id = project_id.clone();
+            wasm_bindgen_futures::spawn_local(async move {
+                let move_data = serde_json::to_string(&move_data).unwrap();
+                match api_move_task(dragged_id.to_string(), &move_data).await {
+                    Ok(moved_task) => dispatch.reduce_mut(move |store| {
+                        if let Some(task) = store
+                            .tasks_user
+                            .iter_mut()
+                            .flatten()
+                            .find(|task| task.id == moved_task.id)
+                        {
+                            task.position = moved_task.position;
+                        }
+                    }),
                     Err(e) => {
-                        // set_page_loading(false, dispatch.clone());
-                        set_show_alert(e.to_string(), dispatch);
-                        // navigator.push(&router::MainRoute::LoginPage);
+                        // The order shown is no longer the one saved.
+                        reload_tasks(project_id, dispatch.clone()).await;
+                        set_show_alert(e, dispatch);
                     }
                 }
             });
-        },
-        (),
-    );
+        })
+    };
+
+    let board_route = match project_id {
+        Some(id) => MainRoute::ProjectBoardPage { id: id.clone() },
+        None => MainRoute::BoardPage,
+    };
 
     html! {
         <Layout>
           <section class="bg-ct-blue-600 px-8 py-12 rounded-2xl">
             <div class="flex flex-col justify-center mx-auto">
-                if let Some(user) = user {
+                if let Some(project) = project {
+                  <h1 class="text-xl md:text-3xl font-bold text-center my-8" style={format!("color: {};", project.color)}>
+                    {format!("{} {}", project.icon, project.name)}
+                  </h1>
+                  <div class="mb-6">
+                    <SharePanel key={project.id.to_string()} kind="projects" id={project.id.to_string()} />
+                  </div>
+                } else if let Some(user) = user {
                   <h1 class="text-xl md:text-3xl font-bold text-center my-8 text-amber-600">
                     {format!("Welcome back to your Task List, {}!!", capitalize(user.username))}
                   </h1>
@@ -57,17 +280,69 @@ pub fn tasks_page() -> Html {
                 }
 
                 if let Some(user_tasks) = tasks {
+                    if !selected.is_empty() {
+                      <BulkActionBar
+                        selected={selected.len()}
+                        on_action={on_bulk_action}
+                        on_clear_selection={on_clear_selection}
+                      />
+                    }
+                    <div class="flex justify-end gap-4 mb-2 text-sm text-slate-300">
+                      <Link<MainRoute> to={board_route} classes="hover:text-amber-500">
+                        {"Board view"}
+                      </Link<MainRoute>>
+                      if user_tasks.iter().any(|task| task.completed) {
+                        <button type="button" onclick={on_clear_completed} class="hover:text-amber-500">
+                          {"Clear completed"}
+                        </button>
+                      }
+                    </div>
                     if user_tasks.len() > 0 {
                       <ul class="bg-zinc-800 p-8 rounded-xl grid md:grid-cols-2 lg:grid-cols-3 gap-2">
                         {
                           user_tasks.into_iter().map(|task| {
+                           let task_id = task.id;
+                           let on_toggle_selected = on_toggle_selected.clone();
+                           let on_drag_start = on_drag_start.clone();
+                           let on_drop = on_drop.clone();
+
                            html!{
-                               <li key={task.id.to_string()}>
+                               <li
+                                 key={task.id.to_string()}
+                                 draggable="true"
+                                 class={format!("relative {}", if *dragged == Some(task.id) { "opacity-50" } else { "" })}
+                                 ondragstart={Callback::from(move |event: DragEvent| on_drag_start.emit((event, task_id)))}
+                                 ondragend={on_drag_end.clone()}
+                                 // Dropping is only allowed where the default of `dragover` is prevented.
+                                 ondragover={Callback::from(|event: DragEvent| event.prevent_default())}
+                                 ondrop={Callback::from(move |event: DragEvent| {
+                                   event.prevent_default();
+                                   on_drop.emit(task_id);
+                                 })}
+                               >
+                                 // Outside the card, which is a link to the task.
+                                 <input
+                                   type="checkbox"
+                                   title="Select"
+                                   class="absolute top-3 right-3 z-10 w-4 h-4 accent-sky-500 cursor-pointer"
+                                   checked={selected.contains(&task.id)}
+                                   onclick={Callback::from(move |_: MouseEvent| on_toggle_selected.emit(task_id))}
+                                 />
                                  <TaskCard
                                    id={task.id}
                                    title={task.title}
                                    description={task.description}
                                    completed={task.completed}
+                                   due_date={task.due_date}
+                                   repeats={task.recurrence.is_some()}
+                                   tags={
+                                     tags.iter()
+                                       .filter(|tag| task.tag_ids.contains(&tag.id))
+                                       .cloned()
+                                       .collect::<Vec<_>>()
+                                   }
+                                   progress={task.progress}
+                                   tracked_seconds={task.tracked_seconds}
                                  />
                                </li>
                            }
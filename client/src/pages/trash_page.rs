@@ -0,0 +1,160 @@
+use wasm_bindgen_futures::spawn_local;
+use yew::{function_component, html, use_effect_with_deps, use_state, Callback, Html, MouseEvent};
+use yewdux::functional::use_store;
+
+use crate::{
+    api::{
+        task_api::{api_delete_task_permanently, api_empty_trash, api_get_trash, api_restore_task},
+        types::Task,
+    },
+    helpers::date_convert,
+    layout::Layout,
+    store::{set_show_alert, Store},
+};
+
+#[function_component(TrashPage)]
+pub fn trash_page() -> Html {
+    let (_, dispatch) = use_store::<Store>();
+    let trash = use_state(|| None::<Vec<Task>>);
+
+    /****** Getting the trashed tasks when the component is mounted ******/
+
+    {
+        let trash = trash.clone();
+        let dispatch = dispatch.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                spawn_local(async move {
+                    match api_get_trash().await {
+                        Ok(tasks) => trash.set(Some(tasks)),
+                        Err(e) => set_show_alert(e, dispatch),
+                    }
+                });
+            },
+            (),
+        );
+    }
+
+    /****** Restoring and Deleting for good ******/
+
+    let remove_from_trash = {
+        let trash = trash.clone();
+
+        Callback::from(move |task_id: String| {
+            let mut tasks = (*trash).clone().unwrap_or_default();
+            tasks.retain(|task| task.id.to_string() != task_id);
+            trash.set(Some(tasks));
+        })
+    };
+
+    let on_restore = {
+        let remove_from_trash = remove_from_trash.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |task_id: String| {
+            let remove_from_trash = remove_from_trash.clone();
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                match api_restore_task(task_id.clone()).await {
+                    Ok(_) => remove_from_trash.emit(task_id),
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_delete = {
+        let remove_from_trash = remove_from_trash.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |task_id: String| {
+            let remove_from_trash = remove_from_trash.clone();
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                match api_delete_task_permanently(task_id.clone()).await {
+                    Ok(_) => remove_from_trash.emit(task_id),
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    let on_empty = {
+        let trash = trash.clone();
+        let dispatch = dispatch.clone();
+
+        Callback::from(move |_: MouseEvent| {
+            let trash = trash.clone();
+            let dispatch = dispatch.clone();
+
+            spawn_local(async move {
+                match api_empty_trash().await {
+                    Ok(deleted) => {
+                        trash.set(Some(Vec::new()));
+                        set_show_alert(format!("Deleted {} tasks for good", deleted), dispatch);
+                    }
+                    Err(e) => set_show_alert(e, dispatch),
+                }
+            });
+        })
+    };
+
+    html! {
+        <Layout>
+          <section class="bg-ct-blue-600 px-8 py-12 rounded-2xl">
+            <div class="flex flex-col justify-center mx-auto">
+              <h1 class="text-xl md:text-3xl font-bold text-center my-8 text-amber-600">
+                {"Trash"}
+              </h1>
+
+              if let Some(tasks) = (*trash).clone() {
+                if tasks.is_empty() {
+                  <p class="text-center text-slate-400">{"The trash is empty."}</p>
+                } else {
+                  <div class="flex justify-end mb-3">
+                    <button type="button" onclick={on_empty}
+                      class="bg-red-700 hover:bg-red-600 px-3 py-1 rounded-md text-sm">
+                      {"Empty trash"}
+                    </button>
+                  </div>
+                  <ul class="bg-zinc-800 p-6 rounded-xl flex flex-col gap-3">
+                    {
+                      tasks.iter().map(|task| {
+                        let restore_id = task.id.to_string();
+                        let delete_id = task.id.to_string();
+                        let on_restore = on_restore.clone();
+                        let on_delete = on_delete.clone();
+
+                        html! {
+                          <li key={task.id.to_string()} class="flex flex-wrap items-center gap-3">
+                            <span class="flex-1 truncate">{&task.title}</span>
+                            if let Some(deleted_at) = task.deleted_at {
+                              <span class="text-xs text-slate-400">{format!("Deleted {}", date_convert(deleted_at))}</span>
+                            }
+                            <button type="button" class="text-xs text-lime-500 hover:text-lime-400"
+                              onclick={Callback::from(move |_: MouseEvent| on_restore.emit(restore_id.clone()))}
+                            >
+                              {"Restore"}
+                            </button>
+                            <button type="button" class="text-xs text-red-400 hover:text-red-300"
+                              onclick={Callback::from(move |_: MouseEvent| on_delete.emit(delete_id.clone()))}
+                            >
+                              {"Delete forever"}
+                            </button>
+                          </li>
+                        }
+                      }).collect::<Html>()
+                    }
+                  </ul>
+                }
+              } else {
+                <p class="text-center">{"Loading..."}</p>
+              }
+            </div>
+          </section>
+        </Layout>
+    }
+}
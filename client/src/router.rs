@@ -2,9 +2,9 @@ use yew::{html, Html};
 use yew_router::{components::Redirect, Routable, Switch};
 
 use crate::pages::{
-    home_page::HomePage, login_page::LoginPage, not_found::NotFound, profile_page::ProfilePage,
-    register_page::RegisterPage, task_form_page::task_form_page::TaskFormPage,
-    tasks_page::TasksPage,
+    board_page::BoardPage, home_page::HomePage, login_page::LoginPage, not_found::NotFound,
+    profile_page::ProfilePage, register_page::RegisterPage, shared_page::SharedPage,
+    task_form_page::task_form_page::TaskFormPage, tasks_page::TasksPage, trash_page::TrashPage,
 };
 
 #[derive(Clone, PartialEq, Routable)]
@@ -19,6 +19,16 @@ pub enum MainRoute {
     Tasks,
     #[at("/add-task")]
     AddTask,
+    #[at("/projects/:id")]
+    ProjectPage { id: String },
+    #[at("/projects/:id/board")]
+    ProjectBoardPage { id: String },
+    #[at("/board")]
+    BoardPage,
+    #[at("/shared")]
+    SharedPage,
+    #[at("/trash")]
+    TrashPage,
     #[at("/profile")]
     ProfilePage,
     #[at("/")]
@@ -48,6 +58,11 @@ pub fn switch_main(route: MainRoute) -> Html {
             html!(<Switch<TasksRoute> render={switch_tasks} />)
         }
         MainRoute::AddTask => html!(<TaskFormPage />),
+        MainRoute::ProjectPage { id } => html!(<TasksPage project_id={id} />),
+        MainRoute::ProjectBoardPage { id } => html!(<BoardPage project_id={id} />),
+        MainRoute::BoardPage => html!(<BoardPage />),
+        MainRoute::SharedPage => html!(<SharedPage />),
+        MainRoute::TrashPage => html!(<TrashPage />),
         MainRoute::ProfilePage => html!(<ProfilePage />),
         MainRoute::NotFound => html!(<NotFound />),
     }
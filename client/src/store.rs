@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 use yewdux::prelude::*;
 
-use crate::api::types::{Task, User};
+use crate::{
+    api::types::{Project, Tag, Task, TimeEntry, User},
+    sync,
+};
 
 /****** Auth Store ******/
 
@@ -16,6 +20,141 @@ pub fn set_auth_user(user: Option<User>, dispatch: Dispatch<Store>) {
 pub fn set_tasks_user(tasks: Option<Vec<Task>>, dispatch: Dispatch<Store>) {
     dispatch.reduce_mut(move |store| {
         store.tasks_user = tasks;
+        cache_tasks(store);
+    })
+}
+
+/// Keeps the offline copy of the list shown in step with it.
+fn cache_tasks(store: &Store) {
+    if let Some(tasks) = store.tasks_user.as_ref() {
+        sync::save_tasks_cache(store.tasks_project.as_deref(), tasks);
+    }
+}
+
+pub fn set_tasks_project(project: Option<String>, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.tasks_project = project;
+    })
+}
+
+/****** Live Events Store ******/
+
+/// Applies a task that was created or updated elsewhere to `tasks_user`:
+/// it is replaced unless it is older than the one shown, added in order
+/// if it belongs to the list, and taken out if it no longer does.
+pub fn apply_live_task(task: Task, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        let belongs = match store.tasks_project.as_deref() {
+            None => store.auth_user.as_ref().map(|user| user.id) == Some(task.user_id),
+            Some("inbox") => {
+                store.auth_user.as_ref().map(|user| user.id) == Some(task.user_id)
+                    && task.project_id.is_none()
+            }
+            Some(project_id) => {
+                task.project_id.map(|id| id.to_string()).as_deref() == Some(project_id)
+            }
+        };
+        let Some(tasks) = store.tasks_user.as_mut() else {
+            return;
+        };
+
+        match tasks.iter().position(|current| current.id == task.id) {
+            Some(index) if !belongs => {
+                tasks.remove(index);
+            }
+            Some(index) if task.version >= tasks[index].version => tasks[index] = task,
+            Some(_) => {}
+            None if belongs => {
+                let index = tasks
+                    .iter()
+                    .position(|current| current.position > task.position)
+                    .unwrap_or(tasks.len());
+                tasks.insert(index, task);
+            }
+            None => {}
+        }
+        cache_tasks(store);
+    })
+}
+
+/// Takes a task that was deleted elsewhere out of `tasks_user`.
+pub fn remove_live_task(task_id: Uuid, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        if let Some(tasks) = store.tasks_user.as_mut() {
+            tasks.retain(|task| task.id != task_id);
+        }
+        cache_tasks(store);
+    })
+}
+
+/****** Sync Store ******/
+
+pub fn set_offline(offline: bool, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.sync.offline = offline;
+    })
+}
+
+pub fn set_sync_pending(pending: usize, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.sync.pending = pending;
+    })
+}
+
+pub fn set_syncing(syncing: bool, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.sync.syncing = syncing;
+    })
+}
+
+/****** Tags Store ******/
+
+pub fn set_tags_user(tags: Option<Vec<Tag>>, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.tags_user = tags;
+    })
+}
+
+/****** Projects Store ******/
+
+pub fn set_projects_user(projects: Option<Vec<Project>>, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.projects_user = projects;
+    })
+}
+
+/****** Shares Store ******/
+
+pub fn set_shared_projects(projects: Option<Vec<Project>>, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.shared_projects = projects;
+    })
+}
+
+/****** Timer Store ******/
+
+pub fn set_running_timer(time_entry: Option<TimeEntry>, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.running_timer = time_entry;
+    })
+}
+
+/// Adds the time of a timer that has just been stopped to the total of its task.
+pub fn add_stopped_timer(time_entry: TimeEntry, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        if store.running_timer.as_ref().map(|timer| timer.id) == Some(time_entry.id) {
+            store.running_timer = None;
+        }
+        if let (Some(task), Some(ended_at)) = (
+            store
+                .tasks_user
+                .iter_mut()
+                .flatten()
+                .find(|task| task.id == time_entry.task_id),
+            time_entry.ended_at,
+        ) {
+            task.tracked_seconds += (ended_at - time_entry.started_at).num_seconds();
+        }
     })
 }
 
@@ -25,14 +164,34 @@ pub fn set_tasks_user(tasks: Option<Vec<Task>>, dispatch: Dispatch<Store>) {
 pub struct Store {
     pub auth_user: Option<User>,
     pub tasks_user: Option<Vec<Task>>,
+    /// The filter `tasks_user` was fetched with: `None` for all the tasks
+    /// of the user, `inbox` or the ID of a project.
+    pub tasks_project: Option<String>,
+    pub tags_user: Option<Vec<Tag>>,
+    pub projects_user: Option<Vec<Project>>,
+    pub shared_projects: Option<Vec<Project>>,
+    /// The timer of the user, while one is running.
+    pub running_timer: Option<TimeEntry>,
     pub page_loading: bool,
     pub alert_input: AlertInput,
+    pub sync: SyncStatus,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Default, Clone)]
+pub struct SyncStatus {
+    /// Whether the server could not be reached the last time it was tried.
+    pub offline: bool,
+    /// Number of changes made offline that wait to be sent.
+    pub pending: usize,
+    pub syncing: bool,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Default, Clone)]
 pub struct AlertInput {
     pub show_alert: bool,
     pub alert_message: String,
+    /// Task that the alert offers to bring back from the trash.
+    pub undo_task_id: Option<Uuid>,
 }
 
 pub fn set_page_loading(loading: bool, dispatch: Dispatch<Store>) {
@@ -46,6 +205,17 @@ pub fn set_show_alert(message: String, dispatch: Dispatch<Store>) {
         store.alert_input = AlertInput {
             alert_message: message,
             show_alert: true,
+            undo_task_id: None,
+        };
+    })
+}
+
+pub fn set_show_undo_alert(message: String, task_id: Uuid, dispatch: Dispatch<Store>) {
+    dispatch.reduce_mut(move |store| {
+        store.alert_input = AlertInput {
+            alert_message: message,
+            show_alert: true,
+            undo_task_id: Some(task_id),
         };
     })
 }
@@ -0,0 +1,522 @@
+//! Offline support. Every task list is cached in localStorage, so that it can be shown
+//! while the server cannot be reached, and the tasks created, edited or deleted meanwhile
+//! are queued there too. Once the server is back the queue is replayed in order, and the
+//! changes made elsewhere are pulled from `GET /api/tasks/changes`. An offline edit is
+//! replayed with the `ETag` of the version it was made to, so that the server refuses it
+//! (`412`) when the task was changed elsewhere meanwhile: that change is then kept.
+
+use chrono::{DateTime, Duration, Local};
+use gloo::storage::{LocalStorage, Storage};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use uuid::Uuid;
+use yewdux::dispatch::Dispatch;
+
+use crate::{
+    api::{
+        task_api::{
+            api_create_task, api_delete_task, api_get_single_task, api_get_task_changes,
+            api_get_tasks, api_update_task, UpdateTaskError,
+        },
+        types::Task,
+        REQUEST_FAILED,
+    },
+    store::{
+        apply_live_task, remove_live_task, set_offline, set_show_alert, set_sync_pending,
+        set_syncing, Store,
+    },
+};
+
+/// Prefix of the keys of the cached task lists, followed by their filter.
+const CACHE_KEY: &str = "tasks_cache";
+const QUEUE_KEY: &str = "sync_queue";
+/// How far back the changes are pulled from when a list was last loaded,
+/// in case the clocks of the browser and the server differ.
+const CLOCK_MARGIN_MINUTES: i64 = 5;
+
+/// A task list as cached, with the moment it is known to be up to date with.
+#[derive(Debug, Serialize, Deserialize)]
+struct TasksCache {
+    tasks: Vec<Task>,
+    cursor: DateTime<Local>,
+    /// The task whose change at `cursor` was the last one pulled, if any.
+    #[serde(default)]
+    after: Option<Uuid>,
+}
+
+/// A change to a task made while offline, waiting to be sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Mutation {
+    /// `id` is a temporary one, until the server gives the task its own.
+    Create {
+        id: Uuid,
+        data: Value,
+    },
+    /// `etag` is that of the version that was edited, which the edit only applies to.
+    Update {
+        id: Uuid,
+        data: Value,
+        etag: Option<String>,
+    },
+    Delete {
+        id: Uuid,
+    },
+}
+
+impl Mutation {
+    fn task_id(&self) -> Uuid {
+        match self {
+            Mutation::Create { id, .. } | Mutation::Update { id, .. } | Mutation::Delete { id } => {
+                *id
+            }
+        }
+    }
+}
+
+/// What became of a change to a task.
+pub enum Saved<T> {
+    /// The server has it.
+    Sent(T),
+    /// It waits for the server to be reachable again, or never has to reach it.
+    Queued,
+}
+
+/****** Cache ******/
+
+fn cache_key(project: Option<&str>) -> String {
+    format!("{}:{}", CACHE_KEY, project.unwrap_or("all"))
+}
+
+fn load_cache(project: Option<&str>) -> Option<TasksCache> {
+    LocalStorage::get(cache_key(project)).ok()
+}
+
+/// Caches the task list shown, keeping the moment it is up to date with.
+pub fn save_tasks_cache(project: Option<&str>, tasks: &[Task]) {
+    let (cursor, after) = load_cache(project)
+        .map(|cache| (cache.cursor, cache.after))
+        .unwrap_or_else(|| (Local::now() - Duration::minutes(CLOCK_MARGIN_MINUTES), None));
+    let cache = TasksCache {
+        tasks: tasks.to_vec(),
+        cursor,
+        after,
+    };
+    let _ = LocalStorage::set(cache_key(project), cache);
+}
+
+fn save_cache_cursor(project: Option<&str>, cursor: DateTime<Local>, after: Option<Uuid>) {
+    if let Some(mut cache) = load_cache(project) {
+        cache.cursor = cursor;
+        cache.after = after;
+        let _ = LocalStorage::set(cache_key(project), cache);
+    }
+}
+
+/// Fetches the tasks of a list, or gives the cached ones while offline.
+pub async fn fetch_tasks(
+    project: Option<String>,
+    dispatch: Dispatch<Store>,
+) -> Result<Vec<Task>, String> {
+    match api_get_tasks(project.clone()).await {
+        Ok(tasks) => {
+            set_offline(false, dispatch);
+            save_tasks_cache(project.as_deref(), &tasks);
+            save_cache_cursor(
+                project.as_deref(),
+                Local::now() - Duration::minutes(CLOCK_MARGIN_MINUTES),
+                None,
+            );
+            Ok(tasks)
+        }
+        Err(e) if e == REQUEST_FAILED => {
+            set_offline(true, dispatch);
+            load_cache(project.as_deref())
+                .map(|cache| cache.tasks)
+                .ok_or_else(|| "You are offline and this list has not been loaded yet".to_string())
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Fetches a task with its `ETag`, or gives the one shown while offline, or while it
+/// has not reached the server yet.
+pub async fn fetch_task(
+    id: Uuid,
+    dispatch: Dispatch<Store>,
+) -> Result<(Task, Option<String>), String> {
+    let cached = || {
+        dispatch
+            .get()
+            .tasks_user
+            .iter()
+            .flatten()
+            .find(|task| task.id == id)
+            .map(|task| (task.clone(), Some(format!("\"{}\"", task.version))))
+    };
+
+    if has_queued_create(&load_queue(), id) {
+        return cached().ok_or_else(|| "Task not found".to_string());
+    }
+    match api_get_single_task(id.to_string()).await {
+        Err(e) if e == REQUEST_FAILED => {
+            set_offline(true, dispatch.clone());
+            cached().ok_or(e)
+        }
+        result => result,
+    }
+}
+
+/// Forgets the cached lists and the queued changes, e.g. when the user logs out.
+pub fn clear() {
+    let storage = LocalStorage::raw();
+    let keys: Vec<String> = (0..storage.length().unwrap_or(0))
+        .filter_map(|index| storage.key(index).ok().flatten())
+        .filter(|key| key.starts_with(CACHE_KEY) || key == QUEUE_KEY)
+        .collect();
+    for key in keys {
+        LocalStorage::delete(key);
+    }
+}
+
+/****** Queue ******/
+
+fn load_queue() -> Vec<Mutation> {
+    LocalStorage::get(QUEUE_KEY).unwrap_or_default()
+}
+
+fn save_queue(queue: &[Mutation], dispatch: Dispatch<Store>) {
+    let _ = LocalStorage::set(QUEUE_KEY, queue);
+    set_sync_pending(queue.len(), dispatch);
+}
+
+/// Number of changes waiting to be sent.
+pub fn pending_count() -> usize {
+    load_queue().len()
+}
+
+fn has_queued_create(queue: &[Mutation], task_id: Uuid) -> bool {
+    queue
+        .iter()
+        .any(|mutation| matches!(mutation, Mutation::Create { id, .. } if *id == task_id))
+}
+
+/// Sets the fields of `data` on a task, the way the server would.
+fn merge(target: &mut Value, data: &Value) {
+    if let (Some(target), Some(data)) = (target.as_object_mut(), data.as_object()) {
+        for (key, value) in data {
+            target.insert(key.clone(), value.clone());
+        }
+    }
+}
+
+fn patch_task(task: &Task, data: &Value) -> Option<Task> {
+    let mut fields = serde_json::to_value(task).ok()?;
+    merge(&mut fields, data);
+    serde_json::from_value(fields).ok()
+}
+
+/// Creates a task, or queues it when offline; it is shown right away either way.
+pub async fn create_task(
+    task_data: &str,
+    dispatch: Dispatch<Store>,
+) -> Result<Saved<Task>, String> {
+    match api_create_task(task_data).await {
+        Err(e) if e == REQUEST_FAILED => {
+            let data: Value = serde_json::from_str(task_data).map_err(|e| e.to_string())?;
+            let user_id = dispatch
+                .get()
+                .auth_user
+                .as_ref()
+                .map(|user| user.id)
+                .ok_or(e)?;
+
+            // New tasks go on top, as the server puts them.
+            let id = Uuid::new_v4();
+            let now = Local::now();
+            let mut fields = json!({
+                "id": id,
+                "user_id": user_id,
+                "completed": false,
+                "created_at": now,
+                "position": -(now.timestamp() as f64)
+            });
+            merge(&mut fields, &data);
+            let task: Task = serde_json::from_value(fields).map_err(|e| e.to_string())?;
+
+            let mut queue = load_queue();
+            queue.push(Mutation::Create { id, data });
+            save_queue(&queue, dispatch.clone());
+            set_offline(true, dispatch.clone());
+            apply_live_task(task, dispatch);
+
+            Ok(Saved::Queued)
+        }
+        result => {
+            if result.is_ok() {
+                set_offline(false, dispatch);
+            }
+            result.map(Saved::Sent)
+        }
+    }
+}
+
+/// Updates a task, or queues the edit when offline; it is shown right away either way.
+/// Edits of a task that is already queued are folded into the queued change.
+pub async fn update_task(
+    id: Uuid,
+    task_data: &str,
+    etag: Option<String>,
+    dispatch: Dispatch<Store>,
+) -> Result<Saved<Task>, UpdateTaskError> {
+    let data: Value =
+        serde_json::from_str(task_data).map_err(|e| UpdateTaskError::Other(e.to_string()))?;
+
+    let mut queue = load_queue();
+    let queued = queue.iter().any(|mutation| mutation.task_id() == id);
+    if !queued {
+        match api_update_task(id.to_string(), task_data, etag.clone()).await {
+            Err(UpdateTaskError::Other(e)) if e == REQUEST_FAILED => {}
+            result => {
+                if result.is_ok() {
+                    set_offline(false, dispatch);
+                }
+                return result.map(Saved::Sent);
+            }
+        }
+        set_offline(true, dispatch.clone());
+    }
+
+    let shown_task = dispatch
+        .get()
+        .tasks_user
+        .iter()
+        .flatten()
+        .find(|task| task.id == id)
+        .cloned();
+
+    // Folded edits keep the version that the first of them was made to.
+    let folded = queue.iter_mut().rev().find_map(|mutation| match mutation {
+        Mutation::Create { id: task_id, data }
+        | Mutation::Update {
+            id: task_id, data, ..
+        } if *task_id == id => Some(data),
+        _ => None,
+    });
+    match folded {
+        Some(queued_data) => merge(queued_data, &data),
+        None => queue.push(Mutation::Update {
+            id,
+            data: data.clone(),
+            etag: etag.or_else(|| {
+                shown_task
+                    .as_ref()
+                    .map(|task| format!("\"{}\"", task.version))
+            }),
+        }),
+    }
+    save_queue(&queue, dispatch.clone());
+
+    if let Some(task) = shown_task.and_then(|task| patch_task(&task, &data)) {
+        apply_live_task(task, dispatch);
+    }
+
+    Ok(Saved::Queued)
+}
+
+/// Deletes a task, or queues it when offline; it goes away right away either way.
+pub async fn delete_task(id: Uuid, dispatch: Dispatch<Store>) -> Result<Saved<()>, String> {
+    let mut queue = load_queue();
+
+    // A task that never reached the server is just forgotten.
+    if has_queued_create(&queue, id) {
+        queue.retain(|mutation| mutation.task_id() != id);
+        save_queue(&queue, dispatch.clone());
+        remove_live_task(id, dispatch);
+        return Ok(Saved::Queued);
+    }
+
+    match api_delete_task(id.to_string()).await {
+        Err(e) if e == REQUEST_FAILED => {
+            queue.retain(|mutation| mutation.task_id() != id);
+            queue.push(Mutation::Delete { id });
+            save_queue(&queue, dispatch.clone());
+            set_offline(true, dispatch.clone());
+            remove_live_task(id, dispatch);
+
+            Ok(Saved::Queued)
+        }
+        result => {
+            if result.is_ok() {
+                set_offline(false, dispatch);
+            }
+            result.map(Saved::Sent)
+        }
+    }
+}
+
+/****** Synchronization ******/
+
+/// Sends the queued changes in order, then pulls into the list shown the changes made
+/// elsewhere. It stops while the server is still unreachable, to be tried again later.
+pub async fn synchronize(dispatch: Dispatch<Store>) {
+    if dispatch.get().sync.syncing {
+        return;
+    }
+    set_syncing(true, dispatch.clone());
+
+    if replay_queue(dispatch.clone()).await {
+        pull_changes(dispatch.clone()).await;
+    }
+
+    set_syncing(false, dispatch);
+}
+
+/// Returns whether the whole queue could be sent.
+async fn replay_queue(dispatch: Dispatch<Store>) -> bool {
+    loop {
+        // The change is taken out while it is sent, so that the edits made meanwhile
+        // are queued after it instead of being folded into it.
+        let mut queue = load_queue();
+        if queue.is_empty() {
+            return true;
+        }
+        let mutation = queue.remove(0);
+        save_queue(&queue, dispatch.clone());
+
+        if send(&mutation, dispatch.clone()).await.is_err() {
+            let mut queue = load_queue();
+            queue.insert(0, mutation);
+            save_queue(&queue, dispatch.clone());
+            set_offline(true, dispatch);
+            return false;
+        }
+    }
+}
+
+/// Sends a queued change; fails only when the server cannot be reached.
+async fn send(mutation: &Mutation, dispatch: Dispatch<Store>) -> Result<(), ()> {
+    match mutation {
+        Mutation::Create { id, data } => match api_create_task(&data.to_string()).await {
+            Ok(task) => {
+                // The later changes of the task go to the ID it was given.
+                let mut queue = load_queue();
+                for mutation in queue.iter_mut() {
+                    match mutation {
+                        Mutation::Update { id: task_id, .. } | Mutation::Delete { id: task_id }
+                            if task_id == id =>
+                        {
+                            *task_id = task.id
+                        }
+                        _ => {}
+                    }
+                }
+                save_queue(&queue, dispatch.clone());
+
+                remove_live_task(*id, dispatch.clone());
+                apply_live_task(task, dispatch);
+                Ok(())
+            }
+            Err(e) if e == REQUEST_FAILED => Err(()),
+            Err(e) => {
+                let mut queue = load_queue();
+                queue.retain(|mutation| mutation.task_id() != *id);
+                save_queue(&queue, dispatch.clone());
+
+                remove_live_task(*id, dispatch.clone());
+                set_show_alert(
+                    format!("A task created offline could not be saved: {}", e),
+                    dispatch,
+                );
+                Ok(())
+            }
+        },
+        Mutation::Update { id, data, etag } => {
+            let task_data = data.to_string();
+            match api_update_task(id.to_string(), &task_data, etag.clone()).await {
+                Ok(task) => {
+                    apply_live_task(task, dispatch);
+                    Ok(())
+                }
+                Err(UpdateTaskError::Conflict) => resolve_conflict(*id, dispatch).await,
+                Err(UpdateTaskError::Other(e)) if e == REQUEST_FAILED => Err(()),
+                Err(UpdateTaskError::Other(e)) => {
+                    set_show_alert(
+                        format!("A change made offline could not be saved: {}", e),
+                        dispatch,
+                    );
+                    Ok(())
+                }
+            }
+        }
+        // A task that is already gone elsewhere has nothing left to delete.
+        Mutation::Delete { id } => match api_delete_task(id.to_string()).await {
+            Err(e) if e == REQUEST_FAILED => Err(()),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// The task was edited elsewhere since the version the offline edit was made to:
+/// the edit made elsewhere is kept, and the offline one is dropped.
+async fn resolve_conflict(id: Uuid, dispatch: Dispatch<Store>) -> Result<(), ()> {
+    let task = match api_get_single_task(id.to_string()).await {
+        Ok((task, _)) => task,
+        Err(e) if e == REQUEST_FAILED => return Err(()),
+        // Deleted elsewhere meanwhile.
+        Err(_) => {
+            remove_live_task(id, dispatch);
+            return Ok(());
+        }
+    };
+
+    let message = format!(
+        "\"{}\" was changed elsewhere while you were offline, so your edit was discarded",
+        task.title
+    );
+    apply_live_task(task, dispatch.clone());
+    set_show_alert(message, dispatch);
+
+    Ok(())
+}
+
+/// Applies to the list shown the changes made since it was last up to date.
+async fn pull_changes(dispatch: Dispatch<Store>) {
+    let store = dispatch.get();
+    if store.tasks_user.is_none() {
+        return;
+    }
+    let project = store.tasks_project.clone();
+    let Some(cache) = load_cache(project.as_deref()) else {
+        return;
+    };
+
+    let (mut cursor, mut after) = (cache.cursor, cache.after);
+    loop {
+        match api_get_task_changes(cursor, after, project.clone()).await {
+            Ok(changes) => {
+                for task in changes.tasks {
+                    if task.deleted_at.is_some() {
+                        remove_live_task(task.id, dispatch.clone());
+                    } else {
+                        apply_live_task(task, dispatch.clone());
+                    }
+                }
+                (cursor, after) = (changes.cursor, changes.after);
+                save_cache_cursor(project.as_deref(), cursor, after);
+
+                if !changes.has_more {
+                    set_offline(false, dispatch);
+                    return;
+                }
+            }
+            Err(e) if e == REQUEST_FAILED => {
+                set_offline(true, dispatch);
+                return;
+            }
+            Err(e) => {
+                set_show_alert(e, dispatch);
+                return;
+            }
+        }
+    }
+}
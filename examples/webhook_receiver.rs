@@ -0,0 +1,102 @@
+//! A local receiver to try out webhooks: it prints every event it gets
+//! and whether its signature is valid.
+//!
+//! ```bash
+//! $ WEBHOOK_SECRET=<secret of the webhook> cargo run --example webhook_receiver
+//! ```
+//!
+//! Then register `http://localhost:9000/webhook` as a webhook and send it a ping
+//! with `POST /api/webhooks/:id/ping`. Set `WEBHOOK_STATUS` (e.g. to 500) to make
+//! it fail the deliveries and watch them being retried in the delivery log.
+
+use std::net::SocketAddr;
+
+use axum::{
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use ring::hmac;
+
+/// Oldest timestamp accepted, in seconds, so that a captured request cannot be replayed later.
+const MAX_AGE_SECONDS: i64 = 5 * 60;
+
+#[tokio::main]
+async fn main() {
+    let port: u16 = std::env::var("WEBHOOK_PORT")
+        .unwrap_or("9000".to_string())
+        .parse()
+        .unwrap();
+
+    let app = Router::new().route("/webhook", post(receive_handler));
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+
+    println!("->> Receiving webhooks on http://{}/webhook\n", addr);
+
+    axum::Server::bind(&addr)
+        .serve(app.into_make_service())
+        .await
+        .unwrap();
+}
+
+async fn receive_handler(headers: HeaderMap, body: String) -> StatusCode {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let event = header("X-Webhook-Event");
+    let delivery = header("X-Webhook-Delivery");
+    let timestamp = header("X-Webhook-Timestamp");
+    let signature = header("X-Webhook-Signature");
+
+    let verdict = match std::env::var("WEBHOOK_SECRET") {
+        Ok(secret) => match verify(&secret, &timestamp, &body, &signature) {
+            Ok(()) => "✅ valid signature".to_string(),
+            Err(reason) => format!("❌ {}", reason),
+        },
+        Err(_) => "⚠️ not verified, WEBHOOK_SECRET is not set".to_string(),
+    };
+
+    println!("->> {} ({}): {}", event, delivery, verdict);
+    println!("{}\n", body);
+
+    std::env::var("WEBHOOK_STATUS")
+        .ok()
+        .and_then(|status| status.parse().ok())
+        .and_then(|status| StatusCode::from_u16(status).ok())
+        .unwrap_or(StatusCode::NO_CONTENT)
+}
+
+/// Recomputes the signature of a request, as a receiver should do.
+fn verify(secret: &str, timestamp: &str, body: &str, signature: &str) -> Result<(), String> {
+    let sent_at: i64 = timestamp
+        .parse()
+        .map_err(|_| "missing or invalid timestamp".to_string())?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|err| err.to_string())?
+        .as_secs() as i64;
+    if (now - sent_at).abs() > MAX_AGE_SECONDS {
+        return Err("timestamp too old".to_string());
+    }
+
+    let hex = signature
+        .strip_prefix("sha256=")
+        .ok_or("missing or invalid signature")?;
+    let tag = (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect::<Option<Vec<u8>>>()
+        .ok_or("missing or invalid signature")?;
+
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    hmac::verify(&key, format!("{}.{}", timestamp, body).as_bytes(), &tag)
+        .map_err(|_| "signature mismatch".to_string())
+}
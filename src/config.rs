@@ -1,31 +1,635 @@
+use std::{collections::HashMap, fmt, net::SocketAddr, path::PathBuf, str::FromStr};
+
+use axum::http::HeaderValue;
+use axum_extra::extract::cookie::SameSite;
+use clap::Parser;
+
+/// File the settings are read from when `--config` is not given, if it exists.
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+/// Longest lifetime of the access tokens and the session cookie, so that the
+/// dates computed from them cannot overflow.
+const MAX_LIFETIME_DAYS: i64 = 365;
+/// Longest time tasks are kept in the trash, for the same reason.
+const MAX_TRASH_RETENTION_DAYS: i64 = 36_500;
+
+/// Every setting, named after its environment variable, with its default.
+/// The TOML file uses the same names in lowercase.
+const SETTINGS: [(&str, Option<&str>); 25] = [
+    ("LISTEN_ADDR", Some("0.0.0.0:8080")),
+    ("ALLOWED_ORIGINS", Some("http://localhost:3000")),
+    ("STATIC_DIR", Some("client/dist")),
+    (
+        "LOG_FILTER",
+        Some("example_static_file_server=debug,tower_http=debug"),
+    ),
+    ("SUPABASE_URL", None),
+    ("SUPABASE_ANON_KEY", None),
+    ("JWT_SECRET", Some("my_ultra_secure_secret")),
+    ("JWT_EXPIRED_IN", Some("60m")),
+    ("JWT_MAXAGE", Some("60")),
+    ("COOKIE_SECURE", Some("false")),
+    ("COOKIE_SAME_SITE", Some("lax")),
+    ("STORAGE_BACKEND", Some("local")),
+    ("ATTACHMENTS_DIR", Some("attachments")),
+    ("ATTACHMENT_MAX_SIZE", Some("10485760")),
+    ("ATTACHMENT_QUOTA", Some("104857600")),
+    (
+        "ATTACHMENT_MIME_TYPES",
+        Some("image/png,image/jpeg,image/gif,image/webp,application/pdf,text/plain"),
+    ),
+    ("TRASH_RETENTION_DAYS", Some("30")),
+    ("TRASH_PURGE_INTERVAL", Some("3600")),
+    ("WEBHOOK_POLL_INTERVAL", Some("5")),
+    ("WEBHOOK_MAX_ATTEMPTS", Some("8")),
+    ("WEBHOOK_TIMEOUT", Some("10")),
+    ("WEBHOOK_ALLOW_LOCAL", Some("false")),
+    ("EVENTS_HEARTBEAT_INTERVAL", Some("15")),
+    ("SHUTDOWN_DELAY", Some("0")),
+    ("SHUTDOWN_DRAIN_TIMEOUT", Some("30")),
+];
+
+/// Command line flags: they override the configuration file and the environment.
+#[derive(Debug, Parser)]
+#[command(version, about = "Tasks Manager API server")]
+pub struct Cli {
+    /// TOML file to read the settings from [default: config.toml, if it exists]
+    #[arg(short, long, env = "CONFIG_FILE")]
+    config: Option<PathBuf>,
+    /// Address to listen on, e.g. 0.0.0.0:8080
+    #[arg(long)]
+    listen_addr: Option<String>,
+    /// Origins allowed to call the API from a browser, separated by commas
+    #[arg(long)]
+    allowed_origins: Option<String>,
+    /// Directory of the frontend build
+    #[arg(long)]
+    static_dir: Option<String>,
+    /// Filter of the logs, e.g. tower_http=debug
+    #[arg(long)]
+    log_filter: Option<String>,
+    /// Lifetime of the access tokens, e.g. 60m
+    #[arg(long)]
+    jwt_expired_in: Option<String>,
+    /// Lifetime of the session cookie, in minutes
+    #[arg(long)]
+    jwt_maxage: Option<String>,
+    /// Only send the session cookie over HTTPS
+    #[arg(long)]
+    cookie_secure: Option<bool>,
+    /// SameSite policy of the session cookie: lax, strict or none
+    #[arg(long)]
+    cookie_same_site: Option<String>,
+    /// Where attachments are stored: local
+    #[arg(long)]
+    storage_backend: Option<String>,
+    /// Directory of the attachments, with the local storage backend
+    #[arg(long)]
+    attachments_dir: Option<String>,
+    /// Seconds to keep accepting requests once asked to shut down
+    #[arg(long)]
+    shutdown_delay: Option<String>,
+    /// Seconds given to the requests in flight to finish when shutting down
+    #[arg(long)]
+    shutdown_drain_timeout: Option<String>,
+}
+
+/// Where the content of attachments is kept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StorageBackend {
+    /// Files under `ATTACHMENTS_DIR`.
+    Local,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "local" => Ok(StorageBackend::Local),
+            _ => Err("the supported backends are: local".to_string()),
+        }
+    }
+}
+
+/// Every problem found in the configuration, rather than only the first one.
+#[derive(Debug)]
+pub struct ConfigError(Vec<String>);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid configuration:")?;
+        for error in &self.0 {
+            writeln!(f, "  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
+    pub listen_addr: SocketAddr,
+    pub allowed_origins: Vec<HeaderValue>,
+    pub static_dir: String,
+    pub log_filter: String,
+
     pub supabase_url: String,
     pub supabase_anon_key: String,
 
     pub jwt_secret: String,
-    pub jwt_expires_in: String,
-    pub jwt_maxage: i32,
+    pub jwt_expires_in: chrono::Duration,
+    pub jwt_maxage: i64,
+    pub cookie_secure: bool,
+    pub cookie_same_site: SameSite,
+
+    pub storage_backend: StorageBackend,
+    pub attachments_dir: String,
+    pub attachment_max_size: usize,
+    pub attachment_quota: usize,
+    pub attachment_mime_types: Vec<String>,
+
+    pub trash_retention_days: i64,
+    pub trash_purge_interval: u64,
+
+    pub webhook_poll_interval: u64,
+    pub webhook_max_attempts: i32,
+    pub webhook_timeout: u64,
+    /// Lets webhooks reach `http` and local receivers, for testing only.
+    pub webhook_allow_local: bool,
+
+    pub events_heartbeat_interval: u64,
+
+    pub shutdown_delay: u64,
+    pub shutdown_drain_timeout: u64,
 }
 
 impl Config {
-    pub fn init() -> Config {
-        let supabase_url = std::env::var("SUPABASE_URL").expect("SUPABASE_URL must be set");
-        let supabase_anon_key =
-            std::env::var("SUPABASE_ANON_KEY").expect("SUPABASE_ANON_KEY must be set");
+    /// Reads the settings from, in increasing order of precedence: their defaults,
+    /// the TOML file, the environment and the command line.
+    pub fn init() -> Result<Config, ConfigError> {
+        Config::load(&Cli::parse(), |name| std::env::var(name).ok())
+    }
+
+    /// Same as `init`, with `env` looking up the environment variables.
+    fn load(cli: &Cli, env: impl Fn(&str) -> Option<String>) -> Result<Config, ConfigError> {
+        let mut errors = Vec::new();
+        let settings = Settings::load(cli, env, &mut errors);
+
+        let config = Config {
+            listen_addr: settings
+                .parse("LISTEN_ADDR", &mut errors)
+                .unwrap_or_else(|| SocketAddr::from(([0, 0, 0, 0], 8080))),
+            allowed_origins: settings
+                .list("ALLOWED_ORIGINS")
+                .into_iter()
+                .filter_map(|origin| parse_origin(&origin, &mut errors))
+                .collect(),
+            static_dir: settings.required("STATIC_DIR", &mut errors),
+            log_filter: settings.required("LOG_FILTER", &mut errors),
+
+            supabase_url: settings.required("SUPABASE_URL", &mut errors),
+            supabase_anon_key: settings.required("SUPABASE_ANON_KEY", &mut errors),
 
-        let jwt_secret =
-            std::env::var("JWT_SECRET").unwrap_or("my_ultra_secure_secret".to_string());
-        let jwt_expires_in = std::env::var("JWT_EXPIRED_IN").unwrap_or("60m".to_string());
-        let jwt_maxage = std::env::var("JWT_MAXAGE").unwrap_or("60".to_string());
+            jwt_secret: settings.required("JWT_SECRET", &mut errors),
+            jwt_expires_in: settings
+                .get("JWT_EXPIRED_IN")
+                .and_then(|value| {
+                    settings.check("JWT_EXPIRED_IN", parse_duration(&value), &mut errors)
+                })
+                .unwrap_or_else(|| chrono::Duration::minutes(60)),
+            jwt_maxage: settings.bounded("JWT_MAXAGE", MAX_LIFETIME_DAYS * 24 * 60, &mut errors),
+            cookie_secure: settings
+                .parse("COOKIE_SECURE", &mut errors)
+                .unwrap_or_default(),
+            cookie_same_site: settings
+                .get("COOKIE_SAME_SITE")
+                .and_then(|value| {
+                    settings.check("COOKIE_SAME_SITE", parse_same_site(&value), &mut errors)
+                })
+                .unwrap_or(SameSite::Lax),
 
-        Config {
-            supabase_url,
-            supabase_anon_key,
+            storage_backend: settings
+                .parse("STORAGE_BACKEND", &mut errors)
+                .unwrap_or(StorageBackend::Local),
+            attachments_dir: settings.required("ATTACHMENTS_DIR", &mut errors),
+            attachment_max_size: settings.positive("ATTACHMENT_MAX_SIZE", &mut errors),
+            attachment_quota: settings.positive("ATTACHMENT_QUOTA", &mut errors),
+            attachment_mime_types: settings.list("ATTACHMENT_MIME_TYPES"),
 
-            jwt_secret,
-            jwt_expires_in,
-            jwt_maxage: jwt_maxage.parse::<i32>().unwrap(),
+            trash_retention_days: settings.bounded(
+                "TRASH_RETENTION_DAYS",
+                MAX_TRASH_RETENTION_DAYS,
+                &mut errors,
+            ),
+            // The background jobs cannot run at intervals of zero.
+            trash_purge_interval: settings.positive("TRASH_PURGE_INTERVAL", &mut errors),
+
+            webhook_poll_interval: settings.positive("WEBHOOK_POLL_INTERVAL", &mut errors),
+            webhook_max_attempts: settings.positive("WEBHOOK_MAX_ATTEMPTS", &mut errors),
+            webhook_timeout: settings.positive("WEBHOOK_TIMEOUT", &mut errors),
+            webhook_allow_local: settings
+                .parse("WEBHOOK_ALLOW_LOCAL", &mut errors)
+                .unwrap_or_default(),
+
+            events_heartbeat_interval: settings.positive("EVENTS_HEARTBEAT_INTERVAL", &mut errors),
+
+            shutdown_delay: settings
+                .parse("SHUTDOWN_DELAY", &mut errors)
+                .unwrap_or_default(),
+            shutdown_drain_timeout: settings.positive("SHUTDOWN_DRAIN_TIMEOUT", &mut errors),
+        };
+
+        // Browsers drop `SameSite=None` cookies that are not `Secure`.
+        if config.cookie_same_site == SameSite::None && !config.cookie_secure {
+            errors.push("COOKIE_SAME_SITE: `none` requires COOKIE_SECURE=true".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError(errors))
+        }
+    }
+}
+
+/// The raw values of the settings, once the layers are applied.
+struct Settings(HashMap<&'static str, String>);
+
+impl Settings {
+    fn load(cli: &Cli, env: impl Fn(&str) -> Option<String>, errors: &mut Vec<String>) -> Settings {
+        let mut values: HashMap<&'static str, String> = SETTINGS
+            .iter()
+            .filter_map(|(name, default)| default.map(|value| (*name, value.to_string())))
+            .collect();
+
+        let file = cli.config.clone().or_else(|| {
+            let default = PathBuf::from(DEFAULT_CONFIG_FILE);
+            default.exists().then_some(default)
+        });
+        if let Some(file) = file {
+            load_file(&file, &mut values, errors);
+        }
+
+        for (name, _) in SETTINGS {
+            if let Some(value) = env(name) {
+                values.insert(name, value);
+            }
         }
+        // `RUST_LOG` keeps working as it did before the setting existed.
+        if let (None, Some(value)) = (env("LOG_FILTER"), env("RUST_LOG")) {
+            values.insert("LOG_FILTER", value);
+        }
+
+        let flags = [
+            ("LISTEN_ADDR", cli.listen_addr.clone()),
+            ("ALLOWED_ORIGINS", cli.allowed_origins.clone()),
+            ("STATIC_DIR", cli.static_dir.clone()),
+            ("LOG_FILTER", cli.log_filter.clone()),
+            ("JWT_EXPIRED_IN", cli.jwt_expired_in.clone()),
+            ("JWT_MAXAGE", cli.jwt_maxage.clone()),
+            (
+                "COOKIE_SECURE",
+                cli.cookie_secure.map(|secure| secure.to_string()),
+            ),
+            ("COOKIE_SAME_SITE", cli.cookie_same_site.clone()),
+            ("STORAGE_BACKEND", cli.storage_backend.clone()),
+            ("ATTACHMENTS_DIR", cli.attachments_dir.clone()),
+            ("SHUTDOWN_DELAY", cli.shutdown_delay.clone()),
+            ("SHUTDOWN_DRAIN_TIMEOUT", cli.shutdown_drain_timeout.clone()),
+        ];
+        for (name, value) in flags {
+            if let Some(value) = value {
+                values.insert(name, value);
+            }
+        }
+
+        Settings(values)
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        self.0.get(name).cloned()
+    }
+
+    fn required(&self, name: &str, errors: &mut Vec<String>) -> String {
+        match self.get(name) {
+            Some(value) if !value.trim().is_empty() => value,
+            _ => {
+                errors.push(format!("{}: must be set", name));
+                String::new()
+            }
+        }
+    }
+
+    fn list(&self, name: &str) -> Vec<String> {
+        self.get(name)
+            .unwrap_or_default()
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    }
+
+    fn check<T>(
+        &self,
+        name: &str,
+        parsed: Result<T, String>,
+        errors: &mut Vec<String>,
+    ) -> Option<T> {
+        parsed
+            .map_err(|err| {
+                let value = self.get(name).unwrap_or_default();
+                errors.push(format!("{}: invalid value `{}` ({})", name, value, err))
+            })
+            .ok()
+    }
+
+    fn parse<T>(&self, name: &str, errors: &mut Vec<String>) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let value = self.get(name)?;
+        self.check(
+            name,
+            value.trim().parse::<T>().map_err(|err| err.to_string()),
+            errors,
+        )
+    }
+
+    fn positive<T>(&self, name: &str, errors: &mut Vec<String>) -> T
+    where
+        T: FromStr + Default + PartialOrd,
+        T::Err: fmt::Display,
+    {
+        match self.parse::<T>(name, errors) {
+            Some(value) if value > T::default() => value,
+            Some(_) => {
+                errors.push(format!("{}: must be greater than zero", name));
+                T::default()
+            }
+            None => T::default(),
+        }
+    }
+
+    fn bounded<T>(&self, name: &str, max: T, errors: &mut Vec<String>) -> T
+    where
+        T: FromStr + Default + PartialOrd + fmt::Display,
+        T::Err: fmt::Display,
+    {
+        let value = self.positive::<T>(name, errors);
+        if value > max {
+            errors.push(format!("{}: must be at most {}", name, max));
+            return T::default();
+        }
+        value
+    }
+}
+
+/// Applies the settings of a TOML file: `listen_addr = "0.0.0.0:8080"`,
+/// with lists given either as arrays or as strings separated by commas.
+fn load_file(file: &PathBuf, values: &mut HashMap<&'static str, String>, errors: &mut Vec<String>) {
+    let table = match std::fs::read_to_string(file)
+        .map_err(|err| err.to_string())
+        .and_then(|content| {
+            content
+                .parse::<toml::Table>()
+                .map_err(|err| err.to_string())
+        }) {
+        Ok(table) => table,
+        Err(err) => {
+            return errors.push(format!("{}: {}", file.display(), err.trim_end()));
+        }
+    };
+
+    for (key, value) in table {
+        let Some((name, _)) = SETTINGS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&key))
+        else {
+            errors.push(format!("{}: unknown setting `{}`", file.display(), key));
+            continue;
+        };
+
+        let value = match value {
+            toml::Value::String(value) => Some(value),
+            toml::Value::Array(items) => items
+                .into_iter()
+                .map(|item| match item {
+                    toml::Value::String(item) => Some(item),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(|items| items.join(",")),
+            toml::Value::Integer(value) => Some(value.to_string()),
+            toml::Value::Float(value) => Some(value.to_string()),
+            toml::Value::Boolean(value) => Some(value.to_string()),
+            _ => None,
+        };
+        match value {
+            Some(value) => {
+                values.insert(name, value);
+            }
+            None => errors.push(format!(
+                "{}: `{}` must be a string, a number, a boolean or a list of strings",
+                file.display(),
+                key
+            )),
+        }
+    }
+}
+
+fn parse_origin(origin: &str, errors: &mut Vec<String>) -> Option<HeaderValue> {
+    let origin = origin.trim_end_matches('/');
+    let valid = (origin.starts_with("http://") || origin.starts_with("https://"))
+        && !origin[origin.find("://").unwrap_or_default() + 3..].contains('/');
+
+    match HeaderValue::from_str(origin) {
+        Ok(value) if valid => Some(value),
+        _ => {
+            errors.push(format!(
+                "ALLOWED_ORIGINS: invalid origin `{}` (expected e.g. https://example.com)",
+                origin
+            ));
+            None
+        }
+    }
+}
+
+/// Parses a duration like `90s`, `60m`, `12h` or `7d`.
+fn parse_duration(value: &str) -> Result<chrono::Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| "expected a number followed by s, m, h or d".to_string())?;
+
+    let duration = match unit {
+        "s" => chrono::Duration::try_seconds(amount),
+        "m" => chrono::Duration::try_minutes(amount),
+        "h" => chrono::Duration::try_hours(amount),
+        "d" => chrono::Duration::try_days(amount),
+        _ => return Err("expected a number followed by s, m, h or d".to_string()),
+    };
+    if amount == 0 {
+        return Err("must be greater than zero".to_string());
+    }
+
+    duration
+        .filter(|duration| duration.num_days() <= MAX_LIFETIME_DAYS)
+        .ok_or_else(|| format!("must be at most {}d", MAX_LIFETIME_DAYS))
+}
+
+fn parse_same_site(value: &str) -> Result<SameSite, String> {
+    match value.trim().to_lowercase().as_str() {
+        "lax" => Ok(SameSite::Lax),
+        "strict" => Ok(SameSite::Strict),
+        "none" => Ok(SameSite::None),
+        _ => Err("expected lax, strict or none".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Loads the configuration from a TOML file with `file` in it, the environment
+    /// variables of `env` and the command line flags of `flags`.
+    fn load(
+        test: &str,
+        file: &str,
+        env: &[(&str, &str)],
+        flags: &[&str],
+    ) -> Result<Config, ConfigError> {
+        let path =
+            std::env::temp_dir().join(format!("config-{}-{}.toml", test, std::process::id()));
+        std::fs::write(&path, file).unwrap();
+
+        let mut args = vec![
+            "rust-axum-supabase-api".to_string(),
+            "--config".to_string(),
+            path.display().to_string(),
+        ];
+        args.extend(flags.iter().map(|flag| flag.to_string()));
+        let env: HashMap<String, String> = [
+            ("SUPABASE_URL", "http://localhost:54321"),
+            ("SUPABASE_ANON_KEY", "anon"),
+        ]
+        .iter()
+        .chain(env)
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+
+        let config = Config::load(&Cli::parse_from(args), |name| env.get(name).cloned());
+        let _ = std::fs::remove_file(&path);
+        config
+    }
+
+    fn errors(result: Result<Config, ConfigError>) -> Vec<String> {
+        result.unwrap_err().0
+    }
+
+    #[test]
+    fn falls_back_to_the_defaults() {
+        let config = load("defaults", "", &[], &[]).unwrap();
+
+        assert_eq!(config.listen_addr, SocketAddr::from(([0, 0, 0, 0], 8080)));
+        assert_eq!(config.allowed_origins, ["http://localhost:3000"]);
+        assert_eq!(config.jwt_expires_in, chrono::Duration::minutes(60));
+        assert_eq!(config.cookie_same_site, SameSite::Lax);
+        assert_eq!(config.trash_retention_days, 30);
+        assert_eq!(config.attachment_mime_types.len(), 6);
+    }
+
+    #[test]
+    fn layers_the_file_the_environment_and_the_flags() {
+        let file = "listen_addr = \"127.0.0.1:1001\"\nstatic_dir = \"file\"\njwt_maxage = 10";
+        let env = [("LISTEN_ADDR", "127.0.0.1:1002"), ("STATIC_DIR", "env")];
+        let flags = ["--listen-addr", "127.0.0.1:1003"];
+
+        let config = load("layers", file, &env, &flags).unwrap();
+        assert_eq!(config.listen_addr.port(), 1003);
+        assert_eq!(config.static_dir, "env");
+        assert_eq!(config.jwt_maxage, 10);
+
+        let config = load("layers-env", file, &env, &[]).unwrap();
+        assert_eq!(config.listen_addr.port(), 1002);
+
+        let config = load("layers-file", file, &[], &[]).unwrap();
+        assert_eq!(config.listen_addr.port(), 1001);
+        assert_eq!(config.static_dir, "file");
+    }
+
+    #[test]
+    fn reads_lists_as_arrays_or_strings() {
+        let file = "allowed_origins = [\"https://a.example\", \"https://b.example/\"]";
+        let config = load("arrays", file, &[], &[]).unwrap();
+        assert_eq!(
+            config.allowed_origins,
+            ["https://a.example", "https://b.example"]
+        );
+
+        let env = [("ATTACHMENT_MIME_TYPES", "image/png, text/plain")];
+        let config = load("strings", "", &env, &[]).unwrap();
+        assert_eq!(config.attachment_mime_types, ["image/png", "text/plain"]);
+    }
+
+    #[test]
+    fn takes_the_log_filter_from_rust_log_unless_it_is_set() {
+        let config = load("rust-log", "", &[("RUST_LOG", "debug")], &[]).unwrap();
+        assert_eq!(config.log_filter, "debug");
+
+        let env = [("RUST_LOG", "debug"), ("LOG_FILTER", "info")];
+        let config = load("log-filter", "", &env, &[]).unwrap();
+        assert_eq!(config.log_filter, "info");
+    }
+
+    #[test]
+    fn reports_every_invalid_setting_at_once() {
+        let file = "listen_adress = \"0.0.0.0:80\"\nwebhook_timeout = 0";
+        let env = [
+            ("SUPABASE_URL", ""),
+            ("ALLOWED_ORIGINS", "example.com"),
+            ("TRASH_RETENTION_DAYS", "9999999999"),
+        ];
+        let flags = [
+            "--jwt-expired-in",
+            "9999999999999d",
+            "--cookie-same-site",
+            "none",
+        ];
+
+        let errors = errors(load("errors", file, &env, &flags));
+        let expected = [
+            "unknown setting `listen_adress`",
+            "ALLOWED_ORIGINS: invalid origin `example.com`",
+            "SUPABASE_URL: must be set",
+            "JWT_EXPIRED_IN: invalid value `9999999999999d` (must be at most 365d)",
+            "TRASH_RETENTION_DAYS: must be at most 36500",
+            "WEBHOOK_TIMEOUT: must be greater than zero",
+            "COOKIE_SAME_SITE: `none` requires COOKIE_SECURE=true",
+        ];
+        assert_eq!(errors.len(), expected.len(), "{:?}", errors);
+        for message in expected {
+            assert!(
+                errors.iter().any(|error| error.contains(message)),
+                "{} not in {:?}",
+                message,
+                errors
+            );
+        }
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("90s"), Ok(chrono::Duration::seconds(90)));
+        assert_eq!(parse_duration(" 12h "), Ok(chrono::Duration::hours(12)));
+        assert_eq!(parse_duration("365d"), Ok(chrono::Duration::days(365)));
+        assert!(parse_duration("366d").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("60").is_err());
+        assert!(parse_duration("1w").is_err());
+        assert!(parse_duration("-5m").is_err());
     }
 }
@@ -0,0 +1,154 @@
+//! Task events, emitted by the task handlers. Each one is queued for the webhooks
+//! of the owner of the task and fanned out in-process, through a broadcast channel,
+//! to the live event streams (`GET /api/events`) of every user who can see the task.
+//! The handlers hand the events over to a single worker, which sends them out one
+//! at a time, so they reach the streams and the webhooks in the order they happened.
+
+use postgrest::Postgrest;
+use serde_json::{json, Value};
+use tokio::{
+    sync::{broadcast, mpsc, oneshot},
+    task::JoinHandle,
+};
+use uuid::Uuid;
+
+use crate::{
+    handlers::webhooks_handlers::queue_task_event,
+    models::TaskModel,
+    permissions::get_task_audience,
+    webhooks::{payload, TaskEvent},
+    AppState,
+};
+
+/// Events kept for the streams that fall behind; a stream that misses some is told to resync.
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// A task event as it goes out to the live event streams.
+#[derive(Debug, Clone)]
+pub struct LiveEvent {
+    pub event: TaskEvent,
+    /// The users whose streams get the event.
+    pub user_ids: Vec<Uuid>,
+    /// `{ "task": ... }`, as in the responses of the task handlers.
+    pub data: Value,
+}
+
+pub type EventSender = broadcast::Sender<LiveEvent>;
+
+pub fn channel() -> EventSender {
+    broadcast::channel(EVENT_CHANNEL_CAPACITY).0
+}
+
+/// A task event waiting for the worker to send it out.
+#[derive(Debug)]
+pub struct EmittedEvent {
+    event: TaskEvent,
+    task: TaskModel,
+    actor_id: Uuid,
+}
+
+pub type EventEmitter = mpsc::UnboundedSender<EmittedEvent>;
+
+/// The worker that sends out the emitted events.
+pub struct EventWorker {
+    stop: oneshot::Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl EventWorker {
+    /// Lets the worker send out the events emitted so far, then stop.
+    /// Meant for once the server no longer handles requests.
+    pub fn stop(self) -> JoinHandle<()> {
+        let _ = self.stop.send(());
+        self.handle
+    }
+}
+
+/// Starts the worker that sends out the emitted events, in order.
+pub fn spawn_event_worker(client: Postgrest, events: EventSender) -> (EventEmitter, EventWorker) {
+    let (emitter, mut emitted) = mpsc::unbounded_channel::<EmittedEvent>();
+    let (stop, mut stopped) = oneshot::channel();
+
+    let handle = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                emitted_event = emitted.recv() => match emitted_event {
+                    Some(emitted_event) => send_task_event(&client, &events, emitted_event).await,
+                    None => break,
+                },
+                _ = &mut stopped => {
+                    emitted.close();
+                    while let Some(emitted_event) = emitted.recv().await {
+                        send_task_event(&client, &events, emitted_event).await;
+                    }
+                    break;
+                }
+            }
+        }
+    });
+
+    (emitter, EventWorker { stop, handle })
+}
+
+/// Emits an event about a task, done by `actor_id`. It is sent out in the background:
+/// a failure is logged and does not fail the request.
+pub fn emit_task_event(state: &AppState, event: TaskEvent, task: &TaskModel, actor_id: &Uuid) {
+    let emitted_event = EmittedEvent {
+        event,
+        task: task.clone(),
+        actor_id: *actor_id,
+    };
+    if state.emitter.send(emitted_event).is_err() {
+        println!(
+            "->> ❌ Sending the {} event failed: the event worker has stopped\n",
+            event.name()
+        );
+    }
+}
+
+async fn send_task_event(client: &Postgrest, events: &EventSender, emitted_event: EmittedEvent) {
+    let EmittedEvent {
+        event,
+        task,
+        actor_id,
+    } = emitted_event;
+    let data = json!({ "task": task });
+
+    // Live streams show completion as the update it is.
+    if event != TaskEvent::Completed && events.receiver_count() > 0 {
+        match get_task_audience(client, &task).await {
+            Ok(mut user_ids) => {
+                user_ids.push(actor_id);
+                // Sending only fails when nobody is listening.
+                let _ = events.send(LiveEvent {
+                    event,
+                    user_ids,
+                    data: data.clone(),
+                });
+            }
+            Err((_, err)) => println!(
+                "->> ❌ Sending the {} event failed: {}\n",
+                event.name(),
+                err.0["message"]
+            ),
+        }
+    }
+
+    let event_payload = payload(event.name(), &actor_id, data);
+    if let Err((_, err)) = queue_task_event(client, &task.user_id, event, event_payload).await {
+        println!(
+            "->> ❌ Queueing the {} event failed: {}\n",
+            event.name(),
+            err.0["message"]
+        );
+    }
+}
+
+/// Emits the events of an edit of a task: `task.updated`, plus
+/// `task.completed` when it was not completed before.
+pub fn emit_task_update(state: &AppState, task: &TaskModel, was_completed: bool, actor_id: &Uuid) {
+    emit_task_event(state, TaskEvent::Updated, task, actor_id);
+    if task.completed && !was_completed {
+        emit_task_event(state, TaskEvent::Completed, task, actor_id);
+    }
+}
@@ -0,0 +1,385 @@
+//! Serialization of tasks for export: JSON, CSV, Markdown and iCalendar (RFC 5545).
+//! Every format is written as a header, one piece per task and a footer,
+//! so that an export can be streamed without holding all the tasks in memory.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Local, Utc};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::models::TaskModel;
+
+/// Columns of a CSV export, in order.
+const CSV_COLUMNS: [&str; 17] = [
+    "id",
+    "title",
+    "description",
+    "completed",
+    "project_id",
+    "status_id",
+    "tags",
+    "due_date",
+    "recurrence",
+    "auto_complete",
+    "checklist_done",
+    "checklist_total",
+    "tracked_seconds",
+    "position",
+    "version",
+    "created_at",
+    "updated_at",
+];
+/// Longest line of an iCalendar file, in octets, before it has to be folded.
+const ICS_LINE_LENGTH: usize = 75;
+/// How long the event of a task lasts in a calendar, from its due date.
+const ICS_EVENT_DURATION: &str = "PT30M";
+
+#[derive(Debug, Error)]
+#[error("Unknown export format: {0}, expected 'json', 'csv', 'md' or 'ics'")]
+pub struct ExportFormatError(String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Markdown,
+    ICalendar,
+}
+
+impl FromStr for ExportFormat {
+    type Err = ExportFormatError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_ascii_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "ics" | "ical" => Ok(ExportFormat::ICalendar),
+            _ => Err(ExportFormatError(format.to_string())),
+        }
+    }
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::ICalendar => "text/calendar; charset=utf-8",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+            ExportFormat::ICalendar => "ics",
+        }
+    }
+
+    /// What comes before the first task.
+    pub fn header(self) -> String {
+        match self {
+            ExportFormat::Json => "[".to_string(),
+            ExportFormat::Csv => csv_row(CSV_COLUMNS.iter().map(|column| column.to_string())),
+            ExportFormat::Markdown => "# Tasks\n\n".to_string(),
+            ExportFormat::ICalendar => [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//rust-axum-supabase-api//Tasks//EN",
+                "CALSCALE:GREGORIAN",
+            ]
+            .iter()
+            .map(|line| format!("{}\r\n", line))
+            .collect(),
+        }
+    }
+
+    /// A task with the names of its tags; `first` is whether it is the first one written.
+    pub fn task(self, task: &TaskModel, tags: &[String], first: bool) -> String {
+        match self {
+            ExportFormat::Json => json_task(task, tags, first),
+            ExportFormat::Csv => csv_task(task, tags),
+            ExportFormat::Markdown => markdown_task(task, tags),
+            ExportFormat::ICalendar => ics_task(task, tags),
+        }
+    }
+
+    /// What comes after the last task.
+    pub fn footer(self) -> String {
+        match self {
+            ExportFormat::Json => "]\n".to_string(),
+            ExportFormat::Csv | ExportFormat::Markdown => String::new(),
+            ExportFormat::ICalendar => "END:VCALENDAR\r\n".to_string(),
+        }
+    }
+}
+
+/****** JSON ******/
+
+fn json_task(task: &TaskModel, tags: &[String], first: bool) -> String {
+    let mut value = json!(task);
+    if let Value::Object(fields) = &mut value {
+        fields.insert("tags".to_string(), json!(tags));
+    }
+
+    let separator = if first { "\n" } else { ",\n" };
+    format!("{}{}", separator, value)
+}
+
+/****** CSV ******/
+
+fn csv_task(task: &TaskModel, tags: &[String]) -> String {
+    let optional_date =
+        |date: Option<DateTime<Local>>| date.map(|date| date.to_rfc3339()).unwrap_or_default();
+
+    csv_row([
+        task.id.to_string(),
+        task.title.clone(),
+        task.description.clone(),
+        task.completed.to_string(),
+        task.project_id.map(|id| id.to_string()).unwrap_or_default(),
+        task.status_id.map(|id| id.to_string()).unwrap_or_default(),
+        tags.join(", "),
+        optional_date(task.due_date),
+        task.recurrence.clone().unwrap_or_default(),
+        task.auto_complete.to_string(),
+        task.progress.done.to_string(),
+        task.progress.total.to_string(),
+        task.tracked_seconds.to_string(),
+        task.position.to_string(),
+        task.version.to_string(),
+        task.created_at.to_rfc3339(),
+        optional_date(task.updated_at),
+    ])
+}
+
+/// A CSV record as in RFC 4180: fields with commas, quotes or line breaks are quoted.
+fn csv_row(fields: impl IntoIterator<Item = String>) -> String {
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field
+            }
+        })
+        .collect();
+
+    format!("{}\r\n", fields.join(","))
+}
+
+/****** Markdown ******/
+
+fn markdown_task(task: &TaskModel, tags: &[String]) -> String {
+    let mut line = format!(
+        "- [{}] {}",
+        if task.completed { "x" } else { " " },
+        task.title.replace('\n', " ")
+    );
+    if let Some(due_date) = task.due_date {
+        line.push_str(&format!(" (due {})", due_date.format("%Y-%m-%d %H:%M")));
+    }
+    if let Some(recurrence) = &task.recurrence {
+        line.push_str(&format!(" 🔁 `{}`", recurrence));
+    }
+    for tag in tags {
+        line.push_str(&format!(" #{}", tag.replace(' ', "-")));
+    }
+    line.push('\n');
+
+    // The description is indented so that it stays within the list item.
+    for description_line in task.description.lines() {
+        line.push_str(&format!("  {}\n", description_line));
+    }
+
+    line
+}
+
+/****** iCalendar ******/
+
+fn ics_task(task: &TaskModel, tags: &[String]) -> String {
+    let mut lines = vec!["BEGIN:VTODO".to_string()];
+    lines.extend(ics_task_properties(task, tags, &task.title));
+    if let Some(due_date) = task.due_date {
+        lines.push(format!("DUE:{}", ics_date(due_date)));
+    }
+    if task.completed {
+        lines.push("STATUS:COMPLETED".to_string());
+        lines.push("PERCENT-COMPLETE:100".to_string());
+    } else {
+        lines.push("STATUS:NEEDS-ACTION".to_string());
+    }
+    lines.push("END:VTODO".to_string());
+
+    lines.iter().map(|line| ics_fold(line)).collect()
+}
+
+/// A task as a VEVENT that starts when it is due, for the calendar apps
+/// that do not show to-dos; `None` when the task has no due date.
+pub fn ics_event(task: &TaskModel, tags: &[String]) -> Option<String> {
+    let due_date = task.due_date?;
+    let summary = if task.completed {
+        format!("✓ {}", task.title)
+    } else {
+        task.title.clone()
+    };
+
+    let mut lines = vec!["BEGIN:VEVENT".to_string()];
+    lines.extend(ics_task_properties(task, tags, &summary));
+    lines.push(format!("DTSTART:{}", ics_date(due_date)));
+    lines.push(format!("DURATION:{}", ICS_EVENT_DURATION));
+    lines.push("TRANSP:TRANSPARENT".to_string());
+    lines.push("END:VEVENT".to_string());
+
+    Some(lines.iter().map(|line| ics_fold(line)).collect())
+}
+
+/// The name that calendar apps give to a subscribed calendar.
+pub fn ics_calendar_name(name: &str) -> String {
+    ics_fold(&format!("X-WR-CALNAME:{}", ics_text(name)))
+}
+
+/// The properties that to-dos and events of a task have in common.
+fn ics_task_properties(task: &TaskModel, tags: &[String], summary: &str) -> Vec<String> {
+    let last_modified = task.updated_at.unwrap_or(task.created_at);
+    let mut lines = vec![
+        format!("UID:{}", task.id),
+        format!("DTSTAMP:{}", ics_date(last_modified)),
+        format!("CREATED:{}", ics_date(task.created_at)),
+        format!("LAST-MODIFIED:{}", ics_date(last_modified)),
+        format!("SUMMARY:{}", ics_text(summary)),
+    ];
+    if !task.description.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", ics_text(&task.description)));
+    }
+    if let Some(recurrence) = &task.recurrence {
+        lines.push(format!("RRULE:{}", recurrence));
+    }
+    if !tags.is_empty() {
+        let categories: Vec<String> = tags.iter().map(|tag| ics_text(tag)).collect();
+        lines.push(format!("CATEGORIES:{}", categories.join(",")));
+    }
+
+    lines
+}
+
+/// A date-time in UTC, e.g. `20230704T093000Z`.
+fn ics_date(date: DateTime<Local>) -> String {
+    date.with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Escapes a TEXT value: backslashes, semicolons, commas and line breaks.
+fn ics_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Ends a content line, folding it into lines of at most 75 octets
+/// that continue with a space, without splitting a character.
+fn ics_fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / ICS_LINE_LENGTH * 3 + 2);
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > ICS_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            // The space that starts the continuation counts towards its length.
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(title: &str, description: &str) -> TaskModel {
+        serde_json::from_value(json!({
+            "id": "5b1e7c9e-3f0a-4c2b-9d4e-0a1b2c3d4e5f",
+            "title": title,
+            "description": description,
+            "completed": false,
+            "user_id": "0f9e8d7c-6b5a-4938-8271-605f4e3d2c1b",
+            "project_id": null,
+            "due_date": "2024-06-01T09:30:00Z",
+            "created_at": "2024-05-01T08:00:00Z"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn quotes_csv_fields_only_when_needed() {
+        let row = csv_row([
+            "plain".to_string(),
+            "a, b".to_string(),
+            "say \"hi\"".to_string(),
+            "two\nlines".to_string(),
+            String::new(),
+        ]);
+
+        assert_eq!(row, "plain,\"a, b\",\"say \"\"hi\"\"\",\"two\nlines\",\r\n");
+    }
+
+    #[test]
+    fn writes_a_csv_record_per_task_matching_the_header() {
+        let row = csv_task(
+            &task("Buy milk, eggs", ""),
+            &["home".to_string(), "shop".to_string()],
+        );
+
+        assert!(row.starts_with("5b1e7c9e-3f0a-4c2b-9d4e-0a1b2c3d4e5f,\"Buy milk, eggs\",,false,"));
+        assert!(row.contains(",\"home, shop\","));
+        assert_eq!(
+            ExportFormat::Csv.header().trim_end().split(',').count(),
+            CSV_COLUMNS.len()
+        );
+    }
+
+    #[test]
+    fn escapes_ics_text_values() {
+        assert_eq!(ics_text("a\\b; c, d\r\ne\nf"), "a\\\\b\\; c\\, d\\ne\\nf");
+    }
+
+    #[test]
+    fn folds_long_ics_lines_without_splitting_characters() {
+        let line = format!("SUMMARY:{}", "é".repeat(50));
+        let folded = ics_fold(&line);
+
+        assert!(folded.ends_with("\r\n"));
+        for part in folded.trim_end_matches("\r\n").split("\r\n") {
+            assert!(part.len() <= ICS_LINE_LENGTH, "{:?}", part);
+        }
+        assert_eq!(folded.replace("\r\n ", "").trim_end(), line);
+        assert_eq!(ics_fold("DUE:20240601T093000Z"), "DUE:20240601T093000Z\r\n");
+    }
+
+    #[test]
+    fn writes_a_todo_with_escaped_properties() {
+        let todo = ics_task(
+            &task("Call Bob; Alice", "Line one\nLine two"),
+            &["a,b".to_string()],
+        );
+
+        assert!(todo.starts_with("BEGIN:VTODO\r\n"));
+        assert!(todo.contains("\r\nSUMMARY:Call Bob\\; Alice\r\n"));
+        assert!(todo.contains("\r\nDESCRIPTION:Line one\\nLine two\r\n"));
+        assert!(todo.contains("\r\nCATEGORIES:a\\,b\r\n"));
+        assert!(todo.contains("\r\nDUE:20240601T093000Z\r\n"));
+        assert!(todo.ends_with("STATUS:NEEDS-ACTION\r\nEND:VTODO\r\n"));
+    }
+}
@@ -0,0 +1,561 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{multipart::Field, Multipart, Path, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE, X_CONTENT_TYPE_OPTIONS},
+        StatusCode,
+    },
+    response::IntoResponse,
+    Extension, Json,
+};
+use postgrest::Postgrest;
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    models::{AttachmentModel, Role, UserModel},
+    permissions::get_task_with_role,
+    schemas::{CreateAttachmentSchema, InsertAttachmentSchema},
+    storage::{BlobStore, StorageError},
+    AppState,
+};
+
+/// Name of the multipart field that carries the uploaded file.
+const FILE_FIELD: &str = "file";
+
+/// Axum Route Handler to Upload an Attachment to a Task.
+/// The body is a multipart form whose `file` field holds the file.
+pub async fn upload_attachment_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    // A read lock, so that slow uploads do not hold back every other request.
+    let state = data.read().await;
+    let client = &state.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let mut field = loop {
+        let field = multipart.next_field().await.map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Invalid multipart body: {}", err)
+            });
+
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        })?;
+
+        match field {
+            Some(field) if field.name() == Some(FILE_FIELD) => break field,
+            Some(_) => continue,
+            None => {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": format!("The '{}' field with the file is missing", FILE_FIELD)
+                });
+                return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+            }
+        }
+    };
+
+    let file_name = sanitize_file_name(field.file_name().unwrap_or_default());
+    let content_type = field
+        .content_type()
+        .unwrap_or("application/octet-stream")
+        .to_string();
+
+    if !state.env.attachment_mime_types.contains(&content_type) {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Files of type {} are not allowed", content_type)
+        });
+        return Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, Json(error_response)));
+    }
+
+    let bytes = read_field(&mut field, state.env.attachment_max_size).await?;
+
+    if bytes.is_empty() {
+        let error_response = json!({
+            "status": "fail",
+            "message": "The uploaded file is empty"
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    // Checked up front so that a file over the quota is not stored at all;
+    // the insert below checks it again against concurrent uploads.
+    let used = get_used_quota(client, &user.id).await?;
+    if used + bytes.len() > state.env.attachment_quota {
+        return Err(quota_error(used, state.env.attachment_quota));
+    }
+
+    let attachment_id = Uuid::new_v4();
+    let storage_key = format!("{}/{}", id, attachment_id);
+
+    state
+        .blobs
+        .put(&storage_key, &bytes)
+        .await
+        .map_err(storage_error)?;
+
+    let attachment_schema = CreateAttachmentSchema {
+        id: attachment_id.to_string(),
+        file_name,
+        content_type,
+        size: bytes.len() as i64,
+        storage_key: storage_key.clone(),
+        task_id: id.to_string(),
+        user_id: user.id.to_string(),
+    };
+
+    let inserted = insert_attachment(client, &attachment_schema, state.env.attachment_quota).await;
+    let attachment = match inserted {
+        Ok(Some(attachment)) => attachment,
+        Ok(None) => {
+            let _ = state.blobs.delete(&storage_key).await;
+            let used = get_used_quota(client, &user.id).await?;
+            return Err(quota_error(used, state.env.attachment_quota));
+        }
+        Err(err) => {
+            // Without its metadata the blob could never be reached again.
+            let _ = state.blobs.delete(&storage_key).await;
+            return Err(err);
+        }
+    };
+
+    let created_attachment = json!({ "status": "success", "data": json!({
+        "attachment": json!(attachment)
+    })});
+
+    Ok((StatusCode::CREATED, Json(created_attachment)))
+}
+
+/// Axum Route Handler to List the Attachments of a Task, newest first.
+pub async fn get_attachments_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+
+    let response = client
+        .from("attachments")
+        .select("*")
+        .eq("task_id", id.to_string())
+        .order("created_at.desc")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let attachments_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let attachments_data: Vec<AttachmentModel> = serde_json::from_str(&attachments_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let retrieved_attachments = json!({ "status": "success", "data": json!({
+        "attachments": json!(attachments_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_attachments)))
+}
+
+/// Axum Route Handler to Download the content of an Attachment.
+pub async fn download_attachment_handler(
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.read().await;
+    let client = &state.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+    let attachment = get_single_attachment(client, &id, &attachment_id).await?;
+
+    let bytes = state
+        .blobs
+        .get(&attachment.storage_key)
+        .await
+        .map_err(storage_error)?;
+
+    let headers = [
+        (CONTENT_TYPE, attachment.content_type),
+        (
+            CONTENT_DISPOSITION,
+            content_disposition(&attachment.file_name),
+        ),
+        (X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+    ];
+
+    Ok((StatusCode::OK, headers, bytes))
+}
+
+/// Axum Route Handler to Delete an Attachment and its content.
+pub async fn delete_attachment_handler(
+    Path((id, attachment_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+    let attachment = get_single_attachment(client, &id, &attachment_id).await?;
+
+    client
+        .from("attachments")
+        .delete()
+        .eq("id", attachment_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    state
+        .blobs
+        .delete(&attachment.storage_key)
+        .await
+        .map_err(storage_error)?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/****** Helpers ******/
+
+/// Gets the storage keys of all the attachments of the given tasks.
+/// Called before deleting tasks, whose attachment rows go with them by cascade.
+pub async fn get_attachment_keys(
+    client: &Postgrest,
+    task_ids: &[String],
+) -> Result<Vec<String>, (StatusCode, Json<Value>)> {
+    if task_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let response = client
+        .from("attachments")
+        .select("*")
+        .in_("task_id", task_ids)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let attachments_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let attachments_data: Vec<AttachmentModel> = serde_json::from_str(&attachments_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(attachments_data
+        .into_iter()
+        .map(|attachment| attachment.storage_key)
+        .collect())
+}
+
+/// Removes blobs whose metadata is already gone. Failures are ignored:
+/// the rows are deleted by then, so an orphaned blob is the worst outcome.
+pub async fn delete_blobs(blobs: &dyn BlobStore, keys: &[String]) {
+    for key in keys {
+        let _ = blobs.delete(key).await;
+    }
+}
+
+/// Gets an attachment of the task.
+async fn get_single_attachment(
+    client: &Postgrest,
+    task_id: &Uuid,
+    attachment_id: &Uuid,
+) -> Result<AttachmentModel, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("attachments")
+        .select("*")
+        .eq("id", attachment_id.to_string())
+        .eq("task_id", task_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let attachment_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let attachment_data: Vec<AttachmentModel> = serde_json::from_str(&attachment_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    attachment_data.into_iter().next().ok_or_else(|| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Attachment with ID: {} not found", attachment_id)
+        });
+
+        (StatusCode::NOT_FOUND, Json(error_response))
+    })
+}
+
+/// Records an uploaded attachment through the `insert_attachment` database function,
+/// which checks the quota of the uploader in the same transaction.
+/// Returns `None` when the attachment does not fit in it.
+async fn insert_attachment(
+    client: &Postgrest,
+    attachment_schema: &CreateAttachmentSchema,
+    quota: usize,
+) -> Result<Option<AttachmentModel>, (StatusCode, Json<Value>)> {
+    let insert_schema = InsertAttachmentSchema {
+        p_attachment: attachment_schema,
+        p_quota: i64::try_from(quota).unwrap_or(i64::MAX),
+    };
+
+    let response = client
+        .rpc("insert_attachment", json!(insert_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let attachment_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let attachment_data: Vec<AttachmentModel> = serde_json::from_str(&attachment_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(attachment_data.into_iter().next())
+}
+
+/// Sums the sizes of all the attachments uploaded by the user.
+async fn get_used_quota(
+    client: &Postgrest,
+    user_id: &Uuid,
+) -> Result<usize, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("attachments")
+        .select("*")
+        .eq("user_id", user_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let attachments_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let attachments_data: Vec<AttachmentModel> = serde_json::from_str(&attachments_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(attachments_data
+        .iter()
+        .map(|attachment| attachment.size as usize)
+        .sum())
+}
+
+fn quota_error(used: usize, quota: usize) -> (StatusCode, Json<Value>) {
+    let error_response = json!({
+        "status": "fail",
+        "message": format!("Attachment quota exceeded: {} of {} bytes used", used, quota)
+    });
+
+    (StatusCode::PAYLOAD_TOO_LARGE, Json(error_response))
+}
+
+/// Reads the content of a multipart field, stopping as soon as it grows past `max_size`.
+async fn read_field(
+    field: &mut Field<'_>,
+    max_size: usize,
+) -> Result<Vec<u8>, (StatusCode, Json<Value>)> {
+    let mut bytes = Vec::new();
+
+    loop {
+        let chunk = field.chunk().await.map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error reading the uploaded file: {}", err)
+            });
+
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        })?;
+
+        let Some(chunk) = chunk else {
+            return Ok(bytes);
+        };
+
+        if bytes.len() + chunk.len() > max_size {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Attachments can be at most {} bytes", max_size)
+            });
+            return Err((StatusCode::PAYLOAD_TOO_LARGE, Json(error_response)));
+        }
+
+        bytes.extend_from_slice(&chunk);
+    }
+}
+
+/// Keeps only the last path segment of an uploaded file name and drops control characters.
+fn sanitize_file_name(file_name: &str) -> String {
+    let name: String = file_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    let name = name.trim();
+
+    if name.is_empty() {
+        "attachment".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Builds the Content-Disposition of a download: a plain ASCII `filename`
+/// for old clients plus the exact name, percent-encoded, in `filename*`.
+fn content_disposition(file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_graphic() && c != '"' && c != '\\' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let encoded: String = file_name
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{:02X}", b)
+            }
+        })
+        .collect();
+
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        fallback, encoded
+    )
+}
+
+fn storage_error(err: StorageError) -> (StatusCode, Json<Value>) {
+    let status = match err {
+        StorageError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    let error_response = json!({
+        "status": "fail",
+        "message": format!("Storage error: {}", err)
+    });
+
+    (status, Json(error_response))
+}
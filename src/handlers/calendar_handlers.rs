@@ -0,0 +1,280 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{
+        header::{CACHE_CONTROL, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::IntoResponse,
+    Extension, Json,
+};
+use postgrest::Postgrest;
+use rand_core::{OsRng, RngCore};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    export::{ics_calendar_name, ics_event, ExportFormat},
+    handlers::{export_handlers::get_tag_names, tasks_handlers::filter_tasks},
+    models::{TaskModel, UserModel},
+    schemas::{CalendarFeedQuery, CalendarTokenSchema, FilterTasksQuery},
+    AppState,
+};
+
+/// Number of random bytes in a calendar feed token.
+const CALENDAR_TOKEN_BYTES: usize = 32;
+
+/****** Feed token ******/
+
+/// Axum Route Handler to Fetch the calendar feed token of the logged-in user;
+/// `null` while the feed is turned off.
+pub async fn get_calendar_token_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let response = client
+        .from("users")
+        .select("calendar_token")
+        .eq("id", user.id.to_string())
+        .single()
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let token_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let token_data: CalendarTokenSchema = serde_json::from_str(&token_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let retrieved_token = json!({ "status": "success", "data": json!({
+        "calendar_token": token_data.calendar_token
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_token)))
+}
+
+/// Axum Route Handler to Create the calendar feed token of the logged-in user,
+/// or to replace it so that the old feed address stops working.
+pub async fn regenerate_calendar_token_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let mut bytes = [0u8; CALENDAR_TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    let token: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+    set_calendar_token(client, &user.id, Some(token.clone())).await?;
+
+    let created_token = json!({ "status": "success", "data": json!({
+        "calendar_token": token
+    })});
+
+    Ok((StatusCode::CREATED, Json(created_token)))
+}
+
+/// Axum Route Handler to Turn off the calendar feed of the logged-in user.
+pub async fn delete_calendar_token_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    set_calendar_token(client, &user.id, None).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/****** Feed ******/
+
+/// Axum Route Handler to Fetch the calendar feed of a user, `<token>.ics`.
+/// It needs no login, so that calendar apps can poll it: the token is the secret.
+/// Only the tasks with a due date are in it.
+pub async fn calendar_feed_handler(
+    Path(feed): Path<String>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Query(query): Query<CalendarFeedQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let not_found = || {
+        let error_response = json!({
+            "status": "fail",
+            "message": "Calendar feed not found"
+        });
+
+        (StatusCode::NOT_FOUND, Json(error_response))
+    };
+
+    let token = feed.strip_suffix(".ics").ok_or_else(not_found)?;
+    let events = match query.items.as_deref() {
+        None | Some("events") => true,
+        Some("todos") => false,
+        Some(items) => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Unknown items: {}, expected 'events' or 'todos'", items)
+            });
+            return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+        }
+    };
+
+    let user = get_feed_owner(client, token).await?.ok_or_else(not_found)?;
+
+    let filter = FilterTasksQuery {
+        tags: None,
+        tag_mode: None,
+        project: query.project.clone(),
+    };
+    let mut request = filter_tasks(client, &user.id, &filter)
+        .await?
+        .not("is", "due_date", "null");
+    if !query.completed.unwrap_or(false) {
+        request = request.eq("completed", "false");
+    }
+
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tasks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tasks_data: Vec<TaskModel> = serde_json::from_str(&tasks_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tag_names = get_tag_names(client, &user.id).await?;
+
+    let mut calendar = ExportFormat::ICalendar.header();
+    calendar.push_str(&ics_calendar_name(&format!("Tasks of {}", user.username)));
+    for task in &tasks_data {
+        let tags: Vec<String> = task
+            .tag_ids
+            .iter()
+            .filter_map(|tag_id| tag_names.get(tag_id).cloned())
+            .collect();
+
+        if events {
+            calendar.push_str(&ics_event(task, &tags).unwrap_or_default());
+        } else {
+            calendar.push_str(&ExportFormat::ICalendar.task(task, &tags, false));
+        }
+    }
+    calendar.push_str(&ExportFormat::ICalendar.footer());
+
+    let headers = [
+        (CONTENT_TYPE, ExportFormat::ICalendar.content_type()),
+        // The feed is personal: shared caches must not keep it.
+        (CACHE_CONTROL, "private, max-age=300"),
+    ];
+
+    Ok((StatusCode::OK, headers, calendar))
+}
+
+/// The user whose calendar feed has the token, if any.
+async fn get_feed_owner(
+    client: &Postgrest,
+    token: &str,
+) -> Result<Option<UserModel>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("users")
+        .select("*")
+        .eq("calendar_token", token)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let user_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let user_data: Vec<UserModel> = serde_json::from_str(&user_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    Ok(user_data.into_iter().next())
+}
+
+async fn set_calendar_token(
+    client: &Postgrest,
+    user_id: &Uuid,
+    calendar_token: Option<String>,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let token_schema = CalendarTokenSchema { calendar_token };
+
+    client
+        .from("users")
+        .update(json!(token_schema).to_string())
+        .eq("id", user_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,329 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::Local;
+use postgrest::Postgrest;
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    models::{CommentModel, Role, UserModel},
+    permissions::get_task_with_role,
+    schemas::{CommentsQuery, CreateCommentSchema, UpdateCommentSchema},
+    validators::{CommentEntry, ValidatedRequest},
+    AppState,
+};
+
+/// Columns selected for a comment: its own columns plus its author.
+const COMMENT_COLUMNS: &str = "*, author:users(id, username, email, created_at)";
+/// Number of comments per page when the request does not say otherwise.
+const DEFAULT_COMMENTS_LIMIT: usize = 20;
+/// Largest number of comments per page that can be requested.
+const MAX_COMMENTS_LIMIT: usize = 100;
+
+/// Axum Route Handler to Add a Comment to a Task.
+/// Everyone who can see the task can take part in its discussion.
+pub async fn create_comment_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<CommentEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+
+    let comment_schema = CreateCommentSchema {
+        body: body.body,
+        task_id: id.to_string(),
+        user_id: user.id.to_string(),
+    };
+
+    let response = client
+        .from("comments")
+        .select(COMMENT_COLUMNS)
+        .insert(json!(comment_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let comment_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let comment_data: Vec<CommentModel> =
+        serde_json::from_str(&comment_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match comment_data.first() {
+        Some(comment) => {
+            let created_comment = json!({ "status": "success", "data": json!({
+                "comment": json!(comment)
+            })});
+
+            Ok((StatusCode::CREATED, Json(created_comment)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while creating the comment"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Fetch a Page of the Comments of a Task, oldest first.
+pub async fn get_comments_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(query): Query<CommentsQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+
+    let page = query.page.unwrap_or(1).max(1);
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_COMMENTS_LIMIT)
+        .clamp(1, MAX_COMMENTS_LIMIT);
+    let offset = (page - 1) * limit;
+
+    // One more comment than requested tells us whether there is a next page.
+    let response = client
+        .from("comments")
+        .select(COMMENT_COLUMNS)
+        .eq("task_id", id.to_string())
+        .order("created_at.asc")
+        .range(offset, offset + limit)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let comments_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let mut comments_data: Vec<CommentModel> =
+        serde_json::from_str(&comments_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let has_more = comments_data.len() > limit;
+    comments_data.truncate(limit);
+
+    let retrieved_comments = json!({ "status": "success", "data": json!({
+        "comments": json!(comments_data),
+        "page": page,
+        "limit": limit,
+        "has_more": has_more
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_comments)))
+}
+
+/// Axum Route Handler to Edit a Comment. Only its author can edit it.
+pub async fn update_comment_handler(
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<CommentEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+    get_own_comment(client, &id, &comment_id, &user.id).await?;
+
+    let comment_schema = UpdateCommentSchema {
+        body: body.body,
+        updated_at: Local::now(),
+    };
+
+    let response = client
+        .from("comments")
+        .select(COMMENT_COLUMNS)
+        .update(json!(comment_schema).to_string())
+        .eq("id", comment_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let comment_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let comment_data: Vec<CommentModel> =
+        serde_json::from_str(&comment_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match comment_data.first() {
+        Some(comment) => {
+            let updated_comment = json!({ "status": "success", "data": json!({
+                "comment": json!(comment)
+            })});
+
+            Ok((StatusCode::OK, Json(updated_comment)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while updating the comment"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Delete a Comment. Only its author can delete it.
+pub async fn delete_comment_handler(
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+    get_own_comment(client, &id, &comment_id, &user.id).await?;
+
+    client
+        .from("comments")
+        .delete()
+        .eq("id", comment_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/****** Helpers ******/
+
+/// Gets a comment of the task, ensuring that the user is its author.
+async fn get_own_comment(
+    client: &Postgrest,
+    task_id: &Uuid,
+    comment_id: &Uuid,
+    user_id: &Uuid,
+) -> Result<CommentModel, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("comments")
+        .select("*")
+        .eq("id", comment_id.to_string())
+        .eq("task_id", task_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let comment_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let comment_data: Vec<CommentModel> =
+        serde_json::from_str(&comment_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let comment = comment_data.into_iter().next().ok_or_else(|| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Comment with ID: {} not found", comment_id)
+        });
+
+        (StatusCode::NOT_FOUND, Json(error_response))
+    })?;
+
+    if &comment.user_id != user_id {
+        let error_response = json!({
+            "status": "fail",
+            "message": "You can only change your own comments"
+        });
+        return Err((StatusCode::FORBIDDEN, Json(error_response)));
+    }
+
+    Ok(comment)
+}
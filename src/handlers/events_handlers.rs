@@ -0,0 +1,62 @@
+use std::{sync::Arc, time::Duration};
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    Extension, Json,
+};
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tokio_stream::{
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
+    StreamExt,
+};
+
+use crate::{models::UserModel, AppState};
+
+/// Event sent to a stream that fell behind and missed some events:
+/// the client has to fetch its tasks again.
+const RESYNC_EVENT: &str = "resync";
+
+/// Axum Route Handler of the live event stream of the logged-in user (Server-Sent Events).
+/// Every change to a task they can see comes as a `task.created`, `task.updated`
+/// or `task.deleted` event whose data is `{ "task": ... }`; a comment is sent
+/// as a heartbeat when there is nothing else to send. The stream ends when the
+/// server shuts down.
+pub async fn events_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let (receiver, heartbeat, shutdown) = {
+        let state = data.read().await;
+        (
+            state.events.subscribe(),
+            Duration::from_secs(state.env.events_heartbeat_interval),
+            state.shutdown.clone(),
+        )
+    };
+
+    let stream = BroadcastStream::new(receiver).filter_map(move |message| match message {
+        Ok(event) if event.user_ids.contains(&user.id) => Some(
+            Event::default()
+                .event(event.event.name())
+                .json_data(&event.data),
+        ),
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(_)) => {
+            Some(Ok(Event::default().event(RESYNC_EVENT).data("{}")))
+        }
+    });
+    // The stream ends when the server shuts down, or it would hold the shutdown
+    // up until the drain timeout; the browser reconnects to another instance.
+    let stream = stream
+        .map(Some)
+        .merge(shutdown.stream().map(|_| None))
+        .map_while(|event| event);
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::new().interval(heartbeat).text("heartbeat")))
+}
@@ -0,0 +1,199 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    body::{boxed, Body, Bytes},
+    extract::{Query, State},
+    http::{
+        header::{CONTENT_DISPOSITION, CONTENT_TYPE},
+        StatusCode,
+    },
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::Local;
+use postgrest::{Builder, Postgrest};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    export::ExportFormat,
+    handlers::tasks_handlers::filter_tasks,
+    models::{TagModel, TaskModel, UserModel},
+    schemas::{ExportTasksQuery, FilterTasksQuery},
+    AppState,
+};
+
+/// Number of tasks read from the database at a time while exporting.
+const EXPORT_PAGE_SIZE: usize = 500;
+
+/// Axum Route Handler to Export the tasks of the logged-in user as a file.
+/// The tasks are read page by page and streamed as they come.
+pub async fn export_tasks_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(export): Query<ExportTasksQuery>,
+    Query(filter): Query<FilterTasksQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let format = export.format.as_deref().unwrap_or("json");
+    let format: ExportFormat = format.parse().map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("{}", err)
+        });
+
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })?;
+
+    // The client is cloned so that the lock is not held while the export is streamed.
+    let client = data.read().await.client.clone();
+
+    let mut request = filter_tasks(&client, &user.id, &filter).await?;
+    if let Some(completed) = export.completed {
+        request = request.eq("completed", completed.to_string());
+    }
+    let tag_names = get_tag_names(&client, &user.id).await?;
+
+    // The first page is read before answering, so that errors still get a JSON response.
+    let first_page = get_tasks_page(request.clone(), 0).await?;
+
+    let (mut sender, body) = Body::channel();
+    tokio::spawn(async move {
+        let mut chunk = format.header();
+        let mut page = first_page;
+        let mut offset = 0;
+        let mut first = true;
+
+        loop {
+            for task in &page {
+                let tags: Vec<String> = task
+                    .tag_ids
+                    .iter()
+                    .filter_map(|tag_id| tag_names.get(tag_id).cloned())
+                    .collect();
+                chunk.push_str(&format.task(task, &tags, first));
+                first = false;
+            }
+
+            // The client went away.
+            if sender.send_data(Bytes::from(chunk)).await.is_err() {
+                return;
+            }
+            chunk = String::new();
+
+            if page.len() < EXPORT_PAGE_SIZE {
+                break;
+            }
+            offset += EXPORT_PAGE_SIZE;
+            page = match get_tasks_page(request.clone(), offset).await {
+                Ok(page) => page,
+                Err((_, err)) => {
+                    println!("->> ❌ Exporting tasks failed: {}\n", err.0["message"]);
+                    // The download ends with an error instead of a truncated file.
+                    sender.abort();
+                    return;
+                }
+            };
+        }
+
+        let _ = sender.send_data(Bytes::from(format.footer())).await;
+    });
+
+    let file_name = format!(
+        "tasks-{}.{}",
+        Local::now().format("%Y-%m-%d"),
+        format.extension()
+    );
+    let headers = [
+        (CONTENT_TYPE, format.content_type().to_string()),
+        (
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}\"", file_name),
+        ),
+    ];
+
+    Ok((StatusCode::OK, headers, boxed(body)))
+}
+
+/// Reads the tasks of a request from `offset` on, at most a page of them.
+async fn get_tasks_page(
+    request: Builder,
+    offset: usize,
+) -> Result<Vec<TaskModel>, (StatusCode, Json<Value>)> {
+    let response = request
+        .range(offset, offset + EXPORT_PAGE_SIZE - 1)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tasks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tasks_data: Vec<TaskModel> = serde_json::from_str(&tasks_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    Ok(tasks_data)
+}
+
+/// Names of the tags of the user by their id.
+pub async fn get_tag_names(
+    client: &Postgrest,
+    user_id: &Uuid,
+) -> Result<HashMap<Uuid, String>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("tags")
+        .select("*")
+        .eq("user_id", user_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tags_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tags_data: Vec<TagModel> = serde_json::from_str(&tags_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    Ok(tags_data
+        .into_iter()
+        .map(|tag| (tag.id, tag.name))
+        .collect())
+}
@@ -0,0 +1,327 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use axum::{
+    extract::{Multipart, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use postgrest::Postgrest;
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    events::emit_task_event,
+    handlers::tasks_handlers::{normalize_recurrence, TASK_COLUMNS},
+    import::{self, ImportFormat, ImportedTask},
+    models::{Role, TaskModel, UserModel},
+    permissions::get_project_with_role,
+    schemas::{ImportTasksQuery, ImportTasksSchema},
+    validators::{TagEntry, TaskEntry},
+    webhooks::TaskEvent,
+    AppState,
+};
+
+/// Name of the multipart field that carries the imported file.
+const FILE_FIELD: &str = "file";
+/// Name of the multipart field that maps the columns of a CSV file, as a JSON
+/// object from the fields of a task to the headers, e.g. `{"title": "Task name"}`.
+const MAPPING_FIELD: &str = "mapping";
+/// Number of import keys looked up at a time, so that the query string stays short.
+const IMPORT_KEYS_BATCH: usize = 100;
+
+/// Axum Route Handler to Import Tasks from a file.
+/// Every record is checked as a new task would be. Unless it is a dry run,
+/// the tasks are only imported when none has errors, and all at once.
+/// Records imported before are skipped.
+pub async fn import_tasks_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(query): Query<ImportTasksQuery>,
+    mut multipart: Multipart,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let mut file = None;
+    let mut mapping = None;
+
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        match field.name() {
+            Some(FILE_FIELD) => {
+                let file_name = field.file_name().unwrap_or_default().to_string();
+                let content = field.text().await.map_err(multipart_error)?;
+                file = Some((file_name, content));
+            }
+            Some(MAPPING_FIELD) => {
+                let content = field.text().await.map_err(multipart_error)?;
+                let columns: HashMap<String, String> =
+                    serde_json::from_str(&content).map_err(|err| {
+                        let error_response = json!({
+                            "status": "fail",
+                            "message": format!("The mapping must be a JSON object from fields to columns: {}", err)
+                        });
+
+                        (StatusCode::BAD_REQUEST, Json(error_response))
+                    })?;
+                mapping = Some(columns);
+            }
+            _ => continue,
+        }
+    }
+
+    let Some((file_name, content)) = file else {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("The '{}' field with the file is missing", FILE_FIELD)
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    };
+
+    let format = match query.format.as_deref() {
+        Some(format) => format.parse().map_err(import_error)?,
+        None => ImportFormat::from_file_name(&file_name).ok_or_else(|| {
+            let error_response = json!({
+                "status": "fail",
+                "message": "The format of the file is unknown, expected 'csv', 'json', 'todotxt' or 'ics'"
+            });
+
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        })?,
+    };
+    let rows = import::parse(format, &content, mapping.as_ref()).map_err(import_error)?;
+
+    let state = data.write().await;
+    let client = &state.client;
+
+    if let Some(project_id) = &query.project_id {
+        get_project_with_role(client, project_id, &user.id, Role::Editor).await?;
+    }
+
+    let import_keys: Vec<&str> = rows
+        .iter()
+        .filter_map(|row| row.task.as_ref().ok())
+        .map(|task| task.import_key.as_str())
+        .collect();
+    let imported_keys = get_imported_keys(client, &user.id, &import_keys).await?;
+
+    let total = rows.len();
+    let mut tasks: Vec<ImportedTask> = Vec::new();
+    let mut duplicates: Vec<usize> = Vec::new();
+    let mut errors: Vec<Value> = Vec::new();
+    let mut seen_keys: HashSet<String> = HashSet::new();
+
+    for row in rows {
+        let mut task = match row.task {
+            Ok(task) => task,
+            Err(message) => {
+                errors.push(json!({ "row": row.row, "messages": [message] }));
+                continue;
+            }
+        };
+
+        if imported_keys.contains(&task.import_key) || !seen_keys.insert(task.import_key.clone()) {
+            duplicates.push(row.row);
+            continue;
+        }
+
+        let entry = TaskEntry {
+            title: task.title.clone(),
+            description: task.description.clone(),
+            tag_ids: Vec::new(),
+            project_id: query.project_id,
+            auto_complete: task.auto_complete,
+            due_date: task.due_date,
+            recurrence: task.recurrence.clone(),
+        };
+        let mut messages: Vec<String> = match entry.validate() {
+            Ok(()) => Vec::new(),
+            Err(err) => err.to_string().lines().map(String::from).collect(),
+        };
+        // Missing tags are created by the import, so their names are checked as a new tag's.
+        for tag in &task.tags {
+            let tag_entry = TagEntry {
+                name: tag.clone(),
+                color: None,
+            };
+            if let Err(err) = tag_entry.validate() {
+                messages.extend(
+                    err.to_string()
+                        .lines()
+                        .map(|line| format!("tags: '{}': {}", tag, line)),
+                );
+            }
+        }
+        if !messages.is_empty() {
+            errors.push(json!({ "row": row.row, "messages": messages }));
+            continue;
+        }
+
+        task.recurrence = task.recurrence.map(normalize_recurrence);
+        tasks.push(task);
+    }
+
+    let report = json!({
+        "total": total,
+        "valid": tasks.len(),
+        "duplicates": duplicates,
+        "errors": errors
+    });
+
+    if query.dry_run.unwrap_or(false) {
+        let dry_run = json!({ "status": "success", "data": json!({
+            "dry_run": true,
+            "report": report,
+            "tasks": json!(tasks)
+        })});
+
+        return Ok((StatusCode::OK, Json(dry_run)));
+    }
+
+    if !errors.is_empty() {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("{} of the rows have errors, nothing was imported", errors.len()),
+            "data": json!({ "report": report })
+        });
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, Json(error_response)));
+    }
+
+    let imported = if tasks.is_empty() {
+        Vec::new()
+    } else {
+        import_tasks(client, &user.id, query.project_id.as_ref(), tasks).await?
+    };
+    for task in &imported {
+        emit_task_event(&state, TaskEvent::Created, task, &user.id);
+    }
+
+    let import_result = json!({ "status": "success", "data": json!({
+        "imported": imported.len(),
+        "report": report
+    })});
+
+    Ok((StatusCode::CREATED, Json(import_result)))
+}
+
+/// Adds the tasks in a single transaction through the `import_tasks` database
+/// function, and returns those that were added.
+async fn import_tasks(
+    client: &Postgrest,
+    user_id: &Uuid,
+    project_id: Option<&Uuid>,
+    tasks: Vec<ImportedTask>,
+) -> Result<Vec<TaskModel>, (StatusCode, Json<Value>)> {
+    let import_schema = ImportTasksSchema {
+        p_user_id: user_id.to_string(),
+        p_project_id: project_id.map(|project_id| project_id.to_string()),
+        p_tasks: tasks,
+    };
+
+    let response = client
+        .rpc("import_tasks", json!(import_schema).to_string())
+        .select(TASK_COLUMNS)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let import_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let imported_data: Vec<TaskModel> = serde_json::from_str(&import_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    Ok(imported_data)
+}
+
+/// Which of the import keys belong to tasks the user has already imported.
+async fn get_imported_keys(
+    client: &Postgrest,
+    user_id: &Uuid,
+    import_keys: &[&str],
+) -> Result<HashSet<String>, (StatusCode, Json<Value>)> {
+    let mut imported_keys = HashSet::new();
+
+    for batch in import_keys.chunks(IMPORT_KEYS_BATCH) {
+        let response = client
+            .from("tasks")
+            .select("import_key")
+            .eq("user_id", user_id.to_string())
+            .in_("import_key", batch)
+            .execute()
+            .await
+            .map_err(|err| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", err)
+                });
+
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+        let keys_response = response.text().await.map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error parsing json response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+        let keys_data: Vec<Value> = serde_json::from_str(&keys_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+        imported_keys.extend(
+            keys_data
+                .iter()
+                .filter_map(|row| row["import_key"].as_str().map(String::from)),
+        );
+    }
+
+    Ok(imported_keys)
+}
+
+fn multipart_error(err: impl std::fmt::Display) -> (StatusCode, Json<Value>) {
+    let error_response = json!({
+        "status": "fail",
+        "message": format!("Invalid multipart body: {}", err)
+    });
+
+    (StatusCode::BAD_REQUEST, Json(error_response))
+}
+
+fn import_error(err: import::ImportError) -> (StatusCode, Json<Value>) {
+    let error_response = json!({
+        "status": "fail",
+        "message": format!("{}", err)
+    });
+
+    (StatusCode::BAD_REQUEST, Json(error_response))
+}
@@ -1,2 +1,17 @@
+pub mod attachments_handlers;
+pub mod calendar_handlers;
+pub mod comments_handlers;
+pub mod events_handlers;
+pub mod export_handlers;
+pub mod import_handlers;
+pub mod projects_handlers;
+pub mod revisions_handlers;
+pub mod shares_handlers;
+pub mod static_handlers;
+pub mod statuses_handlers;
+pub mod tags_handlers;
+pub mod task_items_handlers;
 pub mod tasks_handlers;
+pub mod time_entries_handlers;
 pub mod users_handlers;
+pub mod webhooks_handlers;
@@ -0,0 +1,377 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::Local;
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    models::{ProjectModel, Role, UserModel},
+    permissions::get_project_with_role,
+    schemas::{CreateProjectSchema, DeleteProjectQuery, ProjectsQuery, UpdateProjectSchema},
+    validators::{ProjectEntry, UpdateProjectEntry, ValidatedRequest},
+    AppState,
+};
+
+/// Color assigned to a project when the request does not provide one.
+const DEFAULT_PROJECT_COLOR: &str = "#d97706";
+/// Icon assigned to a project when the request does not provide one.
+const DEFAULT_PROJECT_ICON: &str = "📁";
+
+/// Axum Route Handler to Add a Project.
+pub async fn create_project_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<ProjectEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    // Unless told otherwise, a new project goes after the existing ones.
+    let sort_order = match body.sort_order {
+        Some(sort_order) => sort_order,
+        None => {
+            let response = client
+                .from("projects")
+                .select("*")
+                .eq("user_id", user.id.to_string())
+                .order("sort_order.desc")
+                .limit(1)
+                .execute()
+                .await
+                .map_err(|err| {
+                    let error_response = json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", err)
+                    });
+
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?;
+
+            let project_response = response.text().await.map_err(|err| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": format!("Error parsing json response: {}", err)
+                });
+
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+            let project_data: Vec<ProjectModel> =
+                serde_json::from_str(&project_response).map_err(|err| {
+                    let error_response = json!({
+                        "status": "fail",
+                        "message": format!("Error deserializing response: {}", err)
+                    });
+
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?;
+
+            project_data
+                .first()
+                .map_or(0, |project| project.sort_order + 1)
+        }
+    };
+
+    let project_schema = CreateProjectSchema {
+        name: body.name,
+        color: body
+            .color
+            .unwrap_or_else(|| DEFAULT_PROJECT_COLOR.to_string()),
+        icon: body
+            .icon
+            .unwrap_or_else(|| DEFAULT_PROJECT_ICON.to_string()),
+        sort_order,
+        user_id: user.id.to_string(),
+    };
+
+    let response = client
+        .from("projects")
+        .insert(json!(project_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let project_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let project_data: Vec<ProjectModel> =
+        serde_json::from_str(&project_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match project_data.first() {
+        Some(project) => {
+            let created_project = json!({ "status": "success", "data": json!({
+                "project": json!(project)
+            })});
+
+            Ok((StatusCode::CREATED, Json(created_project)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while creating the project"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Fetch All Projects of the logged-in user.
+pub async fn get_projects_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(query): Query<ProjectsQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let mut request = client
+        .from("projects")
+        .select("*")
+        .eq("user_id", user.id.to_string())
+        .order("sort_order.asc,created_at.asc");
+
+    if !query.archived.unwrap_or(false) {
+        request = request.eq("archived", "false");
+    }
+
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let projects_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let projects_data: Vec<ProjectModel> =
+        serde_json::from_str(&projects_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let retrieved_projects = json!({ "status": "success", "data": json!({
+        "projects": json!(projects_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_projects)))
+}
+
+/// Axum Route Handler to Retrieve a Single Project.
+pub async fn get_single_project_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let (project, role) = get_project_with_role(client, &id, &user.id, Role::Viewer).await?;
+
+    let retrieved_project = json!({ "status": "success", "data": json!({
+        "project": json!(project),
+        "role": json!(role)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_project)))
+}
+
+/// Axum Route Handler to Edit a Project.
+pub async fn update_project_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<UpdateProjectEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let (retrieved_project, _) = get_project_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let project_schema = UpdateProjectSchema {
+        name: body.name.unwrap_or(retrieved_project.name),
+        color: body.color.unwrap_or(retrieved_project.color),
+        icon: body.icon.unwrap_or(retrieved_project.icon),
+        archived: body.archived.unwrap_or(retrieved_project.archived),
+        sort_order: body.sort_order.unwrap_or(retrieved_project.sort_order),
+    };
+
+    let response = client
+        .from("projects")
+        .update(json!(project_schema).to_string())
+        .eq("id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let project_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let project_data: Vec<ProjectModel> =
+        serde_json::from_str(&project_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match project_data.first() {
+        Some(project) => {
+            let updated_project = json!({ "status": "success", "data": json!({
+                "project": json!(project)
+            })});
+
+            Ok((StatusCode::OK, Json(updated_project)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while updating the project"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Delete a Project.
+/// By default its tasks are moved to the inbox (the `tasks.project_id`
+/// foreign key is set to null); with `?tasks=cascade` they are deleted too.
+pub async fn delete_project_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(query): Query<DeleteProjectQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let cascade = match query.tasks.as_deref() {
+        None | Some("inbox") => false,
+        Some("cascade") => true,
+        Some(tasks) => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Unknown tasks option: {}, expected 'inbox' or 'cascade'", tasks)
+            });
+            return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+        }
+    };
+
+    get_project_with_role(client, &id, &user.id, Role::Owner).await?;
+
+    // The tasks have to go first: once the project is deleted
+    // the foreign key has already moved them to the inbox.
+    // They go to the trash like any deleted task, and restoring
+    // them later brings them back to the inbox.
+    if cascade {
+        client
+            .from("tasks")
+            .update(json!({ "deleted_at": Local::now() }).to_string())
+            .eq("project_id", id.to_string())
+            .is("deleted_at", "null")
+            .execute()
+            .await
+            .map_err(|err| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", err)
+                });
+
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+    }
+
+    let response = client
+        .from("projects")
+        .delete()
+        .eq("id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let project_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let project_data: Vec<ProjectModel> =
+        serde_json::from_str(&project_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match project_data.first() {
+        Some(_project) => Ok(StatusCode::NO_CONTENT),
+        None => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Project with ID: {} not found", id)
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{
+    extract::{Path, State},
+    http::{header::ETAG, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::Local;
+use postgrest::Postgrest;
+use serde_json::{json, Map, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    handlers::tasks_handlers::{check_if_match, conflict_error, task_etag, TASK_COLUMNS},
+    models::{FieldChange, RevisionModel, Role, TaskModel, UserModel},
+    permissions::{get_project_with_role, get_task_with_role},
+    schemas::CreateRevisionSchema,
+    AppState,
+};
+
+/// Columns selected for a revision: its own columns plus the user who made the edit.
+const REVISION_COLUMNS: &str = "*, editor:users(id, username, email, created_at)";
+/// Fields of a task whose changes are recorded in its history.
+const TRACKED_FIELDS: [&str; 7] = [
+    "title",
+    "description",
+    "completed",
+    "project_id",
+    "auto_complete",
+    "due_date",
+    "recurrence",
+];
+
+/// Axum Route Handler to Fetch the History of a Task, newest revision first.
+pub async fn get_history_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+
+    let revisions_data = get_revisions(client, &id).await?;
+
+    let retrieved_history = json!({ "status": "success", "data": json!({
+        "revisions": json!(revisions_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_history)))
+}
+
+/// Axum Route Handler to Revert a Task to a past Revision.
+/// The task gets back the values it had right after that edit, undoing every later one,
+/// and the revert is itself recorded as a new revision.
+/// Like any edit, it honours the `If-Match` header.
+pub async fn revert_revision_handler(
+    Path((id, revision_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let (retrieved_task, _) = get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+    check_if_match(&headers, &retrieved_task)?;
+
+    let revisions_data = get_revisions(client, &id).await?;
+    let Some(position) = revisions_data
+        .iter()
+        .position(|revision| revision.id == revision_id)
+    else {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Revision with ID: {} not found", revision_id)
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    };
+
+    // The revisions come newest first, so undoing them in order walks back in time.
+    let before = task_fields(&retrieved_task);
+    let mut fields = before.clone();
+    for revision in &revisions_data[..position] {
+        for (field, change) in &revision.changes {
+            fields.insert(field.clone(), change.old.clone());
+        }
+    }
+
+    if fields == before {
+        let etag = task_etag(&retrieved_task);
+        let reverted_task = json!({ "status": "success", "data": json!({
+            "task": json!(retrieved_task)
+        })});
+
+        return Ok((StatusCode::OK, [(ETAG, etag)], Json(reverted_task)));
+    }
+
+    if fields.get("project_id") != before.get("project_id") {
+        let project_id: Option<Uuid> =
+            serde_json::from_value(fields["project_id"].clone()).unwrap_or_default();
+        if let Some(project_id) = project_id {
+            get_project_with_role(client, &project_id, &user.id, Role::Editor).await?;
+        }
+    }
+
+    let version = retrieved_task.version;
+    fields.insert("updated_at".to_string(), json!(Local::now()));
+
+    let response = client
+        .from("tasks")
+        .select(TASK_COLUMNS)
+        .update(Value::Object(fields).to_string())
+        .eq("id", id.to_string())
+        .eq("version", version.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let task_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let task_data: Vec<TaskModel> = serde_json::from_str(&task_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match task_data.into_iter().next() {
+        Some(task) => {
+            record_revision(client, &id, &user.id, &before, &task_fields(&task)).await?;
+
+            let etag = task_etag(&task);
+            let reverted_task = json!({ "status": "success", "data": json!({
+                "task": json!(task)
+            })});
+
+            Ok((StatusCode::OK, [(ETAG, etag)], Json(reverted_task)))
+        }
+        None => Err(conflict_error()),
+    }
+}
+
+/****** Helpers ******/
+
+/// The values of the tracked fields of a task, by name.
+pub fn task_fields(task: &TaskModel) -> Map<String, Value> {
+    let task = json!(task);
+
+    TRACKED_FIELDS
+        .iter()
+        .map(|field| (field.to_string(), task[*field].clone()))
+        .collect()
+}
+
+/// Records the edit of a task made by the user, given the values of its tracked
+/// fields before and after it. Nothing is recorded when no field changed.
+pub async fn record_revision(
+    client: &Postgrest,
+    task_id: &Uuid,
+    user_id: &Uuid,
+    before: &Map<String, Value>,
+    after: &Map<String, Value>,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let changes: BTreeMap<String, FieldChange> = TRACKED_FIELDS
+        .iter()
+        .filter_map(|field| {
+            let old = before.get(*field).cloned().unwrap_or_default();
+            let new = after.get(*field).cloned().unwrap_or_default();
+            (old != new).then(|| (field.to_string(), FieldChange { old, new }))
+        })
+        .collect();
+
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let revision_schema = CreateRevisionSchema {
+        task_id: task_id.to_string(),
+        user_id: user_id.to_string(),
+        changes,
+    };
+
+    client
+        .from("task_revisions")
+        .insert(json!(revision_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(())
+}
+
+/// Gets the revisions of a task, newest first.
+async fn get_revisions(
+    client: &Postgrest,
+    task_id: &Uuid,
+) -> Result<Vec<RevisionModel>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("task_revisions")
+        .select(REVISION_COLUMNS)
+        .eq("task_id", task_id.to_string())
+        .order("created_at.desc")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let revisions_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let revisions_data: Vec<RevisionModel> =
+        serde_json::from_str(&revisions_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(revisions_data)
+}
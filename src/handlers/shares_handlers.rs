@@ -0,0 +1,503 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use postgrest::Postgrest;
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    handlers::tasks_handlers::TASK_COLUMNS,
+    models::{ProjectModel, Role, ShareModel, TaskModel, UserModel},
+    permissions::{get_project_with_role, get_task_with_role},
+    schemas::{CreateShareSchema, UpdateShareSchema},
+    validators::{ShareEntry, UpdateShareEntry, ValidatedRequest},
+    AppState,
+};
+
+/// Columns selected for a share: its own columns plus the user it is shared with.
+/// `shares` has two foreign keys to `users`, so the embedding names the one to follow.
+const SHARE_COLUMNS: &str = "*, user:users!shares_user_id_fkey(id, username, email, created_at)";
+
+/// Axum Route Handler to Share a Task with another user.
+pub async fn share_task_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<ShareEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let (task, _) = get_task_with_role(client, &id, &user.id, Role::Owner).await?;
+    let recipient = get_recipient(client, &body.email, &task.user_id, "task_id", &id).await?;
+
+    let share_schema = CreateShareSchema {
+        task_id: Some(id.to_string()),
+        project_id: None,
+        user_id: recipient.id.to_string(),
+        role: body.role,
+        shared_by: user.id.to_string(),
+    };
+
+    let share = insert_share(client, &share_schema).await?;
+
+    let created_share = json!({ "status": "success", "data": json!({
+        "share": json!(share)
+    })});
+
+    Ok((StatusCode::CREATED, Json(created_share)))
+}
+
+/// Axum Route Handler to Share a Project with another user.
+/// The user gets the same access to every task of the project.
+pub async fn share_project_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<ShareEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let (project, _) = get_project_with_role(client, &id, &user.id, Role::Owner).await?;
+    let recipient = get_recipient(client, &body.email, &project.user_id, "project_id", &id).await?;
+
+    let share_schema = CreateShareSchema {
+        task_id: None,
+        project_id: Some(id.to_string()),
+        user_id: recipient.id.to_string(),
+        role: body.role,
+        shared_by: user.id.to_string(),
+    };
+
+    let share = insert_share(client, &share_schema).await?;
+
+    let created_share = json!({ "status": "success", "data": json!({
+        "share": json!(share)
+    })});
+
+    Ok((StatusCode::CREATED, Json(created_share)))
+}
+
+/// Axum Route Handler to Fetch the Shares of a Task.
+pub async fn get_task_shares_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Owner).await?;
+    let shares_data = get_shares(client, "task_id", &id).await?;
+
+    let retrieved_shares = json!({ "status": "success", "data": json!({
+        "shares": json!(shares_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_shares)))
+}
+
+/// Axum Route Handler to Fetch the Shares of a Project.
+pub async fn get_project_shares_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    get_project_with_role(client, &id, &user.id, Role::Owner).await?;
+    let shares_data = get_shares(client, "project_id", &id).await?;
+
+    let retrieved_shares = json!({ "status": "success", "data": json!({
+        "shares": json!(shares_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_shares)))
+}
+
+/// Axum Route Handler to Fetch the Tasks and Projects shared with the logged-in user.
+pub async fn get_shared_with_me_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let mut shares_data = get_shares(client, "user_id", &user.id).await?;
+
+    let task_ids: Vec<Uuid> = shares_data
+        .iter()
+        .filter_map(|share| share.task_id)
+        .collect();
+    let project_ids: Vec<Uuid> = shares_data
+        .iter()
+        .filter_map(|share| share.project_id)
+        .collect();
+
+    let mut tasks_data: Vec<TaskModel> =
+        get_by_ids(client, "tasks", TASK_COLUMNS, &task_ids).await?;
+
+    // Tasks in the trash stay hidden until their owner restores them.
+    let trashed_ids: Vec<Uuid> = tasks_data
+        .iter()
+        .filter(|task| task.deleted_at.is_some())
+        .map(|task| task.id)
+        .collect();
+    tasks_data.retain(|task| task.deleted_at.is_none());
+    shares_data
+        .retain(|share| !matches!(share.task_id, Some(task_id) if trashed_ids.contains(&task_id)));
+    let projects_data: Vec<ProjectModel> =
+        get_by_ids(client, "projects", "*", &project_ids).await?;
+
+    let retrieved_shares = json!({ "status": "success", "data": json!({
+        "shares": json!(shares_data),
+        "tasks": json!(tasks_data),
+        "projects": json!(projects_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_shares)))
+}
+
+/// Axum Route Handler to Change the Role of a Share.
+pub async fn update_share_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<UpdateShareEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let share = get_single_share(client, &id).await?;
+    check_share_owner(client, &share, &user.id).await?;
+
+    let share_schema = UpdateShareSchema { role: body.role };
+
+    let response = client
+        .from("shares")
+        .select(SHARE_COLUMNS)
+        .update(json!(share_schema).to_string())
+        .eq("id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let share_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let share_data: Vec<ShareModel> = serde_json::from_str(&share_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match share_data.first() {
+        Some(share) => {
+            let updated_share = json!({ "status": "success", "data": json!({
+                "share": json!(share)
+            })});
+
+            Ok((StatusCode::OK, Json(updated_share)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while updating the share"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Revoke a Share.
+/// Besides the owners, the user a share was given to can also revoke it to leave.
+pub async fn delete_share_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let share = get_single_share(client, &id).await?;
+    if share.user_id != user.id {
+        check_share_owner(client, &share, &user.id).await?;
+    }
+
+    client
+        .from("shares")
+        .delete()
+        .eq("id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/****** Helpers ******/
+
+/// Gets the user registered with `email`, ensuring that the task or project
+/// (`column` and `id`) can be shared with them: they neither own it nor have a share yet.
+async fn get_recipient(
+    client: &Postgrest,
+    email: &str,
+    owner_id: &Uuid,
+    column: &str,
+    id: &Uuid,
+) -> Result<UserModel, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("users")
+        .select("*")
+        .eq("email", email)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let user_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let user_data: Vec<UserModel> = serde_json::from_str(&user_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let recipient = user_data.into_iter().next().ok_or_else(|| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("User with email: {} not found", email)
+        });
+
+        (StatusCode::NOT_FOUND, Json(error_response))
+    })?;
+
+    if &recipient.id == owner_id {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("User with email: {} is already the owner", email)
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let shares_data = get_shares(client, column, id).await?;
+    if shares_data
+        .iter()
+        .any(|share| share.user_id == recipient.id)
+    {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Already shared with the user with email: {}", email)
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    Ok(recipient)
+}
+
+async fn insert_share(
+    client: &Postgrest,
+    share_schema: &CreateShareSchema,
+) -> Result<ShareModel, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("shares")
+        .select(SHARE_COLUMNS)
+        .insert(json!(share_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let share_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let share_data: Vec<ShareModel> = serde_json::from_str(&share_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    share_data.into_iter().next().ok_or_else(|| {
+        let error_response = json!({
+            "status": "error",
+            "message": "Something bad happened while creating the share"
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })
+}
+
+/// Ensures that the user is an owner of the task or project of the share.
+async fn check_share_owner(
+    client: &Postgrest,
+    share: &ShareModel,
+    user_id: &Uuid,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    if let Some(task_id) = &share.task_id {
+        get_task_with_role(client, task_id, user_id, Role::Owner).await?;
+    } else if let Some(project_id) = &share.project_id {
+        get_project_with_role(client, project_id, user_id, Role::Owner).await?;
+    }
+
+    Ok(())
+}
+
+async fn get_single_share(
+    client: &Postgrest,
+    id: &Uuid,
+) -> Result<ShareModel, (StatusCode, Json<Value>)> {
+    get_shares(client, "id", id)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Share with ID: {} not found", id)
+            });
+
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })
+}
+
+/// Gets the shares whose `column` is `id`.
+async fn get_shares(
+    client: &Postgrest,
+    column: &str,
+    id: &Uuid,
+) -> Result<Vec<ShareModel>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("shares")
+        .select(SHARE_COLUMNS)
+        .eq(column, id.to_string())
+        .order("created_at.asc")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let shares_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    serde_json::from_str(&shares_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })
+}
+
+/// Gets the rows of `table` with the given ids.
+async fn get_by_ids<T: serde::de::DeserializeOwned>(
+    client: &Postgrest,
+    table: &str,
+    columns: &str,
+    ids: &[Uuid],
+) -> Result<Vec<T>, (StatusCode, Json<Value>)> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let response = client
+        .from(table)
+        .select(columns)
+        .in_("id", ids.iter().map(|id| id.to_string()))
+        .order("created_at.desc")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let rows_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    serde_json::from_str(&rows_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })
+}
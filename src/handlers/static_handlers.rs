@@ -0,0 +1,159 @@
+use std::{path::Path, sync::Arc, time::UNIX_EPOCH};
+
+use axum::{
+    body::{boxed, Body},
+    extract::State,
+    http::{
+        header::{
+            ALLOW, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH, ETAG, IF_MODIFIED_SINCE,
+            IF_NONE_MATCH, LAST_MODIFIED,
+        },
+        HeaderMap, HeaderValue, Method, Request, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use tokio::sync::RwLock;
+use tower::ServiceExt;
+use tower_http::services::{ServeDir, ServeFile};
+
+use crate::AppState;
+
+/// Assets whose name carries a hash of their content never change: a new build renames them.
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// Everything else is checked with the server before every use, which only costs
+/// a `304 Not Modified` while it has not changed.
+const REVALIDATE: &str = "no-cache";
+
+/// Axum Route Handler of the frontend build. Files are served from the static
+/// directory, precompressed with Brotli or gzip when a `.br` or `.gz` file lies
+/// next to them (they are compressed on the fly otherwise); a missing file is
+/// a `404`, while any other path is a route of the app and gets `index.html`.
+/// Paths under `/api/` that no route matched are a `404` whatever the method.
+pub async fn static_file_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    mut req: Request<Body>,
+) -> Response {
+    let path = req.uri().path().to_string();
+    if path == "/api" || path.starts_with("/api/") {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("No route for {}", path)
+        });
+        return (StatusCode::NOT_FOUND, Json(error_response)).into_response();
+    }
+
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return (
+            StatusCode::METHOD_NOT_ALLOWED,
+            [(ALLOW, HeaderValue::from_static("GET, HEAD"))],
+        )
+            .into_response();
+    }
+
+    let static_dir = data.read().await.env.static_dir.clone();
+    let is_asset = is_asset_path(&path);
+
+    // The `ETag` decides when there is one, as the dates only have a precision of a second.
+    let if_none_match = req.headers_mut().remove(IF_NONE_MATCH);
+    if if_none_match.is_some() {
+        req.headers_mut().remove(IF_MODIFIED_SINCE);
+    }
+
+    let res = if is_asset {
+        ServeDir::new(&static_dir)
+            .precompressed_br()
+            .precompressed_gzip()
+            .oneshot(req)
+            .await
+    } else {
+        ServeFile::new(Path::new(&static_dir).join("index.html"))
+            .precompressed_br()
+            .precompressed_gzip()
+            .oneshot(req)
+            .await
+    };
+    let mut res = match res {
+        Ok(res) => res.map(boxed),
+        Err(err) => match err {},
+    };
+
+    if res.status() == StatusCode::NOT_FOUND {
+        return (StatusCode::NOT_FOUND, "Not found\n").into_response();
+    }
+    if !res.status().is_success() && res.status() != StatusCode::NOT_MODIFIED {
+        return res;
+    }
+
+    let cache_control = if is_asset && is_hashed(&path) {
+        IMMUTABLE
+    } else {
+        REVALIDATE
+    };
+    res.headers_mut()
+        .insert(CACHE_CONTROL, HeaderValue::from_static(cache_control));
+
+    if let Some(etag) = entity_tag(res.headers()) {
+        if if_none_match.is_some_and(|tags| matches_etag(&tags, &etag)) {
+            let mut not_modified = StatusCode::NOT_MODIFIED.into_response();
+            for name in [CACHE_CONTROL, LAST_MODIFIED] {
+                if let Some(value) = res.headers().get(&name) {
+                    not_modified.headers_mut().insert(name, value.clone());
+                }
+            }
+            not_modified.headers_mut().insert(ETAG, etag);
+            return not_modified;
+        }
+        res.headers_mut().insert(ETAG, etag);
+    }
+
+    res
+}
+
+/// A path that names a file, as opposed to a route of the app.
+fn is_asset_path(path: &str) -> bool {
+    path.rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains('.'))
+}
+
+/// Trunk names the files it builds `<name>-<hash>.<extension>`.
+fn is_hashed(path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or_default();
+    let stem = name.split('.').next().unwrap_or_default();
+    let stem = stem.strip_suffix("_bg").unwrap_or(stem);
+
+    stem.rsplit_once('-')
+        .is_some_and(|(_, hash)| hash.len() >= 8 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// A weak `ETag` made of the size and the modification date of the file served,
+/// so that it changes with the file and the encoding it was sent in.
+fn entity_tag(headers: &HeaderMap) -> Option<HeaderValue> {
+    let length = headers.get(CONTENT_LENGTH)?.to_str().ok()?;
+    let modified = headers.get(LAST_MODIFIED)?.to_str().ok()?;
+    let modified = httpdate::parse_http_date(modified)
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    let encoding = headers
+        .get(CONTENT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .map(|encoding| format!("-{}", encoding))
+        .unwrap_or_default();
+
+    HeaderValue::from_str(&format!("W/\"{}-{:x}{}\"", length, modified, encoding)).ok()
+}
+
+fn matches_etag(if_none_match: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(tags) = if_none_match.to_str() else {
+        return false;
+    };
+    let etag = etag.to_str().unwrap_or_default();
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+
+    tags.split(',')
+        .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(etag))
+}
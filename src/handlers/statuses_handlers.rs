@@ -0,0 +1,439 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use postgrest::Postgrest;
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    models::{Role, StatusModel, UserModel},
+    permissions::get_project_with_role,
+    schemas::{CreateStatusSchema, StatusesQuery, UpdateStatusSchema},
+    validators::{StatusEntry, UpdateStatusEntry, ValidatedRequest},
+    AppState,
+};
+
+/// Color assigned to a status when the request does not provide one.
+const DEFAULT_STATUS_COLOR: &str = "#64748b";
+/// Columns (name, color, done) a board starts with.
+const DEFAULT_STATUSES: [(&str, &str, bool); 4] = [
+    ("To do", DEFAULT_STATUS_COLOR, false),
+    ("In progress", "#0284c7", false),
+    ("Blocked", "#dc2626", false),
+    ("Done", "#16a34a", true),
+];
+
+/// Axum Route Handler to Fetch the Statuses (columns) of a board, in order.
+/// A board that has none yet gets the default ones.
+pub async fn get_statuses_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(query): Query<StatusesQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    // The columns of a project belong to its owner, whoever opens the board first.
+    let owner_id = match &query.project {
+        Some(project_id) => {
+            let (project, _) =
+                get_project_with_role(client, project_id, &user.id, Role::Viewer).await?;
+            project.user_id
+        }
+        None => user.id,
+    };
+
+    let mut statuses_data = get_statuses(client, &owner_id, query.project.as_ref()).await?;
+    if statuses_data.is_empty() {
+        let statuses_schema: Vec<CreateStatusSchema> = DEFAULT_STATUSES
+            .iter()
+            .zip(0..)
+            .map(|((name, color, done), sort_order)| CreateStatusSchema {
+                name: name.to_string(),
+                color: color.to_string(),
+                done: *done,
+                sort_order,
+                user_id: owner_id.to_string(),
+                project_id: query.project.map(|project_id| project_id.to_string()),
+            })
+            .collect();
+
+        statuses_data = insert_statuses(client, &statuses_schema).await?;
+    }
+
+    let retrieved_statuses = json!({ "status": "success", "data": json!({
+        "statuses": json!(statuses_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_statuses)))
+}
+
+/// Axum Route Handler to Add a Status to a board.
+pub async fn create_status_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<StatusEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    // Only the owner of a project decides the workflow of its board.
+    let owner_id = match &body.project_id {
+        Some(project_id) => {
+            let (project, _) =
+                get_project_with_role(client, project_id, &user.id, Role::Owner).await?;
+            project.user_id
+        }
+        None => user.id,
+    };
+
+    // Unless told otherwise, a new column goes after the existing ones.
+    let sort_order = match body.sort_order {
+        Some(sort_order) => sort_order,
+        None => get_statuses(client, &owner_id, body.project_id.as_ref())
+            .await?
+            .last()
+            .map_or(0, |status| status.sort_order + 1),
+    };
+
+    let status_schema = CreateStatusSchema {
+        name: body.name,
+        color: body
+            .color
+            .unwrap_or_else(|| DEFAULT_STATUS_COLOR.to_string()),
+        done: body.done,
+        sort_order,
+        user_id: owner_id.to_string(),
+        project_id: body.project_id.map(|project_id| project_id.to_string()),
+    };
+
+    match insert_statuses(client, &[status_schema])
+        .await?
+        .into_iter()
+        .next()
+    {
+        Some(status) => {
+            let created_status = json!({ "status": "success", "data": json!({
+                "status": json!(status)
+            })});
+
+            Ok((StatusCode::CREATED, Json(created_status)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while creating the status"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Edit a Status.
+/// Marking a column as done (or not) completes (or reopens) the tasks in it.
+pub async fn update_status_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<UpdateStatusEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let retrieved_status = get_status_with_role(client, &id, &user.id, Role::Owner).await?;
+
+    let status_schema = UpdateStatusSchema {
+        name: body.name.unwrap_or(retrieved_status.name),
+        color: body.color.unwrap_or(retrieved_status.color),
+        done: body.done.unwrap_or(retrieved_status.done),
+        sort_order: body.sort_order.unwrap_or(retrieved_status.sort_order),
+    };
+
+    let response = client
+        .from("statuses")
+        .update(json!(status_schema).to_string())
+        .eq("id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let status_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let status_data: Vec<StatusModel> = serde_json::from_str(&status_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let Some(status) = status_data.into_iter().next() else {
+        let error_response = json!({
+            "status": "error",
+            "message": "Something bad happened while updating the status"
+        });
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)));
+    };
+
+    // The column already says `done`, so the database keeps the tasks in it.
+    if status.done != retrieved_status.done {
+        client
+            .from("tasks")
+            .update(json!({ "completed": status.done }).to_string())
+            .eq("status_id", id.to_string())
+            .execute()
+            .await
+            .map_err(|err| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", err)
+                });
+
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+    }
+
+    let updated_status = json!({ "status": "success", "data": json!({
+        "status": json!(status)
+    })});
+
+    Ok((StatusCode::OK, Json(updated_status)))
+}
+
+/// Axum Route Handler to Delete a Status.
+/// Its tasks keep whether they are completed and go back to the first matching column
+/// (the `tasks.status_id` foreign key is set to null).
+pub async fn delete_status_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_status_with_role(client, &id, &user.id, Role::Owner).await?;
+
+    client
+        .from("statuses")
+        .delete()
+        .eq("id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/****** Helpers ******/
+
+/// Ensures that a status is a column of the board a task is on: the one of its project,
+/// or the personal board of its owner for the tasks that are not in any project.
+pub async fn check_status_board(
+    client: &Postgrest,
+    status_id: &Uuid,
+    task_owner_id: &Uuid,
+    project_id: Option<&Uuid>,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let status = get_status(client, status_id).await?;
+
+    let on_board = match (project_id, &status.project_id) {
+        (Some(project_id), Some(status_project_id)) => project_id == status_project_id,
+        (None, None) => task_owner_id == &status.user_id,
+        _ => false,
+    };
+    if !on_board {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Status with ID: {} is not a column of the board of the task", status_id)
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    Ok(())
+}
+
+/// Gets a status, failing if the user's role on its board is lower than `required`.
+/// Only its owner has a role on a personal board.
+async fn get_status_with_role(
+    client: &Postgrest,
+    status_id: &Uuid,
+    user_id: &Uuid,
+    required: Role,
+) -> Result<StatusModel, (StatusCode, Json<Value>)> {
+    let status = get_status(client, status_id).await?;
+
+    match &status.project_id {
+        Some(project_id) => {
+            get_project_with_role(client, project_id, user_id, required).await?;
+        }
+        None if &status.user_id != user_id => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Status with ID: {} not found", status_id)
+            });
+            return Err((StatusCode::NOT_FOUND, Json(error_response)));
+        }
+        None => {}
+    }
+
+    Ok(status)
+}
+
+async fn get_status(
+    client: &Postgrest,
+    status_id: &Uuid,
+) -> Result<StatusModel, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("statuses")
+        .select("*")
+        .eq("id", status_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let status_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let status_data: Vec<StatusModel> = serde_json::from_str(&status_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    status_data.into_iter().next().ok_or_else(|| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Status with ID: {} not found", status_id)
+        });
+
+        (StatusCode::NOT_FOUND, Json(error_response))
+    })
+}
+
+/// Gets the columns of the board of a project, or of the personal board of a user, in order.
+async fn get_statuses(
+    client: &Postgrest,
+    user_id: &Uuid,
+    project_id: Option<&Uuid>,
+) -> Result<Vec<StatusModel>, (StatusCode, Json<Value>)> {
+    let mut request = client
+        .from("statuses")
+        .select("*")
+        .order("sort_order.asc,created_at.asc");
+    request = match project_id {
+        Some(project_id) => request.eq("project_id", project_id.to_string()),
+        None => request
+            .eq("user_id", user_id.to_string())
+            .is("project_id", "null"),
+    };
+
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let statuses_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let statuses_data: Vec<StatusModel> =
+        serde_json::from_str(&statuses_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(statuses_data)
+}
+
+async fn insert_statuses(
+    client: &Postgrest,
+    statuses_schema: &[CreateStatusSchema],
+) -> Result<Vec<StatusModel>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("statuses")
+        .insert(json!(statuses_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let statuses_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let statuses_data: Vec<StatusModel> =
+        serde_json::from_str(&statuses_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(statuses_data)
+}
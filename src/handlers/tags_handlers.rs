@@ -0,0 +1,402 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    models::{TagModel, UserModel},
+    schemas::{CreateTagSchema, UpdateTagSchema},
+    validators::{TagEntry, UpdateTagEntry, ValidatedRequest},
+    AppState,
+};
+
+/// Color assigned to a tag when the request does not provide one.
+const DEFAULT_TAG_COLOR: &str = "#d97706";
+
+/// Axum Route Handler to Add a Tag.
+pub async fn create_tag_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<TagEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    // Tag names are unique per user.
+    let response = client
+        .from("tags")
+        .select("*")
+        .eq("user_id", user.id.to_string())
+        .eq("name", &body.name)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tag_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tag_data: Vec<TagModel> = serde_json::from_str(&tag_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    if !tag_data.is_empty() {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Tag with name: {} already exists", body.name)
+        });
+        return Err((StatusCode::CONFLICT, Json(error_response)));
+    }
+
+    let tag_schema = CreateTagSchema {
+        name: body.name,
+        color: body.color.unwrap_or_else(|| DEFAULT_TAG_COLOR.to_string()),
+        user_id: user.id.to_string(),
+    };
+
+    let response = client
+        .from("tags")
+        .insert(json!(tag_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tag_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tag_data: Vec<TagModel> = serde_json::from_str(&tag_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match tag_data.first() {
+        Some(tag) => {
+            let created_tag = json!({ "status": "success", "data": json!({
+                "tag": json!(tag)
+            })});
+
+            Ok((StatusCode::CREATED, Json(created_tag)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while creating the tag"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Fetch All Tags of the logged-in user.
+pub async fn get_tags_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let response = client
+        .from("tags")
+        .select("*")
+        .eq("user_id", user.id.to_string())
+        .order("name.asc")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tags_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tags_data: Vec<TagModel> = serde_json::from_str(&tags_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let retrieved_tags = json!({ "status": "success", "data": json!({
+        "tags": json!(tags_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_tags)))
+}
+
+/// Axum Route Handler to Retrieve a Single Tag.
+pub async fn get_single_tag_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let response = client
+        .from("tags")
+        .select("*")
+        .eq("id", id.to_string())
+        .eq("user_id", user.id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tag_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tag_data: Vec<TagModel> = serde_json::from_str(&tag_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match tag_data.first() {
+        Some(tag) => {
+            let retrieved_tag = json!({ "status": "success", "data": json!({
+                "tag": json!(tag)
+            })});
+
+            Ok((StatusCode::OK, Json(retrieved_tag)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Tag with ID: {} not found", id)
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Edit a Tag.
+pub async fn update_tag_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<UpdateTagEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let response = client
+        .from("tags")
+        .select("*")
+        .eq("id", id.to_string())
+        .eq("user_id", user.id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tag_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tag_data: Vec<TagModel> = serde_json::from_str(&tag_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let retrieved_tag = tag_data.into_iter().next().ok_or_else(|| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Tag with ID: {} not found", id)
+        });
+
+        (StatusCode::NOT_FOUND, Json(error_response))
+    })?;
+
+    let tag_schema = UpdateTagSchema {
+        name: body.name.unwrap_or(retrieved_tag.name),
+        color: body.color.unwrap_or(retrieved_tag.color),
+    };
+
+    let response = client
+        .from("tags")
+        .update(json!(tag_schema).to_string())
+        .eq("id", id.to_string())
+        .eq("user_id", user.id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tag_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    // A failed update (e.g. a name already used by another tag) does not
+    // deserialize into a list of tags, so it ends up here as a conflict.
+    let tag_data: Vec<TagModel> = serde_json::from_str(&tag_response).map_err(|_| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Tag with name: {} already exists", tag_schema.name)
+        });
+
+        (StatusCode::CONFLICT, Json(error_response))
+    })?;
+
+    match tag_data.first() {
+        Some(tag) => {
+            let updated_tag = json!({ "status": "success", "data": json!({
+                "tag": json!(tag)
+            })});
+
+            Ok((StatusCode::OK, Json(updated_tag)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while updating the tag"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Delete a Tag.
+/// The `task_tags` foreign key cascades, so the tag is also removed from its tasks.
+pub async fn delete_tag_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let response = client
+        .from("tags")
+        .delete()
+        .eq("id", id.to_string())
+        .eq("user_id", user.id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tag_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tag_data: Vec<TagModel> = serde_json::from_str(&tag_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match tag_data.first() {
+        Some(_tag) => Ok(StatusCode::NO_CONTENT),
+        None => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Tag with ID: {} not found", id)
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::Local;
+use postgrest::Postgrest;
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    handlers::{
+        revisions_handlers::{record_revision, task_fields},
+        tasks_handlers::create_next_occurrence,
+    },
+    models::{Role, TaskItemModel, TaskModel, UserModel},
+    permissions::get_task_with_role,
+    schemas::{CreateTaskItemSchema, ReorderTaskItemsBody, UpdateTaskItemSchema},
+    validators::{TaskItemEntry, UpdateTaskItemEntry, ValidatedRequest},
+    AppState,
+};
+
+/// Axum Route Handler to Fetch the Checklist of a Task.
+pub async fn get_task_items_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+    let items_data = get_task_items(client, &id).await?;
+
+    let retrieved_items = json!({ "status": "success", "data": json!({
+        "items": json!(items_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_items)))
+}
+
+/// Axum Route Handler to Add an Item to the Checklist of a Task.
+pub async fn create_task_item_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<TaskItemEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    // Unless told otherwise, a new item goes at the end of the checklist.
+    let position = match body.position {
+        Some(position) => position,
+        None => get_task_items(client, &id)
+            .await?
+            .last()
+            .map_or(0, |item| item.position + 1),
+    };
+
+    let item_schema = CreateTaskItemSchema {
+        text: body.text,
+        position,
+        task_id: id.to_string(),
+    };
+
+    let response = client
+        .from("task_items")
+        .insert(json!(item_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let item_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let item_data: Vec<TaskItemModel> = serde_json::from_str(&item_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match item_data.first() {
+        Some(item) => {
+            let created_item = json!({ "status": "success", "data": json!({
+                "item": json!(item)
+            })});
+
+            Ok((StatusCode::CREATED, Json(created_item)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while creating the checklist item"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Edit an Item of the Checklist of a Task.
+pub async fn update_task_item_handler(
+    Path((id, item_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<UpdateTaskItemEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let (mut task, _) = get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let retrieved_item = get_task_items(client, &id)
+        .await?
+        .into_iter()
+        .find(|item| item.id == item_id)
+        .ok_or_else(|| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Checklist item with ID: {} not found", item_id)
+            });
+
+            (StatusCode::NOT_FOUND, Json(error_response))
+        })?;
+
+    let item_schema = UpdateTaskItemSchema {
+        text: body.text.unwrap_or(retrieved_item.text),
+        done: body.done.unwrap_or(retrieved_item.done),
+    };
+
+    let response = client
+        .from("task_items")
+        .update(json!(item_schema).to_string())
+        .eq("id", item_id.to_string())
+        .eq("task_id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let item_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let item_data: Vec<TaskItemModel> = serde_json::from_str(&item_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match item_data.first() {
+        Some(item) => {
+            auto_complete_task(client, &mut task, &user.id).await?;
+
+            let updated_item = json!({ "status": "success", "data": json!({
+                "item": json!(item)
+            })});
+
+            Ok((StatusCode::OK, Json(updated_item)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while updating the checklist item"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Delete an Item of the Checklist of a Task.
+pub async fn delete_task_item_handler(
+    Path((id, item_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let (mut task, _) = get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let response = client
+        .from("task_items")
+        .delete()
+        .eq("id", item_id.to_string())
+        .eq("task_id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let item_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let item_data: Vec<TaskItemModel> = serde_json::from_str(&item_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match item_data.first() {
+        Some(_item) => {
+            // Removing the last pending item also completes the checklist.
+            auto_complete_task(client, &mut task, &user.id).await?;
+
+            Ok(StatusCode::NO_CONTENT)
+        }
+        None => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Checklist item with ID: {} not found", item_id)
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Reorder the Checklist of a Task.
+/// The body lists every item id of the checklist in its new order.
+pub async fn reorder_task_items_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Json(body): Json<ReorderTaskItemsBody>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let items_data = get_task_items(client, &id).await?;
+
+    let mut current_ids: Vec<Uuid> = items_data.iter().map(|item| item.id).collect();
+    let mut new_ids = body.item_ids.clone();
+    current_ids.sort();
+    new_ids.sort();
+
+    if current_ids != new_ids {
+        let error_response = json!({
+            "status": "fail",
+            "message": "The new order must list every item of the checklist exactly once"
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    for (position, item_id) in body.item_ids.iter().enumerate() {
+        client
+            .from("task_items")
+            .update(json!({ "position": position }).to_string())
+            .eq("id", item_id.to_string())
+            .eq("task_id", id.to_string())
+            .execute()
+            .await
+            .map_err(|err| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", err)
+                });
+
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+    }
+
+    let items_data = get_task_items(client, &id).await?;
+
+    let reordered_items = json!({ "status": "success", "data": json!({
+        "items": json!(items_data)
+    })});
+
+    Ok((StatusCode::OK, Json(reordered_items)))
+}
+
+/****** Helpers ******/
+
+/// Gets the checklist items of a task ordered by position.
+pub async fn get_task_items(
+    client: &Postgrest,
+    task_id: &Uuid,
+) -> Result<Vec<TaskItemModel>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("task_items")
+        .select("*")
+        .eq("task_id", task_id.to_string())
+        .order("position.asc,created_at.asc")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let items_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    serde_json::from_str(&items_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })
+}
+
+/// If the task has the auto-complete rule enabled, marks it as
+/// completed once every item of its checklist is done.
+/// Like any completion, this schedules the next occurrence of a recurring task.
+async fn auto_complete_task(
+    client: &Postgrest,
+    task: &mut TaskModel,
+    user_id: &Uuid,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    if !task.auto_complete || task.completed {
+        return Ok(());
+    }
+
+    let items_data = get_task_items(client, &task.id).await?;
+    if items_data.is_empty() || items_data.iter().any(|item| !item.done) {
+        return Ok(());
+    }
+
+    client
+        .from("tasks")
+        .update(json!({ "completed": true, "updated_at": Local::now() }).to_string())
+        .eq("id", task.id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let before = task_fields(task);
+    task.completed = true;
+    create_next_occurrence(client, task).await?;
+    record_revision(client, &task.id, user_id, &before, &task_fields(task)).await?;
+
+    Ok(())
+}
@@ -1,24 +1,59 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{
+        header::{ETAG, IF_MATCH},
+        HeaderMap, StatusCode,
+    },
     response::IntoResponse,
     Extension, Json,
 };
+use chrono::{DateTime, Local};
+use postgrest::{Builder, Postgrest};
 use serde_json::{json, Value};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 use crate::{
-    models::{TaskModel, UserModel},
-    schemas::{CreateTaskSchema, UpdateTaskBody, UpdateTaskSchema},
-    validators::{TaskEntry, ValidatedRequest},
+    events::{emit_task_event, emit_task_update},
+    handlers::{
+        attachments_handlers::{delete_blobs, get_attachment_keys},
+        revisions_handlers::{record_revision, task_fields},
+        statuses_handlers::check_status_board,
+        task_items_handlers::get_task_items,
+    },
+    models::{Role, TagModel, TaskModel, UserModel},
+    permissions::{get_project_with_role, get_task_with_role, get_trashed_task_with_role},
+    recurrence::Recurrence,
+    schemas::{
+        ClearCompletedQuery, CreateTaskItemSchema, CreateTaskSchema, FilterTasksQuery,
+        TaskChangesQuery, TaskTagSchema, UpdateTaskSchema,
+    },
+    storage::BlobStore,
+    validators::{
+        BulkAction, BulkTaskEntry, MoveTaskEntry, RecurrencePreviewEntry, TaskEntry,
+        UpdateTaskEntry, ValidatedRequest,
+    },
+    webhooks::TaskEvent,
     AppState,
 };
 
 // const USER_ID: &str = "405f1394-e5eb-470d-8174-14020f55e881";
 
+/// Columns selected for a task: its own columns plus the ids of its tags,
+/// embedded through the `task_tags` join table, the state of its checklist items
+/// and the time tracked on it.
+pub const TASK_COLUMNS: &str =
+    "*, task_tags(tag_id), task_items(done), time_entries(started_at, ended_at)";
+/// Number of occurrences previewed when the request does not say otherwise.
+const DEFAULT_OCCURRENCES: usize = 5;
+/// Most changes returned at once to a client that catches up.
+const CHANGES_PAGE_SIZE: usize = 500;
+/// How long, in seconds, a write may take to commit after it stamped `changed_at`:
+/// the cursor stays that far behind, so that a client does not skip it.
+const CHANGES_MARGIN_SECS: i64 = 10;
+
 /// Axum Route Handler to Add a Record.
 // Important: the first 2 extractors must be "server state";
 // the latter corresponds to the body of the request.
@@ -30,7 +65,14 @@ pub async fn create_task_handler(
     Extension(user): Extension<UserModel>,
     ValidatedRequest(body): ValidatedRequest<TaskEntry>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    let client = &data.write().await.client;
+    let state = data.write().await;
+    let client = &state.client;
+
+    check_tags_ownership(client, &user.id, &body.tag_ids).await?;
+    // Editors of a shared project can add tasks to it as well.
+    if let Some(project_id) = &body.project_id {
+        get_project_with_role(client, project_id, &user.id, Role::Editor).await?;
+    }
 
     // We get the user_id of the request extensions that, thanks to the
     // route protection middleware, carry the UserModel object.
@@ -38,6 +80,10 @@ pub async fn create_task_handler(
         title: body.title,
         description: body.description,
         user_id: user.id.to_string(),
+        project_id: body.project_id.map(|project_id| project_id.to_string()),
+        auto_complete: body.auto_complete,
+        due_date: body.due_date,
+        recurrence: body.recurrence.map(normalize_recurrence),
     };
 
     // println!("body: {}", json!(task_body).to_string());
@@ -74,8 +120,12 @@ pub async fn create_task_handler(
         (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
     })?;
 
-    match task_data.iter().next() {
-        Some(task) => {
+    match task_data.into_iter().next() {
+        Some(mut task) => {
+            task.tag_ids = set_task_tags(client, &task.id, &body.tag_ids).await?;
+
+            emit_task_event(&state, TaskEvent::Created, &task, &user.id);
+
             let created_task = json!({ "status": "success", "data": json!({
                 "task": json!(task)
             })});
@@ -96,26 +146,22 @@ pub async fn create_task_handler(
 pub async fn get_tasks_handler(
     State(data): State<Arc<RwLock<AppState>>>,
     Extension(user): Extension<UserModel>,
+    Query(query): Query<FilterTasksQuery>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
     let client = &data.read().await.client;
 
     // We get the user_id of the request extensions that, thanks to the
     // route protection middleware, carry the UserModel object.
-    let response = client
-        .from("tasks")
-        .select("*")
-        .eq("user_id", user.id.to_string())
-        .order("created_at.desc")
-        .execute()
-        .await
-        .map_err(|err| {
-            let error_response = json!({
-                "status": "fail",
-                "message": format!("Database error: {}", err)
-            });
+    let request = filter_tasks(client, &user.id, &query).await?;
 
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
-        })?;
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
 
     let tasks_response = response.text().await.map_err(|err| {
         let error_response = json!({
@@ -146,12 +192,182 @@ pub async fn get_tasks_handler(
 pub async fn get_single_task_handler(
     Path(id): Path<Uuid>,
     State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
     let client = &data.read().await.client;
 
+    let (task, role) = get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+
+    let etag = task_etag(&task);
+    let retrieved_task = json!({ "status": "success", "data": json!({
+        "task": json!(task),
+        "role": json!(role)
+    })});
+
+    Ok((StatusCode::OK, [(ETAG, etag)], Json(retrieved_task)))
+}
+
+/// Axum Route Handler to Edit a Record.
+/// Only the fields present in the body are written, in a single update that returns the new row.
+/// When an `If-Match` header is sent, that update only matches the task if it has not
+/// changed since that `ETag` was handed out, and answers `412` otherwise; without one,
+/// the last edit wins.
+pub async fn update_task_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    headers: HeaderMap,
+    ValidatedRequest(body): ValidatedRequest<UpdateTaskEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    let expected_versions = if_match_versions(&headers)?;
+    let (retrieved_task, _) = get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let task_owner_id = retrieved_task.user_id;
+    let was_completed = retrieved_task.completed;
+    let before = task_fields(&retrieved_task);
+
+    let recurrence = body
+        .recurrence
+        .map(|recurrence| recurrence.map(normalize_recurrence));
+
+    // The schedule rule spans two fields, so it is checked against the ones that are not sent.
+    let repeats = match &recurrence {
+        Some(recurrence) => recurrence.is_some(),
+        None => retrieved_task.recurrence.is_some(),
+    };
+    let has_due_date = body.due_date.unwrap_or(retrieved_task.due_date).is_some();
+    if repeats && !has_due_date {
+        let error_response = json!({
+            "status": "fail",
+            "message": "A recurring task needs a due date"
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    // A task can only go to a column of the board it ends up on.
+    if let Some(Some(status_id)) = &body.status_id {
+        let project_id = body.project_id.unwrap_or(retrieved_task.project_id);
+        check_status_board(client, status_id, &task_owner_id, project_id.as_ref()).await?;
+    }
+
+    let task_schema = UpdateTaskSchema {
+        title: body.title,
+        description: body.description,
+        completed: body.completed,
+        project_id: body.project_id,
+        auto_complete: body.auto_complete,
+        due_date: body.due_date,
+        recurrence,
+        status_id: body.status_id,
+        updated_at: Local::now(),
+    };
+
+    // Tags are per user, so those of a shared task are the ones of its owner.
+    if let Some(tag_ids) = &body.tag_ids {
+        check_tags_ownership(client, &task_owner_id, tag_ids).await?;
+    }
+    if let Some(Some(project_id)) = &body.project_id {
+        get_project_with_role(client, project_id, &user.id, Role::Editor).await?;
+    }
+
+    let mut query = client
+        .from("tasks")
+        .select(TASK_COLUMNS)
+        .update(json!(task_schema).to_string())
+        .eq("id", id.to_string())
+        .is("deleted_at", "null");
+    if let Some(versions) = &expected_versions {
+        // An edit made since the ETag was handed out bumped the version, so nothing matches.
+        query = query.in_(
+            "version",
+            versions.iter().map(|version| version.to_string()),
+        );
+    }
+
+    let response = query.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let task_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let task_data: Vec<TaskModel> = serde_json::from_str(&task_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match task_data.into_iter().next() {
+        Some(mut task) => {
+            if let Some(tag_ids) = body.tag_ids {
+                task.tag_ids = set_task_tags(client, &task.id, &tag_ids).await?;
+            }
+
+            // Completing an occurrence of a recurring task schedules the next one.
+            let next_task = if task.completed && !was_completed {
+                create_next_occurrence(client, &mut task).await?
+            } else {
+                None
+            };
+
+            record_revision(client, &id, &user.id, &before, &task_fields(&task)).await?;
+
+            emit_task_update(&state, &task, was_completed, &user.id);
+            if let Some(next_task) = &next_task {
+                emit_task_event(&state, TaskEvent::Created, next_task, &user.id);
+            }
+
+            let etag = task_etag(&task);
+            let update_task = json!({ "status": "success", "data": json!({
+                "task": json!(task),
+                "next_task": json!(next_task)
+            })});
+
+            Ok((StatusCode::OK, [(ETAG, etag)], Json(update_task)))
+        }
+        None if expected_versions.is_some() => Err(conflict_error()),
+        None => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Task with ID: {} not found", id)
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Delete a Record.
+/// The task is moved to the trash, from where it can be restored until it is purged.
+pub async fn delete_task_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    let (retrieved_task, _) = get_task_with_role(client, &id, &user.id, Role::Owner).await?;
+
     let response = client
         .from("tasks")
-        .select("*")
+        .update(json!({ "deleted_at": Local::now() }).to_string())
         .eq("id", &id.to_string())
         .execute()
         .await
@@ -183,12 +399,9 @@ pub async fn get_single_task_handler(
     })?;
 
     match task_data.iter().next() {
-        Some(task) => {
-            let retrieved_task = json!({ "status": "success", "data": json!({
-                "task": json!(task)
-            })});
-
-            Ok((StatusCode::OK, Json(retrieved_task)))
+        Some(_task) => {
+            emit_task_event(&state, TaskEvent::Deleted, &retrieved_task, &user.id);
+            Ok(StatusCode::NO_CONTENT)
         }
         None => {
             let error_response = json!({
@@ -200,18 +413,131 @@ pub async fn get_single_task_handler(
     }
 }
 
-/// Axum Route Handler to Edit a Record.
-pub async fn update_task_handler(
-    Path(id): Path<Uuid>,
-    State(data): State<Arc<RwLock<AppState>>>,
-    Json(body): Json<UpdateTaskBody>,
+/// Axum Route Handler to Preview the next occurrences of a recurrence rule.
+pub async fn preview_recurrence_handler(
+    ValidatedRequest(body): ValidatedRequest<RecurrencePreviewEntry>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    let client = &data.write().await.client;
+    let recurrence = parse_recurrence(&body.rule)?;
+
+    let start = body.start.unwrap_or_else(Local::now);
+    let occurrences = recurrence.occurrences(start, body.count.unwrap_or(DEFAULT_OCCURRENCES));
+
+    let preview = json!({ "status": "success", "data": json!({
+        "rule": recurrence.to_string(),
+        "occurrences": json!(occurrences)
+    })});
+
+    Ok((StatusCode::OK, Json(preview)))
+}
+
+/****** Versions ******/
+
+/// The `ETag` of a task, which changes with every edit of it.
+pub fn task_etag(task: &TaskModel) -> String {
+    format!("\"{}\"", task.version)
+}
+
+/// Checks the `If-Match` header of a request, if any, against the current `ETag` of the task.
+/// Returns the version that the task must still have when it is written, or `None` when
+/// the request does not ask for one, so that the check holds against concurrent edits too.
+pub fn check_if_match(
+    headers: &HeaderMap,
+    task: &TaskModel,
+) -> Result<Option<i32>, (StatusCode, Json<Value>)> {
+    let Some(if_match) = headers.get(IF_MATCH) else {
+        return Ok(None);
+    };
+    let Ok(if_match) = if_match.to_str() else {
+        return Err(conflict_error());
+    };
+
+    let etag = task_etag(task);
+    let tags: Vec<&str> = if_match
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .collect();
+
+    if tags.contains(&"*") {
+        Ok(None)
+    } else if tags.contains(&etag.as_str()) {
+        Ok(Some(task.version))
+    } else {
+        Err(conflict_error())
+    }
+}
+
+/// Reads the versions of a task that the `If-Match` header of a request accepts,
+/// for the update to be filtered on. Returns `None` when any version will do.
+pub fn if_match_versions(
+    headers: &HeaderMap,
+) -> Result<Option<Vec<i32>>, (StatusCode, Json<Value>)> {
+    let Some(if_match) = headers.get(IF_MATCH) else {
+        return Ok(None);
+    };
+    let Ok(if_match) = if_match.to_str() else {
+        return Err(conflict_error());
+    };
+
+    let tags: Vec<&str> = if_match
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .collect();
+    if tags.contains(&"*") {
+        return Ok(None);
+    }
+
+    // Tags that are not ETags of this server cannot match any version.
+    let versions: Vec<i32> = tags
+        .iter()
+        .filter_map(|tag| tag.strip_prefix('"')?.strip_suffix('"')?.parse().ok())
+        .collect();
+    if versions.is_empty() {
+        return Err(conflict_error());
+    }
+
+    Ok(Some(versions))
+}
+
+/// The error of an edit made to an outdated version of a task.
+pub fn conflict_error() -> (StatusCode, Json<Value>) {
+    let error_response = json!({
+        "status": "fail",
+        "message": "The task was changed by someone else since you loaded it"
+    });
+
+    (StatusCode::PRECONDITION_FAILED, Json(error_response))
+}
+
+/****** Recurrence ******/
+
+/// Creates the occurrence that follows a completed task of a recurring series:
+/// a copy of it due at the next date of the rule, with its tags and an unchecked
+/// checklist. The series then continues on the new task, so the completed one
+/// stops repeating and completing it again does not create another copy.
+pub async fn create_next_occurrence(
+    client: &Postgrest,
+    task: &mut TaskModel,
+) -> Result<Option<TaskModel>, (StatusCode, Json<Value>)> {
+    let Some(Ok(recurrence)) = task.recurrence.as_deref().map(str::parse::<Recurrence>) else {
+        return Ok(None);
+    };
+    let Some(due_date) = recurrence.next_after(task.due_date.unwrap_or_else(Local::now)) else {
+        return Ok(None);
+    };
+
+    let task_schema = CreateTaskSchema {
+        title: task.title.clone(),
+        description: task.description.clone(),
+        user_id: task.user_id.to_string(),
+        project_id: task.project_id.map(|project_id| project_id.to_string()),
+        auto_complete: task.auto_complete,
+        due_date: Some(due_date),
+        recurrence: task.recurrence.clone(),
+    };
 
     let response = client
         .from("tasks")
-        .select("*")
-        .eq("id", &id.to_string())
+        .insert(json!(task_schema).to_string())
         .execute()
         .await
         .map_err(|err| {
@@ -241,31 +567,48 @@ pub async fn update_task_handler(
         (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
     })?;
 
-    let retrieved_task = task_data.into_iter().next().ok_or_else(|| {
+    let Some(mut next_task) = task_data.into_iter().next() else {
         let error_response = json!({
-            "status": "fail",
-            "message": format!("Task with ID: {} not found", &id.to_string())
+            "status": "error",
+            "message": "Something bad happened while creating the next occurrence"
         });
+        return Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)));
+    };
 
-        (StatusCode::NOT_FOUND, Json(error_response))
-    })?;
+    next_task.tag_ids = set_task_tags(client, &next_task.id, &task.tag_ids).await?;
 
-    let task_schema = UpdateTaskSchema {
-        title: body.title.to_owned().unwrap_or(retrieved_task.title),
-        description: body
-            .description
-            .to_owned()
-            .unwrap_or(retrieved_task.description),
-        completed: body
-            .completed
-            .to_owned()
-            .unwrap_or(retrieved_task.completed),
-    };
+    let items = get_task_items(client, &task.id).await?;
+    if !items.is_empty() {
+        let items_schema: Vec<CreateTaskItemSchema> = items
+            .into_iter()
+            .map(|item| CreateTaskItemSchema {
+                text: item.text,
+                position: item.position,
+                task_id: next_task.id.to_string(),
+            })
+            .collect();
 
-    let response = client
+        client
+            .from("task_items")
+            .insert(json!(items_schema).to_string())
+            .execute()
+            .await
+            .map_err(|err| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": format!("Database error: {}", err)
+                });
+
+                (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+            })?;
+
+        next_task.progress.total = items_schema.len();
+    }
+
+    client
         .from("tasks")
-        .update(&json!(task_schema).to_string())
-        .eq("id", &id.to_string())
+        .update(json!({ "recurrence": null }).to_string())
+        .eq("id", task.id.to_string())
         .execute()
         .await
         .map_err(|err| {
@@ -276,8 +619,72 @@ pub async fn update_task_handler(
 
             (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
         })?;
+    task.recurrence = None;
+    // Like any update, clearing the rule bumped the version of the task.
+    task.version += 1;
 
-    let task_response = response.text().await.map_err(|err| {
+    Ok(Some(next_task))
+}
+
+fn parse_recurrence(rule: &str) -> Result<Recurrence, (StatusCode, Json<Value>)> {
+    rule.parse::<Recurrence>().map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Invalid recurrence: {}", err)
+        });
+
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    })
+}
+
+/// Stores rules in a single canonical form, e.g. `freq=weekly` as `FREQ=WEEKLY`.
+pub fn normalize_recurrence(rule: String) -> String {
+    match rule.parse::<Recurrence>() {
+        Ok(recurrence) => recurrence.to_string(),
+        Err(_) => rule,
+    }
+}
+
+/****** Changes ******/
+
+/// Axum Route Handler to Fetch the tasks changed since a moment, oldest change first,
+/// for a client to catch up after being offline. Deleted tasks come too, with `deleted_at` set,
+/// so that they can be dropped. `cursor` and `after` are the `since` and `after` of the next
+/// request, and `has_more` tells whether it has to be made right away. Changes are ordered
+/// by `changed_at` then by task id, so a page that ends among changes made at the same
+/// moment goes on with the next of them. The last page keeps the cursor a little
+/// in the past, as changes still being committed are stamped earlier than they show up:
+/// the next request returns the recent changes again, which the client already has.
+pub async fn get_task_changes_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(changes): Query<TaskChangesQuery>,
+    Query(filter): Query<FilterTasksQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let since = changes.since.to_rfc3339();
+    let request = match changes.after {
+        Some(after) => client.from("tasks").or(format!(
+            "changed_at.gt.\"{since}\",and(changed_at.eq.\"{since}\",id.gt.{after})"
+        )),
+        None => client.from("tasks").gt("changed_at", &since),
+    };
+    let request = request
+        .order("changed_at.asc,id.asc")
+        .limit(CHANGES_PAGE_SIZE + 1);
+    let request = scope_tasks(client, request, &user.id, &filter).await?;
+
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tasks_response = response.text().await.map_err(|err| {
         let error_response = json!({
             "status": "fail",
             "message": format!("Error parsing json response: {}", err)
@@ -286,7 +693,7 @@ pub async fn update_task_handler(
         (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
     })?;
 
-    let task_data: Vec<TaskModel> = serde_json::from_str(&task_response).map_err(|err| {
+    let mut tasks_data: Vec<TaskModel> = serde_json::from_str(&tasks_response).map_err(|err| {
         let error_response = json!({
             "status": "fail",
             "message": format!("Error deserializing response: {}", err)
@@ -295,35 +702,64 @@ pub async fn update_task_handler(
         (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
     })?;
 
-    match task_data.iter().next() {
-        Some(task) => {
-            let update_task = json!({ "status": "success", "data": json!({
-                "task": json!(task)
-            })});
+    let has_more = tasks_data.len() > CHANGES_PAGE_SIZE;
+    tasks_data.truncate(CHANGES_PAGE_SIZE);
+    let last_change = tasks_data
+        .last()
+        .and_then(|task| Some((task.changed_at?, task.id)));
+    let settled = Local::now() - chrono::Duration::seconds(CHANGES_MARGIN_SECS);
+    let (cursor, after) = match last_change {
+        // A full page moves on past its last change, so that a burst of changes cannot stall it.
+        Some((changed_at, id)) if has_more || changed_at <= settled => (changed_at, Some(id)),
+        Some(_) if settled > changes.since => (settled, None),
+        _ => (changes.since, changes.after),
+    };
 
-            Ok((StatusCode::OK, Json(update_task)))
-        }
-        None => {
-            let error_response = json!({
-                "status": "error",
-                "message": format!("Something bad happened while updating the task")
-            });
-            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
-        }
-    }
+    let task_changes = json!({ "status": "success", "data": json!({
+        "tasks": json!(tasks_data),
+        "cursor": cursor,
+        "after": after,
+        "has_more": has_more
+    })});
+
+    Ok((StatusCode::OK, Json(task_changes)))
 }
 
-/// Axum Route Handler to Delete a Record.
-pub async fn delete_task_handler(
+/****** Trash ******/
+
+/// Axum Route Handler to Fetch the Tasks in the trash of the logged-in user,
+/// most recently deleted first.
+pub async fn get_trash_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let tasks_data = get_trashed_tasks(client, Some(&user.id), None).await?;
+
+    let retrieved_tasks = json!({ "status": "success", "data": json!({
+        "tasks": json!(tasks_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_tasks)))
+}
+
+/// Axum Route Handler to Restore a Task from the trash.
+pub async fn restore_task_handler(
     Path(id): Path<Uuid>,
     State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
 ) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    let client = &data.write().await.client;
+    let state = data.write().await;
+    let client = &state.client;
+
+    get_trashed_task_with_role(client, &id, &user.id, Role::Owner).await?;
 
     let response = client
         .from("tasks")
-        .delete()
-        .eq("id", &id.to_string())
+        .select(TASK_COLUMNS)
+        .update(json!({ "deleted_at": null }).to_string())
+        .eq("id", id.to_string())
         .execute()
         .await
         .map_err(|err| {
@@ -353,20 +789,901 @@ pub async fn delete_task_handler(
         (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
     })?;
 
-    match task_data.iter().next() {
-        Some(_task) => Ok(StatusCode::NO_CONTENT),
+    match task_data.into_iter().next() {
+        Some(task) => {
+            // The task shows up again where it was.
+            emit_task_event(&state, TaskEvent::Updated, &task, &user.id);
+
+            let restored_task = json!({ "status": "success", "data": json!({
+                "task": json!(task)
+            })});
+
+            Ok((StatusCode::OK, Json(restored_task)))
+        }
         None => {
             let error_response = json!({
-                "status": "fail",
-                "message": format!("Task with ID: {} not found", &id.to_string())
+                "status": "error",
+                "message": "Something bad happened while restoring the task"
             });
-            Err((StatusCode::NOT_FOUND, Json(error_response)))
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
         }
     }
 }
 
-/*
- * ORDEN DE LOS EXTRACTORES. VER:
- * https://docs.rs/axum/latest/axum/extract/index.html#the-order-of-extractors
- * (VIENE DE ESTE ERROR: https://github.com/tokio-rs/axum/discussions/641)
- */
+/// Axum Route Handler to Permanently Delete a Task that is in the trash.
+pub async fn delete_task_permanently_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    get_trashed_task_with_role(client, &id, &user.id, Role::Owner).await?;
+    purge_tasks(client, state.blobs.as_ref(), &[id.to_string()]).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Axum Route Handler to Empty the trash of the logged-in user.
+pub async fn empty_trash_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    let task_ids: Vec<String> = get_trashed_tasks(client, Some(&user.id), None)
+        .await?
+        .iter()
+        .map(|task| task.id.to_string())
+        .collect();
+    purge_tasks(client, state.blobs.as_ref(), &task_ids).await?;
+
+    let emptied_trash = json!({ "status": "success", "data": json!({
+        "deleted": task_ids.len()
+    })});
+
+    Ok((StatusCode::OK, Json(emptied_trash)))
+}
+
+/// Gets the tasks in the trash, optionally only those of a user
+/// or those deleted before a given moment.
+pub async fn get_trashed_tasks(
+    client: &Postgrest,
+    user_id: Option<&Uuid>,
+    deleted_before: Option<DateTime<Local>>,
+) -> Result<Vec<TaskModel>, (StatusCode, Json<Value>)> {
+    let mut request = client
+        .from("tasks")
+        .select(TASK_COLUMNS)
+        .not("is", "deleted_at", "null")
+        .order("deleted_at.desc");
+
+    if let Some(user_id) = user_id {
+        request = request.eq("user_id", user_id.to_string());
+    }
+    if let Some(deleted_before) = deleted_before {
+        request = request.lt("deleted_at", deleted_before.to_rfc3339());
+    }
+
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tasks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    serde_json::from_str(&tasks_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })
+}
+
+/// Deletes trashed tasks for good, together with the content of their attachments.
+/// Their items, tags, comments, shares and attachment rows go with them by cascade.
+pub async fn purge_tasks(
+    client: &Postgrest,
+    blobs: &dyn BlobStore,
+    task_ids: &[String],
+) -> Result<(), (StatusCode, Json<Value>)> {
+    if task_ids.is_empty() {
+        return Ok(());
+    }
+
+    let attachment_keys = get_attachment_keys(client, task_ids).await?;
+
+    client
+        .from("tasks")
+        .delete()
+        .in_("id", task_ids)
+        .not("is", "deleted_at", "null")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    delete_blobs(blobs, &attachment_keys).await;
+
+    Ok(())
+}
+
+/****** Ordering ******/
+
+/// Axum Route Handler to Move a Task between two others of the list.
+/// Only the moved task changes: it takes a position between those of its new neighbours,
+/// unless they are too close for one to fit, in which case its list is renumbered first.
+pub async fn move_task_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<MoveTaskEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    let (task, _) = get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let mut renumbered = false;
+    let position = loop {
+        let neighbours = get_neighbour_positions(client, &task, &body, &user.id).await?;
+
+        match position_between(neighbours) {
+            Some(position) => break position,
+            None if !renumbered => {
+                renumber_task_positions(client, &id).await?;
+                renumbered = true;
+            }
+            None => {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": "The neighbours of the task were moved at the same time, please try again"
+                });
+                return Err((StatusCode::CONFLICT, Json(error_response)));
+            }
+        }
+    };
+
+    let response = client
+        .from("tasks")
+        .select(TASK_COLUMNS)
+        .update(json!({ "position": position }).to_string())
+        .eq("id", id.to_string())
+        .is("deleted_at", "null")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let task_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let task_data: Vec<TaskModel> = serde_json::from_str(&task_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    match task_data.into_iter().next() {
+        Some(task) => {
+            emit_task_event(&state, TaskEvent::Updated, &task, &user.id);
+
+            let moved_task = json!({ "status": "success", "data": json!({
+                "task": json!(task)
+            })});
+
+            Ok((StatusCode::OK, Json(moved_task)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Task with ID: {} not found", id)
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
+
+/// Gets the positions of the new neighbours of a moved task, checking that they are
+/// in its list and column (the same project, or the tasks of its owner outside of any)
+/// and given in order, so that a wrong move fails before anything is written.
+async fn get_neighbour_positions(
+    client: &Postgrest,
+    task: &TaskModel,
+    body: &MoveTaskEntry,
+    user_id: &Uuid,
+) -> Result<[Option<f64>; 2], (StatusCode, Json<Value>)> {
+    let bad_request = |message: &str| {
+        let error_response = json!({
+            "status": "fail",
+            "message": message
+        });
+        (StatusCode::BAD_REQUEST, Json(error_response))
+    };
+
+    let mut neighbours = [None, None];
+    for (neighbour, neighbour_id) in neighbours.iter_mut().zip([body.before, body.after]) {
+        if let Some(neighbour_id) = neighbour_id {
+            if neighbour_id == task.id {
+                return Err(bad_request("A task cannot be moved next to itself"));
+            }
+            let (neighbour_task, _) =
+                get_task_with_role(client, &neighbour_id, user_id, Role::Viewer).await?;
+
+            let same_list = neighbour_task.project_id == task.project_id
+                && (task.project_id.is_some() || neighbour_task.user_id == task.user_id);
+            if !same_list || neighbour_task.status_id != task.status_id {
+                return Err(bad_request(
+                    "A task can only be moved next to tasks of its own list and column",
+                ));
+            }
+            *neighbour = Some(neighbour_task.position);
+        }
+    }
+
+    if let [Some(before), Some(after)] = neighbours {
+        if before >= after {
+            return Err(bad_request(
+                "The task before must come before the task after",
+            ));
+        }
+    }
+
+    Ok(neighbours)
+}
+
+/// The position of a task moved between two others: halfway between them, or one
+/// past the only neighbour given. `None` when no double lies strictly between them,
+/// or when there is no neighbour to place it by.
+fn position_between(neighbours: [Option<f64>; 2]) -> Option<f64> {
+    let position = match neighbours {
+        [Some(before), Some(after)] => before + (after - before) / 2.0,
+        [Some(before), None] => before + 1.0,
+        [None, Some(after)] => after - 1.0,
+        [None, None] => return None,
+    };
+
+    let [before, after] = neighbours;
+    let fits =
+        before.is_none_or(|before| position > before) && after.is_none_or(|after| position < after);
+
+    fits.then_some(position)
+}
+
+/// Spaces out again the positions of the tasks that share a list with the given one,
+/// through the `renumber_task_positions` database function.
+async fn renumber_task_positions(
+    client: &Postgrest,
+    task_id: &Uuid,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    client
+        .rpc(
+            "renumber_task_positions",
+            json!({ "p_task_id": task_id }).to_string(),
+        )
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(())
+}
+
+/****** Bulk actions ******/
+
+/// Axum Route Handler to apply one action to many Tasks at once.
+/// Every task is checked and changed on its own, so the ones that cannot be
+/// changed are reported next to the others instead of failing the whole request.
+pub async fn bulk_tasks_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<BulkTaskEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    // All the tasks go to the same project, so it is checked once for all of them.
+    if let BulkAction::Move {
+        project_id: Some(project_id),
+    } = &body.action
+    {
+        get_project_with_role(client, project_id, &user.id, Role::Editor).await?;
+    }
+
+    let mut task_ids = body.ids;
+    let mut seen = Vec::with_capacity(task_ids.len());
+    task_ids.retain(|id| {
+        let is_new = !seen.contains(id);
+        seen.push(*id);
+        is_new
+    });
+
+    let mut results = Vec::with_capacity(task_ids.len());
+    for id in &task_ids {
+        let result = match apply_bulk_action(&state, id, &user.id, &body.action).await {
+            Ok(()) => json!({ "id": id, "status": "success" }),
+            Err((_, Json(error))) => json!({
+                "id": id,
+                "status": "fail",
+                "message": error["message"]
+            }),
+        };
+        results.push(result);
+    }
+
+    let failed = results
+        .iter()
+        .filter(|result| result["status"] == "fail")
+        .count();
+
+    let bulk_result = json!({ "status": "success", "data": json!({
+        "results": json!(results),
+        "succeeded": results.len() - failed,
+        "failed": failed
+    })});
+
+    Ok((StatusCode::OK, Json(bulk_result)))
+}
+
+/// Axum Route Handler to move the completed Tasks of the logged-in user to the trash,
+/// optionally only those of a project.
+pub async fn clear_completed_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(query): Query<ClearCompletedQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    // Only the owner of a task can delete it, so the tasks of others
+    // in a shared project are left where they are.
+    let mut request = client
+        .from("tasks")
+        .select("*")
+        .update(json!({ "deleted_at": Local::now() }).to_string())
+        .eq("user_id", user.id.to_string())
+        .eq("completed", "true")
+        .is("deleted_at", "null");
+
+    match query.project.as_deref() {
+        None | Some("") => {}
+        Some("inbox") => request = request.is("project_id", "null"),
+        Some(project) => {
+            let project_id = Uuid::parse_str(project).map_err(|_| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": "The project filter must be a project ID or 'inbox'"
+                });
+
+                (StatusCode::BAD_REQUEST, Json(error_response))
+            })?;
+            request = request.eq("project_id", project_id.to_string());
+        }
+    }
+
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tasks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tasks_data: Vec<TaskModel> = serde_json::from_str(&tasks_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    for task in &tasks_data {
+        emit_task_event(&state, TaskEvent::Deleted, task, &user.id);
+    }
+
+    let cleared_tasks = json!({ "status": "success", "data": json!({
+        "deleted": tasks_data.len()
+    })});
+
+    Ok((StatusCode::OK, Json(cleared_tasks)))
+}
+
+/// Applies a bulk action to a single task, with the same checks as the
+/// handler that does it for one task alone.
+async fn apply_bulk_action(
+    state: &AppState,
+    id: &Uuid,
+    user_id: &Uuid,
+    action: &BulkAction,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let client = &state.client;
+    let required_role = match action {
+        BulkAction::Delete => Role::Owner,
+        _ => Role::Editor,
+    };
+    let (retrieved_task, _) = get_task_with_role(client, id, user_id, required_role).await?;
+
+    let (completed, project_id) = match action {
+        BulkAction::Complete => (Some(true), None),
+        BulkAction::Uncomplete => (Some(false), None),
+        BulkAction::Move { project_id } => (None, Some(*project_id)),
+        BulkAction::Delete => {
+            client
+                .from("tasks")
+                .update(json!({ "deleted_at": Local::now() }).to_string())
+                .eq("id", id.to_string())
+                .execute()
+                .await
+                .map_err(|err| {
+                    let error_response = json!({
+                        "status": "fail",
+                        "message": format!("Database error: {}", err)
+                    });
+
+                    (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+                })?;
+
+            emit_task_event(state, TaskEvent::Deleted, &retrieved_task, user_id);
+            return Ok(());
+        }
+        // Tags are per user, so those of a shared task are the ones of its owner.
+        BulkAction::AddTag { tag_id } => {
+            check_tags_ownership(client, &retrieved_task.user_id, &[*tag_id]).await?;
+            if !retrieved_task.tag_ids.contains(tag_id) {
+                let mut tag_ids = retrieved_task.tag_ids;
+                tag_ids.push(*tag_id);
+                set_task_tags(client, id, &tag_ids).await?;
+            }
+
+            return Ok(());
+        }
+        BulkAction::RemoveTag { tag_id } => {
+            if retrieved_task.tag_ids.contains(tag_id) {
+                let mut tag_ids = retrieved_task.tag_ids;
+                tag_ids.retain(|task_tag_id| task_tag_id != tag_id);
+                set_task_tags(client, id, &tag_ids).await?;
+            }
+
+            return Ok(());
+        }
+    };
+
+    let was_completed = retrieved_task.completed;
+    let before = task_fields(&retrieved_task);
+
+    let task_schema = UpdateTaskSchema {
+        title: None,
+        description: None,
+        completed,
+        project_id,
+        auto_complete: None,
+        due_date: None,
+        recurrence: None,
+        status_id: None,
+        updated_at: Local::now(),
+    };
+
+    let response = client
+        .from("tasks")
+        .select(TASK_COLUMNS)
+        .update(json!(task_schema).to_string())
+        .eq("id", id.to_string())
+        .is("deleted_at", "null")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let task_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let task_data: Vec<TaskModel> = serde_json::from_str(&task_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let Some(mut task) = task_data.into_iter().next() else {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Task with ID: {} not found", id)
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    };
+
+    // Completing an occurrence of a recurring task schedules the next one.
+    if task.completed && !was_completed {
+        if let Some(next_task) = create_next_occurrence(client, &mut task).await? {
+            emit_task_event(state, TaskEvent::Created, &next_task, user_id);
+        }
+    }
+
+    record_revision(client, id, user_id, &before, &task_fields(&task)).await?;
+
+    emit_task_update(state, &task, was_completed, user_id);
+    Ok(())
+}
+
+/****** Filters ******/
+
+/// Query for the live tasks the user can see, narrowed by the filters of the task list
+/// (see `scope_tasks`). Ties in position are broken by id so that the tasks can be read page by page.
+pub async fn filter_tasks(
+    client: &Postgrest,
+    user_id: &Uuid,
+    query: &FilterTasksQuery,
+) -> Result<Builder, (StatusCode, Json<Value>)> {
+    let request = client
+        .from("tasks")
+        .is("deleted_at", "null")
+        .order("position.asc,id.asc");
+
+    scope_tasks(client, request, user_id, query).await
+}
+
+/// Narrows a query on the tasks by the filters of the task list: the own tasks of the user,
+/// those of their inbox or those of a project they have access to, optionally with any
+/// or all of some tags. It also selects the `TASK_COLUMNS`, which the tag filter joins onto.
+async fn scope_tasks(
+    client: &Postgrest,
+    mut request: Builder,
+    user_id: &Uuid,
+    query: &FilterTasksQuery,
+) -> Result<Builder, (StatusCode, Json<Value>)> {
+    // The tasks of a project are listed to everyone it is shared with.
+    match query.project.as_deref() {
+        None | Some("") => request = request.eq("user_id", user_id.to_string()),
+        Some("inbox") => {
+            request = request
+                .eq("user_id", user_id.to_string())
+                .is("project_id", "null")
+        }
+        Some(project) => {
+            let project_id = Uuid::parse_str(project).map_err(|_| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": "The project filter must be a project ID or 'inbox'"
+                });
+
+                (StatusCode::BAD_REQUEST, Json(error_response))
+            })?;
+            get_project_with_role(client, &project_id, user_id, Role::Viewer).await?;
+            request = request.eq("project_id", project_id.to_string());
+        }
+    }
+
+    if let Some(tags) = query.tags.as_deref().filter(|tags| !tags.is_empty()) {
+        let tag_ids = tags
+            .split(',')
+            .map(|tag| Uuid::parse_str(tag.trim()))
+            .collect::<Result<Vec<Uuid>, _>>()
+            .map_err(|_| {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": "The tags filter must be a comma-separated list of tag IDs"
+                });
+
+                (StatusCode::BAD_REQUEST, Json(error_response))
+            })?;
+
+        let match_all = match query.tag_mode.as_deref() {
+            None | Some("or") => false,
+            Some("and") => true,
+            Some(mode) => {
+                let error_response = json!({
+                    "status": "fail",
+                    "message": format!("Unknown tag_mode: {}, expected 'and' or 'or'", mode)
+                });
+                return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+            }
+        };
+
+        let (columns, filters) = tag_filter(&tag_ids, match_all);
+        request = request.select(format!("{}, {}", TASK_COLUMNS, columns));
+        for (column, filter_tag_ids) in filters {
+            request = request.in_(column, filter_tag_ids.iter().map(|id| id.to_string()));
+        }
+    } else {
+        request = request.select(TASK_COLUMNS);
+    }
+
+    Ok(request)
+}
+
+/// The inner joins on `task_tags` that keep the tasks with any (`match_all == false`)
+/// or all (`match_all == true`) of the given tags, and the filters on them: one join for
+/// any of the tags, one per tag for all of them. The query thus grows with the number
+/// of tags, not with the number of tasks that have them.
+fn tag_filter(tag_ids: &[Uuid], match_all: bool) -> (String, Vec<(String, Vec<Uuid>)>) {
+    let groups: Vec<Vec<Uuid>> = if match_all {
+        tag_ids.iter().map(|tag_id| vec![*tag_id]).collect()
+    } else {
+        vec![tag_ids.to_vec()]
+    };
+
+    let aliases: Vec<String> = (0..groups.len())
+        .map(|index| format!("tag_filter_{}", index))
+        .collect();
+    let columns = aliases
+        .iter()
+        .map(|alias| format!("{}:task_tags!inner(tag_id)", alias))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let filters = aliases
+        .iter()
+        .zip(groups)
+        .map(|(alias, group)| (format!("{}.tag_id", alias), group))
+        .collect();
+
+    (columns, filters)
+}
+
+/****** Tags of a task ******/
+
+/// Ensures that every tag to be attached to a task belongs to the user.
+async fn check_tags_ownership(
+    client: &Postgrest,
+    user_id: &Uuid,
+    tag_ids: &[Uuid],
+) -> Result<(), (StatusCode, Json<Value>)> {
+    if tag_ids.is_empty() {
+        return Ok(());
+    }
+
+    let response = client
+        .from("tags")
+        .select("*")
+        .eq("user_id", user_id.to_string())
+        .in_("id", tag_ids.iter().map(|id| id.to_string()))
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let tags_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let tags_data: Vec<TagModel> = serde_json::from_str(&tags_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    if let Some(unknown) = tag_ids
+        .iter()
+        .find(|id| !tags_data.iter().any(|tag| &tag.id == *id))
+    {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Tag with ID: {} not found", unknown)
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    Ok(())
+}
+
+/// Replaces the tags associated with a task and returns their (deduplicated) ids.
+async fn set_task_tags(
+    client: &Postgrest,
+    task_id: &Uuid,
+    tag_ids: &[Uuid],
+) -> Result<Vec<Uuid>, (StatusCode, Json<Value>)> {
+    let mut tag_ids = tag_ids.to_vec();
+    tag_ids.sort();
+    tag_ids.dedup();
+
+    client
+        .from("task_tags")
+        .delete()
+        .eq("task_id", task_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if tag_ids.is_empty() {
+        return Ok(tag_ids);
+    }
+
+    let task_tags = tag_ids
+        .iter()
+        .map(|tag_id| TaskTagSchema {
+            task_id: task_id.to_string(),
+            tag_id: tag_id.to_string(),
+        })
+        .collect::<Vec<_>>();
+
+    client
+        .from("task_tags")
+        .insert(json!(task_tags).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(tag_ids)
+}
+
+/*
+ * ORDEN DE LOS EXTRACTORES. VER:
+ * https://docs.rs/axum/latest/axum/extract/index.html#the-order-of-extractors
+ * (VIENE DE ESTE ERROR: https://github.com/tokio-rs/axum/discussions/641)
+ */
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header::IF_MATCH, HeaderMap, HeaderValue, StatusCode};
+
+    use uuid::Uuid;
+
+    use super::{if_match_versions, position_between, tag_filter};
+
+    #[test]
+    fn joins_the_tags_once_for_any_and_once_per_tag_for_all() {
+        let tag_ids = [Uuid::new_v4(), Uuid::new_v4()];
+
+        let (columns, filters) = tag_filter(&tag_ids, false);
+        assert_eq!(columns, "tag_filter_0:task_tags!inner(tag_id)");
+        assert_eq!(
+            filters,
+            vec![("tag_filter_0.tag_id".to_string(), tag_ids.to_vec())]
+        );
+
+        let (columns, filters) = tag_filter(&tag_ids, true);
+        assert_eq!(
+            columns,
+            "tag_filter_0:task_tags!inner(tag_id), tag_filter_1:task_tags!inner(tag_id)"
+        );
+        assert_eq!(
+            filters,
+            vec![
+                ("tag_filter_0.tag_id".to_string(), vec![tag_ids[0]]),
+                ("tag_filter_1.tag_id".to_string(), vec![tag_ids[1]]),
+            ]
+        );
+    }
+
+    fn if_match(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(IF_MATCH, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn filters_the_update_on_the_versions_of_if_match() {
+        assert_eq!(if_match_versions(&HeaderMap::new()).unwrap(), None);
+        assert_eq!(if_match_versions(&if_match("*")).unwrap(), None);
+        assert_eq!(
+            if_match_versions(&if_match("\"3\"")).unwrap(),
+            Some(vec![3])
+        );
+        assert_eq!(
+            if_match_versions(&if_match("W/\"3\", \"5\"")).unwrap(),
+            Some(vec![3, 5])
+        );
+        let error = if_match_versions(&if_match("\"abc\"")).unwrap_err();
+        assert_eq!(error.0, StatusCode::PRECONDITION_FAILED);
+    }
+
+    #[test]
+    fn moves_a_task_between_its_neighbours() {
+        assert_eq!(position_between([Some(1.0), Some(2.0)]), Some(1.5));
+        assert_eq!(position_between([Some(1.0), None]), Some(2.0));
+        assert_eq!(position_between([None, Some(1.0)]), Some(0.0));
+        assert_eq!(position_between([None, None]), None);
+    }
+
+    #[test]
+    fn needs_a_renumbering_once_the_neighbours_are_too_close() {
+        let mut after = 2.0;
+        let mut moves = 0;
+        while let Some(position) = position_between([Some(1.0), Some(after)]) {
+            assert!(1.0 < position && position < after);
+            after = position;
+            moves += 1;
+        }
+
+        assert!(moves > 40);
+        assert_eq!(
+            position_between([Some(1.0), Some(f64::from_bits(1.0f64.to_bits() + 1))]),
+            None
+        );
+        assert_eq!(position_between([Some(1.0), Some(1.0)]), None);
+    }
+}
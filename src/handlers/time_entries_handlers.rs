@@ -0,0 +1,429 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{DateTime, Days, Local, NaiveDate, TimeZone};
+use postgrest::{Builder, Postgrest};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    models::{ReportTimeEntryModel, Role, TimeEntryModel, UserModel},
+    permissions::get_task_with_role,
+    schemas::{CreateTimeEntrySchema, TimeReportQuery},
+    validators::{TimeEntryEntry, ValidatedRequest},
+    AppState,
+};
+
+/// Columns selected for a time entry: its own columns plus the title of its task.
+const TIME_ENTRY_COLUMNS: &str = "*, task:tasks(title)";
+/// Columns selected for the time report: what each entry is grouped by.
+const REPORT_COLUMNS: &str =
+    "started_at, ended_at, task:tasks(project:projects(id, name), task_tags(tag:tags(id, name)))";
+/// Number of days in the report when the request does not say otherwise.
+const DEFAULT_REPORT_DAYS: u64 = 7;
+/// Longest range of days a report can cover.
+const MAX_REPORT_DAYS: i64 = 366;
+
+/****** Timer ******/
+
+/// Axum Route Handler to Fetch the running timer of the logged-in user, if any.
+pub async fn get_timer_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let request = client
+        .from("time_entries")
+        .select(TIME_ENTRY_COLUMNS)
+        .eq("user_id", user.id.to_string())
+        .is("ended_at", "null");
+    let time_entry_data = execute_time_entries(request).await?;
+
+    let retrieved_timer = json!({ "status": "success", "data": json!({
+        "time_entry": json!(time_entry_data.first())
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_timer)))
+}
+
+/// Axum Route Handler to Start a timer on a Task.
+/// A user has a single running timer, so the one running on another task is stopped.
+pub async fn start_timer_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let now = Local::now();
+    let stopped = stop_timer(client, &user.id, now).await?;
+
+    let time_entry_schema = CreateTimeEntrySchema {
+        started_at: now,
+        ended_at: None,
+        note: None,
+        task_id: id.to_string(),
+        user_id: user.id.to_string(),
+    };
+
+    let time_entry = insert_time_entry(client, &time_entry_schema).await?;
+
+    let started_timer = json!({ "status": "success", "data": json!({
+        "time_entry": json!(time_entry),
+        "stopped": json!(stopped)
+    })});
+
+    Ok((StatusCode::CREATED, Json(started_timer)))
+}
+
+/// Axum Route Handler to Stop the running timer of the logged-in user.
+pub async fn stop_timer_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    match stop_timer(client, &user.id, Local::now()).await? {
+        Some(time_entry) => {
+            let stopped_timer = json!({ "status": "success", "data": json!({
+                "time_entry": json!(time_entry)
+            })});
+
+            Ok((StatusCode::OK, Json(stopped_timer)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "fail",
+                "message": "There is no running timer"
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
+
+/****** Time entries of a task ******/
+
+/// Axum Route Handler to Fetch the Time Entries of a Task, latest first.
+pub async fn get_time_entries_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Viewer).await?;
+
+    let request = client
+        .from("time_entries")
+        .select("*")
+        .eq("task_id", id.to_string())
+        .order("started_at.desc");
+    let time_entries_data = execute_time_entries(request).await?;
+
+    let retrieved_time_entries = json!({ "status": "success", "data": json!({
+        "time_entries": json!(time_entries_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_time_entries)))
+}
+
+/// Axum Route Handler to Add time spent on a Task without the timer.
+pub async fn create_time_entry_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<TimeEntryEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let time_entry_schema = CreateTimeEntrySchema {
+        started_at: body.started_at,
+        ended_at: Some(body.ended_at),
+        note: body.note,
+        task_id: id.to_string(),
+        user_id: user.id.to_string(),
+    };
+
+    let time_entry = insert_time_entry(client, &time_entry_schema).await?;
+
+    let created_time_entry = json!({ "status": "success", "data": json!({
+        "time_entry": json!(time_entry)
+    })});
+
+    Ok((StatusCode::CREATED, Json(created_time_entry)))
+}
+
+/// Axum Route Handler to Delete a Time Entry.
+/// Users can delete their own entries, and the owner of the task any of them.
+pub async fn delete_time_entry_handler(
+    Path((id, entry_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let (_, role) = get_task_with_role(client, &id, &user.id, Role::Editor).await?;
+
+    let mut request = client
+        .from("time_entries")
+        .delete()
+        .eq("id", entry_id.to_string())
+        .eq("task_id", id.to_string());
+    if role < Role::Owner {
+        request = request.eq("user_id", user.id.to_string());
+    }
+
+    match execute_time_entries(request).await?.first() {
+        Some(_time_entry) => Ok(StatusCode::NO_CONTENT),
+        None => {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Time entry with ID: {} not found", entry_id)
+            });
+            Err((StatusCode::NOT_FOUND, Json(error_response)))
+        }
+    }
+}
+
+/****** Report ******/
+
+/// Axum Route Handler to Summarize the time tracked by the logged-in user over a range of days,
+/// grouped by day, project or tag. Entries are cut at the ends of the range (and of each day),
+/// and one with several tags counts towards each of them.
+pub async fn time_report_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    Query(query): Query<TimeReportQuery>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let group_by = query.group_by.as_deref().unwrap_or("day");
+    if !["day", "project", "tag"].contains(&group_by) {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Unknown group_by: {}, expected 'day', 'project' or 'tag'", group_by)
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let to = query.to.unwrap_or_else(|| Local::now().date_naive());
+    let from = query
+        .from
+        .unwrap_or_else(|| to - Days::new(DEFAULT_REPORT_DAYS - 1));
+    let days = (to - from).num_days() + 1;
+    if !(1..=MAX_REPORT_DAYS).contains(&days) {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("The report must cover between 1 and {} days", MAX_REPORT_DAYS)
+        });
+        return Err((StatusCode::BAD_REQUEST, Json(error_response)));
+    }
+
+    let range_start = local_midnight(from);
+    let range_end = local_midnight(to + Days::new(1));
+
+    // Entries that overlap the range: started before it ends and not ended before it starts.
+    let response = client
+        .from("time_entries")
+        .select(REPORT_COLUMNS)
+        .eq("user_id", user.id.to_string())
+        .lt("started_at", range_end.to_rfc3339())
+        .or(format!(
+            "ended_at.is.null,ended_at.gt.{}",
+            range_start.to_rfc3339()
+        ))
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let report_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let entries_data: Vec<ReportTimeEntryModel> =
+        serde_json::from_str(&report_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    // Groups by key, with their label and seconds.
+    let mut groups: BTreeMap<String, (String, i64)> = BTreeMap::new();
+    let mut add = |key: String, label: String, seconds: i64| {
+        groups.entry(key).or_insert((label, 0)).1 += seconds;
+    };
+    let now = Local::now();
+    let mut total_seconds = 0;
+
+    for entry in &entries_data {
+        let start = entry.started_at.max(range_start);
+        let end = entry.ended_at.unwrap_or(now).min(range_end);
+        if end <= start {
+            continue;
+        }
+        let seconds = (end - start).num_seconds();
+        total_seconds += seconds;
+
+        match group_by {
+            "day" => {
+                let mut day_start = start;
+                while day_start < end {
+                    let day = day_start.date_naive();
+                    let day_end = local_midnight(day + Days::new(1)).min(end);
+                    add(
+                        day.to_string(),
+                        day.to_string(),
+                        (day_end - day_start).num_seconds(),
+                    );
+                    day_start = day_end;
+                }
+            }
+            "project" => match &entry.task.project {
+                Some(project) => add(project.id.to_string(), project.name.clone(), seconds),
+                None => add("inbox".to_string(), "Inbox".to_string(), seconds),
+            },
+            _ if entry.task.task_tags.is_empty() => {
+                add("untagged".to_string(), "Untagged".to_string(), seconds)
+            }
+            _ => {
+                for task_tag in &entry.task.task_tags {
+                    add(
+                        task_tag.tag.id.to_string(),
+                        task_tag.tag.name.clone(),
+                        seconds,
+                    );
+                }
+            }
+        }
+    }
+
+    // Days read best in order, projects and tags with the most time first.
+    let mut groups: Vec<Value> = groups
+        .into_iter()
+        .map(|(key, (label, seconds))| json!({ "key": key, "label": label, "seconds": seconds }))
+        .collect();
+    if group_by != "day" {
+        groups.sort_by_key(|group| -group["seconds"].as_i64().unwrap_or_default());
+    }
+
+    let time_report = json!({ "status": "success", "data": json!({
+        "from": from,
+        "to": to,
+        "group_by": group_by,
+        "groups": groups,
+        "total_seconds": total_seconds
+    })});
+
+    Ok((StatusCode::OK, Json(time_report)))
+}
+
+/****** Helpers ******/
+
+/// The start of a day in the local time zone of the server.
+fn local_midnight(day: NaiveDate) -> DateTime<Local> {
+    let midnight = day.and_hms_opt(0, 0, 0).unwrap_or_default();
+
+    // Where a DST change skips midnight, the day starts when the clocks go forward.
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .unwrap_or_else(|| Local.from_utc_datetime(&midnight))
+}
+
+/// Stops the running timer of a user at the given moment, returning it.
+async fn stop_timer(
+    client: &Postgrest,
+    user_id: &Uuid,
+    at: DateTime<Local>,
+) -> Result<Option<TimeEntryModel>, (StatusCode, Json<Value>)> {
+    let request = client
+        .from("time_entries")
+        .select(TIME_ENTRY_COLUMNS)
+        .update(json!({ "ended_at": at }).to_string())
+        .eq("user_id", user_id.to_string())
+        .is("ended_at", "null");
+
+    Ok(execute_time_entries(request).await?.into_iter().next())
+}
+
+async fn insert_time_entry(
+    client: &Postgrest,
+    time_entry_schema: &CreateTimeEntrySchema,
+) -> Result<TimeEntryModel, (StatusCode, Json<Value>)> {
+    let request = client
+        .from("time_entries")
+        .select(TIME_ENTRY_COLUMNS)
+        .insert(json!(time_entry_schema).to_string());
+
+    match execute_time_entries(request).await?.into_iter().next() {
+        Some(time_entry) => Ok(time_entry),
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while recording the time entry"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Runs a request on the `time_entries` table and returns the rows it selected.
+async fn execute_time_entries(
+    request: Builder,
+) -> Result<Vec<TimeEntryModel>, (StatusCode, Json<Value>)> {
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let time_entries_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let time_entries_data: Vec<TimeEntryModel> = serde_json::from_str(&time_entries_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(time_entries_data)
+}
@@ -7,13 +7,14 @@ use axum::{
     response::IntoResponse,
     Extension, Json,
 };
-use axum_extra::extract::cookie::{Cookie, SameSite};
+use axum_extra::extract::cookie::Cookie;
 use jsonwebtoken::{encode, EncodingKey, Header};
 use rand_core::OsRng;
 use serde_json::{json, Value};
 use tokio::sync::RwLock;
 
 use crate::{
+    config::Config,
     models::UserModel,
     response::FilteredUser,
     schemas::{RegisterUserSchema, TokenClaims},
@@ -143,7 +144,7 @@ pub async fn register_user_handler(
     // We create the access token.
     let now = chrono::Utc::now();
     let iat = now.timestamp() as usize;
-    let exp = (now + chrono::Duration::minutes(60)).timestamp() as usize;
+    let exp = (now + data.read().await.env.jwt_expires_in).timestamp() as usize;
     let claims: TokenClaims = TokenClaims {
         sub: user_created.id.to_string(),
         exp,
@@ -160,12 +161,8 @@ pub async fn register_user_handler(
     // println!("Token creado: {:?}", token);
 
     // We create a container for the cookie that will send in the created token.
-    let cookie = Cookie::build("token", token.to_owned())
-        .path("/")
-        .max_age(time::Duration::hours(1))
-        .same_site(SameSite::Lax)
-        .http_only(true)
-        .finish();
+    let env = &data.read().await.env;
+    let cookie = token_cookie(env, token.to_owned(), time::Duration::minutes(env.jwt_maxage));
 
     // We filter the UserModel type to remove the password.
     let user_response = serde_json::json!({"status": "success","data": serde_json::json!({
@@ -254,7 +251,7 @@ pub async fn login_user_handler(
     // We create the access token.
     let now = chrono::Utc::now();
     let iat = now.timestamp() as usize;
-    let exp = (now + chrono::Duration::minutes(60)).timestamp() as usize;
+    let exp = (now + data.read().await.env.jwt_expires_in).timestamp() as usize;
     let claims: TokenClaims = TokenClaims {
         sub: user_found.id.to_string(),
         exp,
@@ -271,12 +268,8 @@ pub async fn login_user_handler(
     // println!("Token creado: {:?}", token);
 
     // We create a container for the cookie that will send in the created token.
-    let cookie = Cookie::build("token", token.to_owned())
-        .path("/")
-        .max_age(time::Duration::hours(1))
-        .same_site(SameSite::Lax)
-        .http_only(true)
-        .finish();
+    let env = &data.read().await.env;
+    let cookie = token_cookie(env, token.to_owned(), time::Duration::minutes(env.jwt_maxage));
 
     // We filter the UserModel type to remove the password.
     let user_response = serde_json::json!({"status": "success","data": serde_json::json!({
@@ -294,13 +287,14 @@ pub async fn login_user_handler(
 }
 
 /// User Logout Handler.
-pub async fn logout_handler() -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
-    let cookie = Cookie::build("token", "")
-        .path("/")
-        .max_age(time::Duration::hours(-1))
-        .same_site(SameSite::Lax)
-        .http_only(true)
-        .finish();
+pub async fn logout_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let cookie = token_cookie(
+        &data.read().await.env,
+        String::new(),
+        time::Duration::hours(-1),
+    );
 
     let mut response = Response::builder()
         .status(StatusCode::NO_CONTENT)
@@ -339,3 +333,14 @@ fn filter_user_record(user: &UserModel) -> FilteredUser {
         created_at: user.created_at,
     }
 }
+
+/// The cookie that holds the access token, with the attributes set in the configuration.
+fn token_cookie(env: &Config, token: String, max_age: time::Duration) -> Cookie<'static> {
+    Cookie::build("token", token)
+        .path("/")
+        .max_age(max_age)
+        .same_site(env.cookie_same_site)
+        .secure(env.cookie_secure)
+        .http_only(true)
+        .finish()
+}
@@ -0,0 +1,686 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Extension, Json,
+};
+use chrono::{DateTime, Local};
+use postgrest::Postgrest;
+use rand_core::{OsRng, RngCore};
+use serde_json::{json, Value};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    models::{UserModel, WebhookDeliveryModel, WebhookModel},
+    schemas::{
+        CreateWebhookDeliverySchema, CreateWebhookSchema, UpdateWebhookDeliverySchema,
+        UpdateWebhookSchema,
+    },
+    validators::{UpdateWebhookEntry, ValidatedRequest, WebhookEntry},
+    webhooks::{self, payload, TaskEvent, PING_EVENT},
+    AppState,
+};
+
+/// Columns selected for a delivery that is about to be sent: its own columns
+/// plus where it goes to.
+const DUE_DELIVERY_COLUMNS: &str = "*, webhook:webhooks(url, secret, active)";
+/// Number of random bytes in the secret of a webhook.
+const WEBHOOK_SECRET_BYTES: usize = 32;
+/// Number of deliveries listed in the log of a webhook.
+const DELIVERY_LOG_SIZE: usize = 50;
+/// Number of due deliveries sent in a round of the delivery job.
+const DELIVERY_BATCH_SIZE: usize = 20;
+
+/****** Webhooks ******/
+
+/// Axum Route Handler to Fetch the Webhooks of the logged-in user.
+pub async fn get_webhooks_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    let response = client
+        .from("webhooks")
+        .select("*")
+        .eq("user_id", user.id.to_string())
+        .order("created_at.asc")
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let webhooks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let webhooks_data: Vec<WebhookModel> =
+        serde_json::from_str(&webhooks_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let retrieved_webhooks = json!({ "status": "success", "data": json!({
+        "webhooks": json!(webhooks_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_webhooks)))
+}
+
+/// Axum Route Handler to Register a Webhook.
+/// The secret its payloads are signed with is generated here.
+pub async fn create_webhook_handler(
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<WebhookEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    check_webhook_url(&body.url, state.env.webhook_allow_local).await?;
+
+    let mut bytes = [0u8; WEBHOOK_SECRET_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+
+    let webhook_schema = CreateWebhookSchema {
+        url: body.url,
+        secret: bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+        events: body.events,
+        user_id: user.id.to_string(),
+    };
+
+    let response = client
+        .from("webhooks")
+        .insert(json!(webhook_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let webhooks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let webhooks_data: Vec<WebhookModel> =
+        serde_json::from_str(&webhooks_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match webhooks_data.into_iter().next() {
+        Some(webhook) => {
+            let created_webhook = json!({ "status": "success", "data": json!({
+                "webhook": json!(webhook)
+            })});
+
+            Ok((StatusCode::CREATED, Json(created_webhook)))
+        }
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while registering the webhook"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+/// Axum Route Handler to Update a Webhook: its URL, its events or whether it is active.
+pub async fn update_webhook_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+    ValidatedRequest(body): ValidatedRequest<UpdateWebhookEntry>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let state = data.write().await;
+    let client = &state.client;
+
+    if let Some(url) = &body.url {
+        check_webhook_url(url, state.env.webhook_allow_local).await?;
+    }
+
+    let webhook_schema = UpdateWebhookSchema {
+        url: body.url,
+        events: body.events,
+        active: body.active,
+    };
+
+    let response = client
+        .from("webhooks")
+        .update(json!(webhook_schema).to_string())
+        .eq("id", id.to_string())
+        .eq("user_id", user.id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let webhooks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let webhooks_data: Vec<WebhookModel> =
+        serde_json::from_str(&webhooks_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match webhooks_data.into_iter().next() {
+        Some(webhook) => {
+            let updated_webhook = json!({ "status": "success", "data": json!({
+                "webhook": json!(webhook)
+            })});
+
+            Ok((StatusCode::OK, Json(updated_webhook)))
+        }
+        None => Err(webhook_not_found(&id)),
+    }
+}
+
+/// Axum Route Handler to Delete a Webhook along with its delivery log.
+pub async fn delete_webhook_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let response = client
+        .from("webhooks")
+        .delete()
+        .eq("id", id.to_string())
+        .eq("user_id", user.id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let webhooks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let webhooks_data: Vec<WebhookModel> =
+        serde_json::from_str(&webhooks_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match webhooks_data.first() {
+        Some(_) => Ok(StatusCode::NO_CONTENT),
+        None => Err(webhook_not_found(&id)),
+    }
+}
+
+/// Axum Route Handler to Send a `ping` event to a Webhook, to check that it is reachable.
+pub async fn ping_webhook_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    let webhook = get_webhook(client, &id, &user.id).await?;
+
+    let delivery_schema = CreateWebhookDeliverySchema {
+        webhook_id: webhook.id.to_string(),
+        event: PING_EVENT.to_string(),
+        payload: payload(PING_EVENT, &user.id, json!({ "webhook_id": webhook.id })),
+    };
+    let delivery = insert_delivery(client, &delivery_schema).await?;
+
+    let queued_delivery = json!({ "status": "success", "data": json!({
+        "delivery": json!(delivery)
+    })});
+
+    Ok((StatusCode::ACCEPTED, Json(queued_delivery)))
+}
+
+/****** Deliveries ******/
+
+/// Axum Route Handler to Fetch the delivery log of a Webhook, latest first.
+pub async fn get_deliveries_handler(
+    Path(id): Path<Uuid>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.read().await.client;
+
+    get_webhook(client, &id, &user.id).await?;
+
+    let response = client
+        .from("webhook_deliveries")
+        .select("*")
+        .eq("webhook_id", id.to_string())
+        .order("created_at.desc")
+        .limit(DELIVERY_LOG_SIZE)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let deliveries_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let deliveries_data: Vec<WebhookDeliveryModel> = serde_json::from_str(&deliveries_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let retrieved_deliveries = json!({ "status": "success", "data": json!({
+        "deliveries": json!(deliveries_data)
+    })});
+
+    Ok((StatusCode::OK, Json(retrieved_deliveries)))
+}
+
+/// Axum Route Handler to Send an event of the log of a Webhook again.
+/// It is queued as a new delivery with the same payload, so the log keeps the old one.
+pub async fn redeliver_handler(
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+    State(data): State<Arc<RwLock<AppState>>>,
+    Extension(user): Extension<UserModel>,
+) -> Result<impl IntoResponse, (StatusCode, Json<Value>)> {
+    let client = &data.write().await.client;
+
+    get_webhook(client, &id, &user.id).await?;
+
+    let response = client
+        .from("webhook_deliveries")
+        .select("*")
+        .eq("id", delivery_id.to_string())
+        .eq("webhook_id", id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let deliveries_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let deliveries_data: Vec<WebhookDeliveryModel> = serde_json::from_str(&deliveries_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let Some(original) = deliveries_data.into_iter().next() else {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Delivery with ID: {} not found", delivery_id)
+        });
+        return Err((StatusCode::NOT_FOUND, Json(error_response)));
+    };
+
+    let delivery_schema = CreateWebhookDeliverySchema {
+        webhook_id: id.to_string(),
+        event: original.event,
+        payload: original.payload,
+    };
+    let delivery = insert_delivery(client, &delivery_schema).await?;
+
+    let queued_delivery = json!({ "status": "success", "data": json!({
+        "delivery": json!(delivery)
+    })});
+
+    Ok((StatusCode::ACCEPTED, Json(queued_delivery)))
+}
+
+/****** Events ******/
+
+/// Queues an event about a task for the webhooks of its owner that subscribe to it.
+pub async fn queue_task_event(
+    client: &Postgrest,
+    user_id: &Uuid,
+    event: TaskEvent,
+    event_payload: Value,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let response = client
+        .from("webhooks")
+        .select("*")
+        .eq("user_id", user_id.to_string())
+        .eq("active", "true")
+        .cs("events", format!("{{{}}}", event.name()))
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let webhooks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let webhooks_data: Vec<WebhookModel> =
+        serde_json::from_str(&webhooks_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    if webhooks_data.is_empty() {
+        return Ok(());
+    }
+
+    let delivery_schemas: Vec<CreateWebhookDeliverySchema> = webhooks_data
+        .iter()
+        .map(|webhook| CreateWebhookDeliverySchema {
+            webhook_id: webhook.id.to_string(),
+            event: event.name().to_string(),
+            payload: event_payload.clone(),
+        })
+        .collect();
+
+    client
+        .from("webhook_deliveries")
+        .insert(json!(delivery_schemas).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(())
+}
+
+/****** Delivery queue ******/
+
+/// The pending deliveries whose next attempt is due, oldest first, with their webhook.
+pub async fn get_due_deliveries(
+    client: &Postgrest,
+    now: DateTime<Local>,
+) -> Result<Vec<WebhookDeliveryModel>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("webhook_deliveries")
+        .select(DUE_DELIVERY_COLUMNS)
+        .eq("status", "pending")
+        .lte("next_attempt_at", now.to_rfc3339())
+        .order("next_attempt_at.asc")
+        .limit(DELIVERY_BATCH_SIZE)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let deliveries_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let deliveries_data: Vec<WebhookDeliveryModel> = serde_json::from_str(&deliveries_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(deliveries_data)
+}
+
+/// Records the outcome of an attempt to deliver an event.
+pub async fn record_delivery_attempt(
+    client: &Postgrest,
+    delivery_id: &Uuid,
+    attempt_schema: &UpdateWebhookDeliverySchema,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    client
+        .from("webhook_deliveries")
+        .update(json!(attempt_schema).to_string())
+        .eq("id", delivery_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(())
+}
+
+async fn get_webhook(
+    client: &Postgrest,
+    id: &Uuid,
+    user_id: &Uuid,
+) -> Result<WebhookModel, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("webhooks")
+        .select("*")
+        .eq("id", id.to_string())
+        .eq("user_id", user_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let webhooks_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let webhooks_data: Vec<WebhookModel> =
+        serde_json::from_str(&webhooks_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    webhooks_data
+        .into_iter()
+        .next()
+        .ok_or_else(|| webhook_not_found(id))
+}
+
+async fn insert_delivery(
+    client: &Postgrest,
+    delivery_schema: &CreateWebhookDeliverySchema,
+) -> Result<WebhookDeliveryModel, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("webhook_deliveries")
+        .insert(json!(delivery_schema).to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let deliveries_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let deliveries_data: Vec<WebhookDeliveryModel> = serde_json::from_str(&deliveries_response)
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    match deliveries_data.into_iter().next() {
+        Some(delivery) => Ok(delivery),
+        None => {
+            let error_response = json!({
+                "status": "error",
+                "message": "Something bad happened while queueing the delivery"
+            });
+            Err((StatusCode::INTERNAL_SERVER_ERROR, Json(error_response)))
+        }
+    }
+}
+
+fn webhook_not_found(id: &Uuid) -> (StatusCode, Json<Value>) {
+    let error_response = json!({
+        "status": "fail",
+        "message": format!("Webhook with ID: {} not found", id)
+    });
+
+    (StatusCode::NOT_FOUND, Json(error_response))
+}
+
+/// Refuses a webhook URL that the server must not send requests to (see `webhooks::check_url`).
+async fn check_webhook_url(url: &str, allow_local: bool) -> Result<(), (StatusCode, Json<Value>)> {
+    webhooks::check_url(url, allow_local)
+        .await
+        .map_err(|message| {
+            let error_response = json!({
+                "status": "fail",
+                "message": message
+            });
+
+            (StatusCode::BAD_REQUEST, Json(error_response))
+        })
+}
@@ -0,0 +1,827 @@
+//! Parsing of the files from which tasks are imported: CSV (with a mapping of its
+//! columns), the JSON of our own export, Todo.txt and iCalendar (its VTODO entries).
+//! Each record of a file becomes a task, or the reason why it could not be read.
+
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+/// Most records that can be imported at once.
+pub const MAX_IMPORT_ROWS: usize = 1000;
+/// Fields of a task that the columns of a CSV file can be mapped to.
+const CSV_FIELDS: [&str; 8] = [
+    "id",
+    "title",
+    "description",
+    "completed",
+    "tags",
+    "due_date",
+    "recurrence",
+    "auto_complete",
+];
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct ImportError(String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Json,
+    TodoTxt,
+    ICalendar,
+}
+
+impl FromStr for ImportFormat {
+    type Err = ImportError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format.to_ascii_lowercase().as_str() {
+            "csv" => Ok(ImportFormat::Csv),
+            "json" => Ok(ImportFormat::Json),
+            "todotxt" | "txt" => Ok(ImportFormat::TodoTxt),
+            "ics" | "ical" => Ok(ImportFormat::ICalendar),
+            _ => Err(ImportError(format!(
+                "Unknown import format: {}, expected 'csv', 'json', 'todotxt' or 'ics'",
+                format
+            ))),
+        }
+    }
+}
+
+impl ImportFormat {
+    /// The format of a file going by the extension of its name, e.g. `todo.txt`.
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let (_, extension) = file_name.rsplit_once('.')?;
+        extension.parse().ok()
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "csv",
+            ImportFormat::Json => "json",
+            ImportFormat::TodoTxt => "todotxt",
+            ImportFormat::ICalendar => "ics",
+        }
+    }
+}
+
+/// A task read from a record of an imported file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedTask {
+    pub title: String,
+    pub description: String,
+    pub completed: bool,
+    pub auto_complete: bool,
+    pub due_date: Option<DateTime<Local>>,
+    pub recurrence: Option<String>,
+    /// Names of the tags of the task.
+    pub tags: Vec<String>,
+    /// Identifies the record across imports, so that importing it again can be skipped.
+    pub import_key: String,
+}
+
+/// A record of an imported file: its number, counted from 1 as shown
+/// to the user (the header of a CSV file being record 1), and what was read from it.
+#[derive(Debug)]
+pub struct ImportRow {
+    pub row: usize,
+    pub task: Result<ImportedTask, String>,
+}
+
+/// Reads the records of a file. `mapping` says which column of a CSV file holds
+/// each field of a task, by its header; the columns named as the fields are used otherwise.
+pub fn parse(
+    format: ImportFormat,
+    content: &str,
+    mapping: Option<&HashMap<String, String>>,
+) -> Result<Vec<ImportRow>, ImportError> {
+    let content = content.trim_start_matches('\u{feff}');
+
+    let rows = match format {
+        ImportFormat::Csv => parse_csv(content, mapping)?,
+        ImportFormat::Json => parse_json(content)?,
+        ImportFormat::TodoTxt => parse_todo_txt(content),
+        ImportFormat::ICalendar => parse_ics(content),
+    };
+
+    if rows.is_empty() {
+        return Err(ImportError("The file has no tasks".to_string()));
+    }
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(ImportError(format!(
+            "At most {} tasks can be imported at once, the file has {}",
+            MAX_IMPORT_ROWS,
+            rows.len()
+        )));
+    }
+
+    Ok(rows)
+}
+
+/// A task as read from a record, before the fields that a format may lack are filled in.
+#[derive(Default)]
+struct RawTask {
+    title: String,
+    description: String,
+    completed: bool,
+    auto_complete: bool,
+    due_date: Option<DateTime<Local>>,
+    recurrence: Option<String>,
+    tags: Vec<String>,
+}
+
+impl RawTask {
+    /// `identity` is what tells the record apart from others of the same format:
+    /// its id when the format has one, or else its content.
+    fn finish(self, format: ImportFormat, identity: &str) -> ImportedTask {
+        let title = self.title.trim().to_string();
+        // A task needs a description; the formats without one get the title.
+        let description = match self.description.trim() {
+            "" => title.clone(),
+            description => description.to_string(),
+        };
+
+        ImportedTask {
+            title,
+            description,
+            completed: self.completed,
+            auto_complete: self.auto_complete,
+            due_date: self.due_date,
+            recurrence: self.recurrence.filter(|rule| !rule.trim().is_empty()),
+            tags: self.tags,
+            import_key: format!("{}:{:016x}", format.name(), fnv1a(identity)),
+        }
+    }
+}
+
+/****** CSV ******/
+
+fn parse_csv(
+    content: &str,
+    mapping: Option<&HashMap<String, String>>,
+) -> Result<Vec<ImportRow>, ImportError> {
+    let mut records = csv_records(content)?.into_iter();
+    let header = records
+        .next()
+        .ok_or_else(|| ImportError("The file is empty".to_string()))?;
+    let header_index = |name: &str| {
+        header
+            .iter()
+            .position(|column| column.trim().eq_ignore_ascii_case(name.trim()))
+    };
+
+    let mut columns: HashMap<&str, usize> = HashMap::new();
+    match mapping {
+        Some(mapping) => {
+            for (field, column) in mapping {
+                let field = CSV_FIELDS
+                    .iter()
+                    .find(|known| known.eq_ignore_ascii_case(field))
+                    .ok_or_else(|| {
+                        ImportError(format!(
+                            "Unknown field in the mapping: {}, expected one of {}",
+                            field,
+                            CSV_FIELDS.join(", ")
+                        ))
+                    })?;
+                let index = header_index(column)
+                    .ok_or_else(|| ImportError(format!("The file has no '{}' column", column)))?;
+                columns.insert(field, index);
+            }
+        }
+        None => {
+            for field in CSV_FIELDS {
+                if let Some(index) = header_index(field) {
+                    columns.insert(field, index);
+                }
+            }
+        }
+    }
+    if !columns.contains_key("title") {
+        return Err(ImportError(
+            "No column is mapped to the title of the tasks".to_string(),
+        ));
+    }
+
+    let rows = records
+        .enumerate()
+        .filter(|(_, record)| record.iter().any(|value| !value.trim().is_empty()))
+        .map(|(index, record)| {
+            let value = |field: &str| {
+                columns
+                    .get(field)
+                    .and_then(|&index| record.get(index))
+                    .map(|value| value.trim())
+                    .unwrap_or_default()
+            };
+
+            let task = (|| {
+                let raw = RawTask {
+                    title: value("title").to_string(),
+                    description: value("description").to_string(),
+                    completed: parse_bool(value("completed"), "completed")?,
+                    auto_complete: parse_bool(value("auto_complete"), "auto_complete")?,
+                    due_date: parse_date(value("due_date"))?,
+                    recurrence: Some(value("recurrence").to_string()),
+                    tags: value("tags")
+                        .split([',', ';'])
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect(),
+                };
+
+                let identity = match value("id") {
+                    "" => record.join("\u{1f}"),
+                    id => id.to_string(),
+                };
+                Ok(raw.finish(ImportFormat::Csv, &identity))
+            })();
+
+            // The header is the first row.
+            ImportRow {
+                row: index + 2,
+                task,
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+/// Splits a CSV file in records as in RFC 4180: fields can be quoted,
+/// and quoted fields can hold commas, line breaks and doubled quotes.
+fn csv_records(content: &str) -> Result<Vec<Vec<String>>, ImportError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => record.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => {}
+            (false, '\n') => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+
+    if quoted {
+        return Err(ImportError(
+            "The file ends inside a quoted field".to_string(),
+        ));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/****** JSON ******/
+
+fn parse_json(content: &str) -> Result<Vec<ImportRow>, ImportError> {
+    let value: Value = serde_json::from_str(content)
+        .map_err(|err| ImportError(format!("The file is not valid JSON: {}", err)))?;
+    let Value::Array(items) = value else {
+        return Err(ImportError(
+            "The file must hold an array of tasks".to_string(),
+        ));
+    };
+
+    let rows = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let task = (|| {
+                let text = |field: &str| match &item[field] {
+                    Value::Null => Ok(String::new()),
+                    Value::String(text) => Ok(text.clone()),
+                    _ => Err(format!("{}: must be a string", field)),
+                };
+                let flag = |field: &str| match &item[field] {
+                    Value::Null => Ok(false),
+                    Value::Bool(flag) => Ok(*flag),
+                    _ => Err(format!("{}: must be true or false", field)),
+                };
+
+                if !item.is_object() {
+                    return Err("The task must be an object".to_string());
+                }
+                let tags = match &item["tags"] {
+                    Value::Null => Vec::new(),
+                    Value::Array(tags) => tags
+                        .iter()
+                        .map(|tag| tag.as_str().map(|tag| tag.trim().to_string()))
+                        .collect::<Option<Vec<String>>>()
+                        .ok_or_else(|| "tags: must be a list of tag names".to_string())?,
+                    _ => return Err("tags: must be a list of tag names".to_string()),
+                };
+
+                let raw = RawTask {
+                    title: text("title")?,
+                    description: text("description")?,
+                    completed: flag("completed")?,
+                    auto_complete: flag("auto_complete")?,
+                    due_date: parse_date(&text("due_date")?)?,
+                    recurrence: Some(text("recurrence")?),
+                    tags,
+                };
+
+                let identity = match &item["id"] {
+                    Value::String(id) => id.clone(),
+                    _ => item.to_string(),
+                };
+                Ok(raw.finish(ImportFormat::Json, &identity))
+            })();
+
+            ImportRow {
+                row: index + 1,
+                task,
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+/****** Todo.txt ******/
+
+/// Reads the lines of a Todo.txt file, e.g.
+/// `x (A) 2023-07-04 2023-07-01 Call Mom +Family @phone due:2023-07-05`:
+/// `x` marks it completed, its projects and contexts become tags, and so does
+/// its priority (`priority-A`); the completion and creation dates are left out.
+fn parse_todo_txt(content: &str) -> Vec<ImportRow> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let task = (|| {
+                let mut raw = RawTask::default();
+                let mut tokens = line.split_whitespace().peekable();
+
+                if tokens.peek() == Some(&"x") {
+                    tokens.next();
+                    raw.completed = true;
+                }
+                if let Some(priority) = tokens.peek().and_then(|token| todo_txt_priority(token)) {
+                    tokens.next();
+                    raw.tags.push(format!("priority-{}", priority));
+                }
+                // The completion date of a completed task, then the creation date.
+                for _ in 0..if raw.completed { 2 } else { 1 } {
+                    if tokens
+                        .peek()
+                        .is_some_and(|token| NaiveDate::parse_from_str(token, "%Y-%m-%d").is_ok())
+                    {
+                        tokens.next();
+                    }
+                }
+
+                let mut words = Vec::new();
+                for token in tokens {
+                    match token.split_once(':') {
+                        Some(("due", date)) => raw.due_date = parse_date(date)?,
+                        Some(("pri", priority)) if !priority.is_empty() => {
+                            raw.tags.push(format!("priority-{}", priority))
+                        }
+                        _ => match token.strip_prefix(['+', '@']) {
+                            Some(tag) if !tag.is_empty() => raw.tags.push(tag.to_string()),
+                            _ => words.push(token),
+                        },
+                    }
+                }
+                raw.title = words.join(" ");
+
+                Ok(raw.finish(ImportFormat::TodoTxt, line.trim()))
+            })();
+
+            ImportRow {
+                row: index + 1,
+                task,
+            }
+        })
+        .collect()
+}
+
+/// The letter of a priority such as `(A)`.
+fn todo_txt_priority(token: &str) -> Option<char> {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next(), chars.next(), chars.next()) {
+        (Some('('), Some(priority), Some(')'), None) if priority.is_ascii_uppercase() => {
+            Some(priority)
+        }
+        _ => None,
+    }
+}
+
+/****** iCalendar ******/
+
+/// Reads the VTODO entries of an iCalendar file; other components, such as
+/// events or the alarms of a to-do, are left out.
+fn parse_ics(content: &str) -> Vec<ImportRow> {
+    // Lines that start with a space or a tab continue the previous one.
+    let unfolded = content
+        .replace("\r\n ", "")
+        .replace("\r\n\t", "")
+        .replace("\n ", "")
+        .replace("\n\t", "");
+
+    let mut rows = Vec::new();
+    let mut todo: Option<Vec<(String, String, String)>> = None;
+    let mut nested = 0;
+
+    for line in unfolded.lines() {
+        let Some((name, params, value)) = ics_property(line) else {
+            continue;
+        };
+
+        match (name.as_str(), value.to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VTODO") if todo.is_none() => todo = Some(Vec::new()),
+            ("BEGIN", _) if todo.is_some() => nested += 1,
+            ("END", "VTODO") if nested == 0 => {
+                if let Some(properties) = todo.take() {
+                    let row = rows.len() + 1;
+                    rows.push(ImportRow {
+                        row,
+                        task: ics_task(&properties),
+                    });
+                }
+            }
+            ("END", _) if todo.is_some() => nested -= 1,
+            _ => {
+                if let (Some(properties), 0) = (todo.as_mut(), nested) {
+                    properties.push((name, params, value));
+                }
+            }
+        }
+    }
+
+    rows
+}
+
+fn ics_task(properties: &[(String, String, String)]) -> Result<ImportedTask, String> {
+    let mut raw = RawTask::default();
+    let mut uid = None;
+
+    for (name, params, value) in properties {
+        match name.as_str() {
+            "UID" => uid = Some(value.clone()),
+            "SUMMARY" => raw.title = ics_unescape(value),
+            "DESCRIPTION" => raw.description = ics_unescape(value),
+            "DUE" => raw.due_date = ics_date(value, params)?,
+            "STATUS" => raw.completed = value.eq_ignore_ascii_case("COMPLETED"),
+            "COMPLETED" => raw.completed = true,
+            "RRULE" => raw.recurrence = Some(value.clone()),
+            "CATEGORIES" => raw.tags.extend(
+                ics_split_list(value)
+                    .into_iter()
+                    .map(|tag| ics_unescape(&tag).trim().to_string())
+                    .filter(|tag| !tag.is_empty()),
+            ),
+            _ => {}
+        }
+    }
+
+    let identity = match uid {
+        Some(uid) => uid,
+        None => properties
+            .iter()
+            .map(|(name, _, value)| format!("{}:{}", name, value))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    Ok(raw.finish(ImportFormat::ICalendar, &identity))
+}
+
+/// Splits a content line such as `DUE;VALUE=DATE:20230704` in its name (upper case),
+/// its parameters and its value. The value starts at the first colon that is not
+/// within a quoted parameter.
+fn ics_property(line: &str) -> Option<(String, String, String)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(index, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(index),
+        _ => None,
+    })?;
+
+    let (name_params, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = name_params.split_once(';').unwrap_or((name_params, ""));
+
+    Some((
+        name.trim().to_ascii_uppercase(),
+        params.to_ascii_uppercase(),
+        value.to_string(),
+    ))
+}
+
+/// A date-time in UTC (`20230704T093000Z`), in local time (`20230704T093000`;
+/// a `TZID` is taken as local time too) or a date (`20230704`), due at its end.
+fn ics_date(value: &str, params: &str) -> Result<Option<DateTime<Local>>, String> {
+    let value = value.trim();
+    let invalid = || format!("DUE: invalid date {}", value);
+
+    if (params.contains("VALUE=DATE") && !params.contains("VALUE=DATE-TIME")) || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").map_err(|_| invalid())?;
+        return local_end_of_day(date).map(Some).ok_or_else(invalid);
+    }
+
+    match value.strip_suffix('Z') {
+        Some(utc) => {
+            let date_time =
+                NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+            Ok(Some(
+                Utc.from_utc_datetime(&date_time).with_timezone(&Local),
+            ))
+        }
+        None => {
+            let date_time =
+                NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+            Local
+                .from_local_datetime(&date_time)
+                .earliest()
+                .map(Some)
+                .ok_or_else(invalid)
+        }
+    }
+}
+
+/// Undoes the escaping of a TEXT value.
+fn ics_unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('n' | 'N') => text.push('\n'),
+                Some(escaped) => text.push(escaped),
+                None => text.push('\\'),
+            },
+            c => text.push(c),
+        }
+    }
+
+    text
+}
+
+/// Splits a list value at the commas that are not escaped.
+fn ics_split_list(value: &str) -> Vec<String> {
+    let mut items = vec![String::new()];
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let item = items.last_mut().unwrap();
+                item.push('\\');
+                if let Some(escaped) = chars.next() {
+                    item.push(escaped);
+                }
+            }
+            ',' => items.push(String::new()),
+            c => items.last_mut().unwrap().push(c),
+        }
+    }
+
+    items
+}
+
+/****** Values ******/
+
+fn parse_bool(value: &str, field: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "" | "false" | "0" | "no" | "n" => Ok(false),
+        "true" | "1" | "yes" | "y" | "x" | "done" | "completed" => Ok(true),
+        _ => Err(format!("{}: expected true or false, got {}", field, value)),
+    }
+}
+
+/// A due date as RFC 3339 (`2023-07-04T09:30:00+02:00`), as a local date and time
+/// (`2023-07-04 09:30`) or as a date (`2023-07-04`), due at its end.
+fn parse_date(value: &str) -> Result<Option<DateTime<Local>>, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let invalid = || format!("due_date: invalid date {}", value);
+
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(Some(date_time.with_timezone(&Local)));
+    }
+    for pattern in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"] {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(value, pattern) {
+            return Local
+                .from_local_datetime(&date_time)
+                .earliest()
+                .map(Some)
+                .ok_or_else(invalid);
+        }
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| invalid())?;
+    local_end_of_day(date).map(Some).ok_or_else(invalid)
+}
+
+/// The last second of a day in local time: a task due on a day is due by its end.
+fn local_end_of_day(date: NaiveDate) -> Option<DateTime<Local>> {
+    let end_of_day = NaiveTime::from_hms_opt(23, 59, 59)?;
+    Local
+        .from_local_datetime(&date.and_time(end_of_day))
+        .earliest()
+}
+
+/// 64-bit FNV-1a hash: short and stable across builds, unlike the hasher of `std`.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks(format: ImportFormat, content: &str) -> Vec<ImportedTask> {
+        parse(format, content, None)
+            .unwrap()
+            .into_iter()
+            .map(|row| row.task.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn reads_csv_with_quoted_fields_and_a_mapping() {
+        let content = "Task name,Notes,Done,Labels\r\n\
+            \"Buy milk, eggs\",\"Two \"\"big\"\" ones\nand bread\",yes,home; shop\r\n\
+            ,,,\r\n\
+            Call Bob,,no,\r\n";
+        let mapping = HashMap::from([
+            ("title".to_string(), "task name".to_string()),
+            ("description".to_string(), "Notes".to_string()),
+            ("completed".to_string(), "Done".to_string()),
+            ("tags".to_string(), "Labels".to_string()),
+        ]);
+
+        let rows = parse(ImportFormat::Csv, content, Some(&mapping)).unwrap();
+        let numbers: Vec<usize> = rows.iter().map(|row| row.row).collect();
+        assert_eq!(numbers, [2, 4]);
+
+        let first = rows[0].task.as_ref().unwrap();
+        assert_eq!(first.title, "Buy milk, eggs");
+        assert_eq!(first.description, "Two \"big\" ones\nand bread");
+        assert!(first.completed);
+        assert_eq!(first.tags, ["home", "shop"]);
+
+        let second = rows[1].task.as_ref().unwrap();
+        assert_eq!(second.description, "Call Bob");
+        assert!(!second.completed);
+    }
+
+    #[test]
+    fn reports_the_csv_records_and_files_that_cannot_be_read() {
+        let rows = parse(ImportFormat::Csv, "title,completed\nA,maybe\n", None).unwrap();
+        assert_eq!(rows[0].row, 2);
+        assert_eq!(
+            rows[0].task.as_ref().unwrap_err(),
+            "completed: expected true or false, got maybe"
+        );
+
+        assert!(parse(ImportFormat::Csv, "name\nA\n", None).is_err());
+        assert!(parse(ImportFormat::Csv, "title\n\"A\n", None).is_err());
+    }
+
+    #[test]
+    fn reads_the_json_export() {
+        let content = r#"[
+            {"id": "1", "title": "Write report", "description": "Q3", "completed": true,
+             "tags": [" work "], "due_date": "2024-06-01T09:30:00Z", "recurrence": "FREQ=WEEKLY"},
+            {"title": 42}
+        ]"#;
+
+        let rows = parse(ImportFormat::Json, content, None).unwrap();
+        let task = rows[0].task.as_ref().unwrap();
+        assert_eq!(task.title, "Write report");
+        assert!(task.completed);
+        assert_eq!(task.tags, ["work"]);
+        assert_eq!(
+            task.due_date,
+            Some(
+                Utc.with_ymd_and_hms(2024, 6, 1, 9, 30, 0)
+                    .unwrap()
+                    .with_timezone(&Local)
+            )
+        );
+        assert_eq!(task.recurrence.as_deref(), Some("FREQ=WEEKLY"));
+
+        assert_eq!(rows[1].row, 2);
+        assert_eq!(
+            rows[1].task.as_ref().unwrap_err(),
+            "title: must be a string"
+        );
+        assert!(parse(ImportFormat::Json, "{}", None).is_err());
+    }
+
+    #[test]
+    fn reads_todo_txt_lines() {
+        let content = "x (A) 2023-07-04 2023-07-01 Call Mom +Family @phone due:2023-07-05\n\
+            \n\
+            2023-07-01 Water the plants pri:B\n";
+
+        let tasks = tasks(ImportFormat::TodoTxt, content);
+        assert_eq!(tasks.len(), 2);
+
+        assert_eq!(tasks[0].title, "Call Mom");
+        assert!(tasks[0].completed);
+        assert_eq!(tasks[0].tags, ["priority-A", "Family", "phone"]);
+        assert_eq!(
+            tasks[0].due_date,
+            local_end_of_day(NaiveDate::from_ymd_opt(2023, 7, 5).unwrap())
+        );
+
+        assert_eq!(tasks[1].title, "Water the plants");
+        assert!(!tasks[1].completed);
+        assert_eq!(tasks[1].tags, ["priority-B"]);
+    }
+
+    #[test]
+    fn reads_the_todos_of_an_icalendar_file() {
+        let content = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\nSUMMARY:Not a task\r\nEND:VEVENT\r\n\
+            BEGIN:VTODO\r\n\
+            UID:todo-1\r\n\
+            SUMMARY:Call Bob\\; then Alice\\, maybe\r\n\
+            DESCRIPTION:Line one\\nLine\r\n  two\r\n\
+            DUE;VALUE=DATE:20230704\r\n\
+            STATUS:COMPLETED\r\n\
+            CATEGORIES:a\\,b,c\r\n\
+            BEGIN:VALARM\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\n\
+            END:VTODO\r\n\
+            END:VCALENDAR\r\n";
+
+        let tasks = tasks(ImportFormat::ICalendar, content);
+        assert_eq!(tasks.len(), 1);
+
+        let task = &tasks[0];
+        assert_eq!(task.title, "Call Bob; then Alice, maybe");
+        assert_eq!(task.description, "Line one\nLine two");
+        assert!(task.completed);
+        assert_eq!(task.tags, ["a,b", "c"]);
+        assert_eq!(
+            task.due_date,
+            local_end_of_day(NaiveDate::from_ymd_opt(2023, 7, 4).unwrap())
+        );
+    }
+
+    #[test]
+    fn keys_a_record_by_its_id_or_else_its_content() {
+        let key =
+            |format: ImportFormat, content: &str| tasks(format, content)[0].import_key.clone();
+
+        // The same id is the same record, even once edited.
+        assert_eq!(
+            key(ImportFormat::Json, r#"[{"id": "1", "title": "A"}]"#),
+            key(ImportFormat::Json, r#"[{"id": "1", "title": "B"}]"#)
+        );
+        assert_ne!(
+            key(ImportFormat::Json, r#"[{"title": "A"}]"#),
+            key(ImportFormat::Json, r#"[{"title": "B"}]"#)
+        );
+        assert_eq!(
+            key(ImportFormat::TodoTxt, "Call Mom\n"),
+            key(ImportFormat::TodoTxt, "  Call Mom  \n")
+        );
+
+        let csv_key = key(ImportFormat::Csv, "id,title\n7,A\n");
+        assert_eq!(csv_key, format!("csv:{:016x}", fnv1a("7")));
+        assert_ne!(
+            csv_key,
+            key(ImportFormat::Json, r#"[{"id": "7", "title": "A"}]"#)
+        );
+    }
+
+    #[test]
+    fn hashes_with_fnv1a() {
+        assert_eq!(fnv1a(""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a("a"), 0xaf63dc4c8601ec8c);
+    }
+}
@@ -0,0 +1,180 @@
+//! Background jobs that run alongside the server. They stop once the server
+//! shuts down, after finishing the round they may be in.
+
+use std::{sync::Arc, time::Duration};
+
+use chrono::Local;
+use tokio::{sync::RwLock, task::JoinHandle};
+
+use crate::{
+    handlers::{
+        tasks_handlers::{get_trashed_tasks, purge_tasks},
+        webhooks_handlers::{get_due_deliveries, record_delivery_attempt},
+    },
+    schemas::UpdateWebhookDeliverySchema,
+    webhooks::{self, DeliveryOutcome},
+    AppState,
+};
+
+/// Periodically deletes for good the tasks that have been in the trash
+/// for longer than the configured retention period.
+pub fn spawn_trash_purge(app_state: Arc<RwLock<AppState>>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let (period, mut shutdown) = {
+            let state = app_state.read().await;
+            (state.env.trash_purge_interval, state.shutdown.clone())
+        };
+        let mut interval = tokio::time::interval(Duration::from_secs(period));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = shutdown.draining() => break,
+            }
+
+            // The lock is not held while the tasks and their files are deleted.
+            let (client, blobs, retention_days) = {
+                let state = app_state.read().await;
+                (
+                    state.client.clone(),
+                    state.blobs.clone(),
+                    state.env.trash_retention_days,
+                )
+            };
+            let Some(deleted_before) = chrono::Duration::try_days(retention_days)
+                .and_then(|retention| Local::now().checked_sub_signed(retention))
+            else {
+                println!("->> ❌ Purging the trash failed: the retention period is out of range\n");
+                continue;
+            };
+
+            let purged = match get_trashed_tasks(&client, None, Some(deleted_before)).await {
+                Ok(tasks) => {
+                    let task_ids: Vec<String> =
+                        tasks.iter().map(|task| task.id.to_string()).collect();
+                    purge_tasks(&client, blobs.as_ref(), &task_ids)
+                        .await
+                        .map(|_| task_ids.len())
+                }
+                Err(err) => Err(err),
+            };
+
+            match purged {
+                Ok(0) => {}
+                Ok(count) => println!("->> 🗑️ Purged {count} tasks from the trash\n"),
+                Err((_, err)) => {
+                    println!("->> ❌ Purging the trash failed: {}\n", err.0["message"])
+                }
+            }
+        }
+    })
+}
+
+/// Periodically sends the webhook deliveries that are due. A failed delivery
+/// is tried again later, with exponential backoff, until it has been attempted
+/// the configured number of times; the queue lives in the database, so pending
+/// deliveries survive a restart.
+pub fn spawn_webhook_deliveries(app_state: Arc<RwLock<AppState>>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let (period, max_attempts, timeout, allow_local, mut shutdown) = {
+            let state = app_state.read().await;
+            (
+                state.env.webhook_poll_interval,
+                state.env.webhook_max_attempts,
+                Duration::from_secs(state.env.webhook_timeout),
+                state.env.webhook_allow_local,
+                state.shutdown.clone(),
+            )
+        };
+        // A redirect could lead a delivery to an address its URL was checked against.
+        let http = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("the HTTP client of the webhooks cannot be built");
+        let mut interval = tokio::time::interval(Duration::from_secs(period));
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = shutdown.draining() => break,
+            }
+
+            // The lock is not held while waiting for the receivers.
+            let client = app_state.read().await.client.clone();
+
+            let deliveries = match get_due_deliveries(&client, Local::now()).await {
+                Ok(deliveries) => deliveries,
+                Err((_, err)) => {
+                    println!(
+                        "->> ❌ Fetching the due webhook deliveries failed: {}\n",
+                        err.0["message"]
+                    );
+                    continue;
+                }
+            };
+
+            for delivery in deliveries {
+                // A delivery to a webhook that has been disabled since is given up.
+                let active = delivery
+                    .webhook
+                    .as_ref()
+                    .is_some_and(|webhook| webhook.active);
+                let outcome = match &delivery.webhook {
+                    Some(webhook) if active => {
+                        // The host may resolve elsewhere than when the webhook was registered.
+                        if let Err(error) = webhooks::check_url(&webhook.url, allow_local).await {
+                            DeliveryOutcome {
+                                succeeded: false,
+                                response_status: None,
+                                error: Some(error),
+                            }
+                        } else {
+                            webhooks::send(
+                                &http,
+                                &webhook.url,
+                                &webhook.secret,
+                                &delivery.id,
+                                &delivery.event,
+                                &delivery.payload,
+                                timeout,
+                            )
+                            .await
+                        }
+                    }
+                    _ => DeliveryOutcome {
+                        succeeded: false,
+                        response_status: None,
+                        error: Some("The webhook is disabled".to_string()),
+                    },
+                };
+
+                let attempts = delivery.attempts + 1;
+                let status = if outcome.succeeded {
+                    "succeeded"
+                } else if !active || attempts >= max_attempts {
+                    "failed"
+                } else {
+                    "pending"
+                };
+
+                let attempt_schema = UpdateWebhookDeliverySchema {
+                    status: status.to_string(),
+                    attempts,
+                    next_attempt_at: webhooks::next_attempt_at(attempts),
+                    last_attempt_at: Local::now(),
+                    response_status: outcome.response_status,
+                    last_error: outcome.error,
+                };
+
+                if let Err((_, err)) =
+                    record_delivery_attempt(&client, &delivery.id, &attempt_schema).await
+                {
+                    println!(
+                        "->> ❌ Recording the webhook delivery {} failed: {}\n",
+                        delivery.id, err.0["message"]
+                    );
+                }
+            }
+        }
+    })
+}
@@ -1,114 +1,157 @@
 // #![allow(unused)] // For beginning only.
 
 mod config;
+mod events;
+mod export;
 mod handlers;
+mod import;
+mod jobs;
 mod middleware;
 mod models;
+mod permissions;
+mod recurrence;
 mod response;
 mod routes;
 mod schemas;
+mod shutdown;
+mod storage;
 mod validators;
+mod webhooks;
 
-use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+use std::{sync::Arc, time::Duration};
 
 use axum::{
-    body::{boxed, Body},
     http::{
-        header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE},
-        HeaderValue, Method, Request, StatusCode,
+        header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH},
+        Method,
     },
-    response::{IntoResponse, Response},
-    routing::get,
     Router,
 };
 use dotenv::dotenv;
 use postgrest::Postgrest;
 use tokio::sync::RwLock;
-use tower::ServiceExt;
-use tower_http::{
-    cors::CorsLayer,
-    services::{ServeDir, ServeFile},
-    trace::TraceLayer,
-};
+use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use config::Config;
+use config::{Config, StorageBackend};
+use events::{EventEmitter, EventSender};
+use shutdown::Shutdown;
+use storage::{BlobStore, LocalBlobStore};
 
 pub struct AppState {
     client: Postgrest,
     env: Config,
+    blobs: Arc<dyn BlobStore>,
+    events: EventSender,
+    emitter: EventEmitter,
+    shutdown: Shutdown,
 }
 
 #[tokio::main]
 async fn main() {
     dotenv().ok();
 
-    tracing_subscriber::registry()
-        .with(
-            EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "example_static_file_server=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
-    let static_file_service = |req: Request<Body>| async {
-        let mut resp = match req.uri().to_string().as_str() {
-            s if s.ends_with("css") => ServeDir::new("client/dist").oneshot(req).await,
-            s if s.ends_with("js") => ServeDir::new("client/dist").oneshot(req).await,
-            s if s.ends_with("wasm") => ServeDir::new("client/dist").oneshot(req).await,
-            s if s.ends_with("svg") => ServeDir::new("client/dist").oneshot(req).await,
-            _ => ServeFile::new("client/dist/index.html").oneshot(req).await,
-        };
-
-        if resp.as_mut().unwrap().status() == 404 {
-            return Ok::<_, Infallible>(
-                Response::builder()
-                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                    .body(boxed(Body::from("Something went wrong...\n")))
-                    .unwrap(),
-            );
+    let config = match Config::init() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("->> ❌ {}", err);
+            std::process::exit(1);
         }
-
-        // Ok::<_, Infallible>(resp.into_response().map(|body| BoxBody::new(body)))
-        Ok::<_, Infallible>(resp.into_response())
     };
 
-    let config = Config::init();
+    tracing_subscriber::registry()
+        .with(EnvFilter::new(&config.log_filter))
+        .with(tracing_subscriber::fmt::layer())
+        .init();
 
     let client =
         Postgrest::new(&config.supabase_url).insert_header("apikey", &config.supabase_anon_key);
 
+    let blobs: Arc<dyn BlobStore> = match config.storage_backend {
+        StorageBackend::Local => Arc::new(LocalBlobStore::new(&config.attachments_dir)),
+    };
+    let (addr, allowed_origins) = (config.listen_addr, config.allowed_origins.clone());
+    let delay = Duration::from_secs(config.shutdown_delay);
+    let drain_timeout = Duration::from_secs(config.shutdown_drain_timeout);
+    let (shutdown_sender, shutdown) = shutdown::channel();
+    let events = events::channel();
+    let (emitter, event_worker) = events::spawn_event_worker(client.clone(), events.clone());
+
     let app_state = Arc::new(RwLock::new(AppState {
         client,
         env: config,
+        blobs,
+        events,
+        emitter,
+        shutdown: shutdown.clone(),
     }));
 
     println!("->> ✅Connection to the database is successful!\n");
 
+    let jobs = [
+        jobs::spawn_trash_purge(app_state.clone()),
+        jobs::spawn_webhook_deliveries(app_state.clone()),
+    ];
+
     let cors = CorsLayer::new()
-        .allow_origin("http://localhost:3000".parse::<HeaderValue>().unwrap())
+        .allow_origin(allowed_origins)
         .allow_methods([Method::GET, Method::POST, Method::PATCH, Method::DELETE])
         .allow_credentials(true)
-        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
+        .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE, IF_MATCH])
+        .expose_headers([ETAG]);
 
     let routes_all = Router::new()
-        .merge(routes::route_healthchecker::healthchecker_router())
+        .merge(routes::route_healthchecker::healthchecker_router(app_state.clone()))
         .merge(routes::routes_tasks::tasks_router(app_state.clone()))
+        .merge(routes::routes_export::export_router(app_state.clone()))
+        .merge(routes::routes_import::import_router(app_state.clone()))
+        .merge(routes::routes_calendar::calendar_router(app_state.clone()))
+        .merge(routes::routes_task_items::task_items_router(app_state.clone()))
+        .merge(routes::routes_comments::comments_router(app_state.clone()))
+        .merge(routes::routes_attachments::attachments_router(app_state.clone()))
+        .merge(routes::routes_revisions::revisions_router(app_state.clone()))
+        .merge(routes::routes_time_entries::time_entries_router(app_state.clone()))
+        .merge(routes::routes_tags::tags_router(app_state.clone()))
+        .merge(routes::routes_projects::projects_router(app_state.clone()))
+        .merge(routes::routes_shares::shares_router(app_state.clone()))
+        .merge(routes::routes_statuses::statuses_router(app_state.clone()))
+        .merge(routes::routes_webhooks::webhooks_router(app_state.clone()))
+        .merge(routes::routes_events::events_router(app_state.clone()))
         .merge(routes::routes_users::users_router(app_state.clone()))
-        .nest_service("/", get(static_file_service))
-        .fallback_service(get(static_file_service))
+        .fallback_service(routes::routes_static::static_router(app_state.clone()))
         .layer(TraceLayer::new_for_http())
         .layer(cors);
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 8080));
     println!("->> LISTENING on {addr}\n");
 
     println!("🚀 Server started successfully!!\n");
 
-    axum::Server::bind(&addr)
+    tokio::spawn(shutdown::listen(shutdown_sender));
+
+    let server = axum::Server::bind(&addr)
         .serve(routes_all.into_make_service())
-        .await
-        .unwrap();
+        .with_graceful_shutdown(shutdown::stop_accepting(shutdown.clone(), delay));
+
+    // The requests still in flight once the drain timeout is over are cut off.
+    let mut draining = shutdown.clone();
+    tokio::select! {
+        result = server => result.unwrap(),
+        _ = async {
+            draining.draining().await;
+            tokio::time::sleep(delay + drain_timeout).await;
+        } => println!("->> ⚠️ Drain timeout reached: closing the remaining connections\n"),
+    }
+
+    // The background jobs get as long again to finish their round, and the event
+    // worker to send out the events of the requests that went through.
+    let deadline = tokio::time::Instant::now() + drain_timeout;
+    for job in jobs.into_iter().chain([event_worker.stop()]) {
+        if tokio::time::timeout_at(deadline, job).await.is_err() {
+            println!("->> ⚠️ A background job did not stop in time\n");
+        }
+    }
+
+    println!("->> 👋 Server stopped\n");
 }
 
 /*
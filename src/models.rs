@@ -1,15 +1,72 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Local};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
 use uuid::Uuid;
 
-#[derive(Debug, Deserialize, Serialize)]
+use crate::response::FilteredUser;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TaskModel {
     pub id: Uuid,
     pub title: String,
     pub description: String,
     pub completed: bool,
     pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub auto_complete: bool,
+    #[serde(default)]
+    pub due_date: Option<DateTime<Local>>,
+    /// Recurrence rule in RRULE form, e.g. `FREQ=WEEKLY;BYDAY=MO`.
+    #[serde(default)]
+    pub recurrence: Option<String>,
+    /// When the task was moved to the trash; `None` for live tasks.
+    #[serde(default)]
+    pub deleted_at: Option<DateTime<Local>>,
+    /// Incremented by every edit; the `ETag` of the task is built from it.
+    #[serde(default)]
+    pub version: i32,
+    /// Rank of the task in the list of its owner, lowest first.
+    #[serde(default)]
+    pub position: f64,
+    /// Column of the task on the board; `None` puts it in the first column
+    /// that matches `completed`.
+    #[serde(default)]
+    pub status_id: Option<Uuid>,
     pub created_at: DateTime<Local>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Local>>,
+    /// Last write to the task, its tags or its checklist, whatever it was.
+    #[serde(default)]
+    pub changed_at: Option<DateTime<Local>>,
+    #[serde(
+        default,
+        rename(deserialize = "task_tags"),
+        deserialize_with = "deserialize_tag_ids"
+    )]
+    pub tag_ids: Vec<Uuid>,
+    #[serde(
+        default,
+        rename(deserialize = "task_items"),
+        deserialize_with = "deserialize_progress"
+    )]
+    pub progress: ChecklistProgress,
+    /// Seconds of work tracked on the task, a running timer counting up to now.
+    #[serde(
+        default,
+        rename(deserialize = "time_entries"),
+        deserialize_with = "deserialize_tracked_seconds"
+    )]
+    pub tracked_seconds: i64,
+}
+
+/// How many of the checklist items of a task are done, e.g. 3 of 5.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct ChecklistProgress {
+    pub done: usize,
+    pub total: usize,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -20,3 +77,271 @@ pub struct UserModel {
     pub password: String,
     pub created_at: DateTime<Local>,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TagModel {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ProjectModel {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    pub icon: String,
+    pub archived: bool,
+    pub sort_order: i32,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+/// A workflow column of the board of a user (`project_id` is `None`) or of a project.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StatusModel {
+    pub id: Uuid,
+    pub name: String,
+    pub color: String,
+    /// Whether the tasks in this column are completed.
+    pub done: bool,
+    pub sort_order: i32,
+    pub user_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub created_at: DateTime<Local>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaskItemModel {
+    pub id: Uuid,
+    pub text: String,
+    pub done: bool,
+    pub position: i32,
+    pub task_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+/// Permission a user has on a task or project, from least to most privileged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Viewer,
+    Editor,
+    Owner,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Viewer => "viewer",
+            Role::Editor => "editor",
+            Role::Owner => "owner",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ShareModel {
+    pub id: Uuid,
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    pub user_id: Uuid,
+    pub role: Role,
+    pub shared_by: Uuid,
+    pub created_at: DateTime<Local>,
+    /// The user the task or project is shared with, when embedded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<FilteredUser>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommentModel {
+    pub id: Uuid,
+    pub body: String,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+    pub updated_at: Option<DateTime<Local>>,
+    /// The author of the comment, when embedded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<FilteredUser>,
+}
+
+/// A recorded edit of a task.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RevisionModel {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+    /// The fields changed by the edit, by name.
+    pub changes: BTreeMap<String, FieldChange>,
+    /// The user who made the edit, when embedded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor: Option<FilteredUser>,
+}
+
+/// The value of a task field before and after an edit.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct FieldChange {
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Time spent by a user on a task; `ended_at` is `None` while its timer runs.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TimeEntryModel {
+    pub id: Uuid,
+    pub started_at: DateTime<Local>,
+    pub ended_at: Option<DateTime<Local>>,
+    pub note: Option<String>,
+    pub task_id: Uuid,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+    /// The task the time was spent on, when embedded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<TimeEntryTask>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TimeEntryTask {
+    pub title: String,
+}
+
+/// A time entry with what the time report groups it by: the project and tags of its task.
+#[derive(Debug, Deserialize)]
+pub struct ReportTimeEntryModel {
+    pub started_at: DateTime<Local>,
+    pub ended_at: Option<DateTime<Local>>,
+    pub task: ReportTask,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportTask {
+    pub project: Option<NamedRef>,
+    #[serde(default)]
+    pub task_tags: Vec<ReportTaskTag>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportTaskTag {
+    pub tag: NamedRef,
+}
+
+/// The id and name of an embedded project or tag.
+#[derive(Debug, Deserialize)]
+pub struct NamedRef {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// An address to which the task events of a user are sent.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebhookModel {
+    pub id: Uuid,
+    pub url: String,
+    /// Key of the HMAC signature of the payloads.
+    pub secret: String,
+    /// The events sent, e.g. `task.created`.
+    pub events: Vec<String>,
+    pub active: bool,
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+/// An event sent, or to be sent, to a webhook.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebhookDeliveryModel {
+    pub id: Uuid,
+    pub event: String,
+    pub payload: Value,
+    /// `pending`, `succeeded` or `failed`.
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Local>,
+    pub last_attempt_at: Option<DateTime<Local>>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub webhook_id: Uuid,
+    pub created_at: DateTime<Local>,
+    /// The webhook the event goes to, when embedded; never sent to clients.
+    #[serde(default, skip_serializing)]
+    pub webhook: Option<DeliveryWebhook>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryWebhook {
+    pub url: String,
+    pub secret: String,
+    pub active: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AttachmentModel {
+    pub id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    /// Key of the content in the blob store; never sent to clients.
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub task_id: Uuid,
+    /// The user who uploaded the attachment and whose quota it counts against.
+    pub user_id: Uuid,
+    pub created_at: DateTime<Local>,
+}
+
+/// PostgREST embeds the `task_tags` join table as `[{ "tag_id": "..." }]`,
+/// so we flatten it into the list of tag ids that the API exposes.
+fn deserialize_tag_ids<'de, D>(deserializer: D) -> Result<Vec<Uuid>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct TagRef {
+        tag_id: Uuid,
+    }
+
+    let tags = Vec::<TagRef>::deserialize(deserializer)?;
+    Ok(tags.into_iter().map(|tag| tag.tag_id).collect())
+}
+
+/// PostgREST embeds the `time_entries` of a task as `[{ "started_at": ..., "ended_at": ... }, ...]`,
+/// which we reduce to the total of their durations.
+fn deserialize_tracked_seconds<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct EntryRef {
+        started_at: DateTime<Local>,
+        ended_at: Option<DateTime<Local>>,
+    }
+
+    let entries = Vec::<EntryRef>::deserialize(deserializer)?;
+    let now = Local::now();
+    Ok(entries
+        .iter()
+        .map(|entry| (entry.ended_at.unwrap_or(now) - entry.started_at).num_seconds())
+        .sum())
+}
+
+/// PostgREST embeds the `task_items` of a task as `[{ "done": true }, ...]`,
+/// which we reduce to the progress of its checklist.
+fn deserialize_progress<'de, D>(deserializer: D) -> Result<ChecklistProgress, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct ItemRef {
+        done: bool,
+    }
+
+    let items = Vec::<ItemRef>::deserialize(deserializer)?;
+    Ok(ChecklistProgress {
+        done: items.iter().filter(|item| item.done).count(),
+        total: items.len(),
+    })
+}
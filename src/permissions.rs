@@ -0,0 +1,318 @@
+//! Access control for tasks and projects.
+//! The creator of a task or project is its owner, and so is the owner of the project
+//! of a task; other users get access through the `shares` table, either on the task
+//! itself or on its project.
+//! A user without any access gets NOT_FOUND, so the resource is not disclosed;
+//! a user whose role is too low for the action gets FORBIDDEN.
+//! Tasks in the trash are only reachable through `get_trashed_task_with_role`.
+
+use axum::{http::StatusCode, Json};
+use postgrest::Postgrest;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{
+    handlers::tasks_handlers::TASK_COLUMNS,
+    models::{ProjectModel, Role, ShareModel, TaskModel},
+};
+
+/// Gets a task together with the role of the user on it,
+/// failing if that role is lower than `required`.
+pub async fn get_task_with_role(
+    client: &Postgrest,
+    task_id: &Uuid,
+    user_id: &Uuid,
+    required: Role,
+) -> Result<(TaskModel, Role), (StatusCode, Json<Value>)> {
+    fetch_task_with_role(client, task_id, user_id, required, false).await
+}
+
+/// Same as `get_task_with_role`, but for a task that is in the trash.
+pub async fn get_trashed_task_with_role(
+    client: &Postgrest,
+    task_id: &Uuid,
+    user_id: &Uuid,
+    required: Role,
+) -> Result<(TaskModel, Role), (StatusCode, Json<Value>)> {
+    fetch_task_with_role(client, task_id, user_id, required, true).await
+}
+
+async fn fetch_task_with_role(
+    client: &Postgrest,
+    task_id: &Uuid,
+    user_id: &Uuid,
+    required: Role,
+    trashed: bool,
+) -> Result<(TaskModel, Role), (StatusCode, Json<Value>)> {
+    let request = client
+        .from("tasks")
+        .select(TASK_COLUMNS)
+        .eq("id", task_id.to_string());
+    let request = if trashed {
+        request.not("is", "deleted_at", "null")
+    } else {
+        request.is("deleted_at", "null")
+    };
+
+    let response = request.execute().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Database error: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let task_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let task_data: Vec<TaskModel> = serde_json::from_str(&task_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let not_found = || {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Task with ID: {} not found", task_id)
+        });
+
+        (StatusCode::NOT_FOUND, Json(error_response))
+    };
+
+    let task = task_data.into_iter().next().ok_or_else(not_found)?;
+
+    let owns_project = match task.project_id {
+        Some(project_id) => get_project(client, &project_id)
+            .await?
+            .is_some_and(|project| &project.user_id == user_id),
+        None => false,
+    };
+
+    // The owner of a project owns the tasks that its editors add to it too.
+    let role = if &task.user_id == user_id || owns_project {
+        Role::Owner
+    } else {
+        // A share on the project of the task also grants access to the task.
+        let filter = match task.project_id {
+            Some(project_id) => format!("task_id.eq.{},project_id.eq.{}", task_id, project_id),
+            None => format!("task_id.eq.{}", task_id),
+        };
+        get_shared_role(client, user_id, &filter)
+            .await?
+            .ok_or_else(not_found)?
+    };
+
+    check_role(role, required, "task")?;
+
+    Ok((task, role))
+}
+
+/// Gets a project together with the role of the user on it,
+/// failing if that role is lower than `required`.
+pub async fn get_project_with_role(
+    client: &Postgrest,
+    project_id: &Uuid,
+    user_id: &Uuid,
+    required: Role,
+) -> Result<(ProjectModel, Role), (StatusCode, Json<Value>)> {
+    let not_found = || {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Project with ID: {} not found", project_id)
+        });
+
+        (StatusCode::NOT_FOUND, Json(error_response))
+    };
+
+    let project = get_project(client, project_id)
+        .await?
+        .ok_or_else(not_found)?;
+
+    let role = if &project.user_id == user_id {
+        Role::Owner
+    } else {
+        let filter = format!("project_id.eq.{}", project_id);
+        get_shared_role(client, user_id, &filter)
+            .await?
+            .ok_or_else(not_found)?
+    };
+
+    check_role(role, required, "project")?;
+
+    Ok((project, role))
+}
+
+/// Gets the users who can see a task: its owner, the owner of its project
+/// and everyone it is shared with, directly or through its project.
+pub async fn get_task_audience(
+    client: &Postgrest,
+    task: &TaskModel,
+) -> Result<Vec<Uuid>, (StatusCode, Json<Value>)> {
+    let mut user_ids = vec![task.user_id];
+
+    let filter = match task.project_id {
+        Some(project_id) => format!("task_id.eq.{},project_id.eq.{}", task.id, project_id),
+        None => format!("task_id.eq.{}", task.id),
+    };
+
+    let response = client
+        .from("shares")
+        .select("*")
+        .or(filter)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let shares_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let shares_data: Vec<ShareModel> = serde_json::from_str(&shares_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    user_ids.extend(shares_data.iter().map(|share| share.user_id));
+
+    if let Some(project_id) = task.project_id {
+        user_ids.extend(
+            get_project(client, &project_id)
+                .await?
+                .map(|project| project.user_id),
+        );
+    }
+
+    user_ids.sort();
+    user_ids.dedup();
+
+    Ok(user_ids)
+}
+
+async fn get_project(
+    client: &Postgrest,
+    project_id: &Uuid,
+) -> Result<Option<ProjectModel>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("projects")
+        .select("*")
+        .eq("id", project_id.to_string())
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let project_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let project_data: Vec<ProjectModel> =
+        serde_json::from_str(&project_response).map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Error deserializing response: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    Ok(project_data.into_iter().next())
+}
+
+/// Gets the highest role granted to the user by the shares matching `filter`,
+/// a PostgREST `or` filter over the `task_id` and `project_id` columns.
+async fn get_shared_role(
+    client: &Postgrest,
+    user_id: &Uuid,
+    filter: &str,
+) -> Result<Option<Role>, (StatusCode, Json<Value>)> {
+    let response = client
+        .from("shares")
+        .select("*")
+        .eq("user_id", user_id.to_string())
+        .or(filter)
+        .execute()
+        .await
+        .map_err(|err| {
+            let error_response = json!({
+                "status": "fail",
+                "message": format!("Database error: {}", err)
+            });
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+        })?;
+
+    let shares_response = response.text().await.map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error parsing json response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    let shares_data: Vec<ShareModel> = serde_json::from_str(&shares_response).map_err(|err| {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!("Error deserializing response: {}", err)
+        });
+
+        (StatusCode::INTERNAL_SERVER_ERROR, Json(error_response))
+    })?;
+
+    Ok(shares_data.iter().map(|share| share.role).max())
+}
+
+fn check_role(role: Role, required: Role, resource: &str) -> Result<(), (StatusCode, Json<Value>)> {
+    if role < required {
+        let error_response = json!({
+            "status": "fail",
+            "message": format!(
+                "You need {} access to this {}, but you only have {} access",
+                required.as_str(),
+                resource,
+                role.as_str()
+            )
+        });
+        return Err((StatusCode::FORBIDDEN, Json(error_response)));
+    }
+
+    Ok(())
+}
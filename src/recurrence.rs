@@ -0,0 +1,432 @@
+//! Recurrence rules for repeating tasks.
+//! They are written as a subset of the RFC 5545 RRULE syntax, e.g. `FREQ=WEEKLY;BYDAY=MO,TH`:
+//! `FREQ` (DAILY, WEEKLY, MONTHLY or YEARLY), `INTERVAL`, `BYDAY` (weekly rules only),
+//! `BYMONTHDAY` (monthly rules only, negative days count from the end of the month) and `UNTIL`.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Duration, Local, Months, NaiveDate, TimeZone, Weekday};
+use thiserror::Error;
+
+/// Most occurrences that can be listed at once.
+pub const MAX_OCCURRENCES: usize = 100;
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct RecurrenceError(String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    pub frequency: Frequency,
+    pub interval: u32,
+    /// Days of the week of a weekly rule; the weekday of the due date when empty.
+    pub weekdays: Vec<Weekday>,
+    /// Days of the month of a monthly rule; the day of the due date when empty.
+    pub month_days: Vec<i32>,
+    /// Last day on which an occurrence can fall.
+    pub until: Option<NaiveDate>,
+}
+
+impl Recurrence {
+    /// The occurrence that follows the one due at `after`, at the same time of day,
+    /// or `None` once the rule has ended.
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let date = self.next_date(after.date_naive())?;
+        if self.until.is_some_and(|until| date > until) {
+            return None;
+        }
+
+        let naive = date.and_time(after.time());
+        // A time skipped by a daylight saving change moves forward by the size of the gap.
+        Local.from_local_datetime(&naive).earliest().or_else(|| {
+            Local
+                .from_local_datetime(&(naive + Duration::hours(1)))
+                .earliest()
+        })
+    }
+
+    /// Up to `count` occurrences following the one due at `start`.
+    pub fn occurrences(&self, start: DateTime<Local>, count: usize) -> Vec<DateTime<Local>> {
+        let mut occurrences = Vec::new();
+        let mut current = start;
+
+        while occurrences.len() < count.min(MAX_OCCURRENCES) {
+            match self.next_after(current) {
+                Some(next) => {
+                    occurrences.push(next);
+                    current = next;
+                }
+                None => break,
+            }
+        }
+
+        occurrences
+    }
+
+    fn next_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        let interval = self.interval as i64;
+
+        match self.frequency {
+            Frequency::Daily => date.checked_add_signed(Duration::days(interval)),
+            Frequency::Weekly => {
+                let mut weekdays: Vec<u32> = if self.weekdays.is_empty() {
+                    vec![date.weekday().num_days_from_monday()]
+                } else {
+                    self.weekdays
+                        .iter()
+                        .map(|weekday| weekday.num_days_from_monday())
+                        .collect()
+                };
+                weekdays.sort_unstable();
+
+                let week_start =
+                    date - Duration::days(date.weekday().num_days_from_monday() as i64);
+
+                // The rest of the current week, then the first day of the next week in the series.
+                [0, interval].iter().find_map(|weeks| {
+                    weekdays.iter().find_map(|day| {
+                        let candidate =
+                            week_start + Duration::weeks(*weeks) + Duration::days(*day as i64);
+                        (candidate > date).then_some(candidate)
+                    })
+                })
+            }
+            Frequency::Monthly => {
+                let month_days = if self.month_days.is_empty() {
+                    vec![date.day() as i32]
+                } else {
+                    self.month_days.clone()
+                };
+                let month_start = date.with_day(1)?;
+
+                // Months without any of the days are skipped, as RFC 5545 does,
+                // so a few years are always enough to find the next one.
+                (0..=48).find_map(|step| {
+                    let month =
+                        month_start.checked_add_months(Months::new(step * self.interval))?;
+                    let mut days: Vec<NaiveDate> = month_days
+                        .iter()
+                        .filter_map(|day| resolve_month_day(month, *day))
+                        .collect();
+                    days.sort_unstable();
+
+                    days.into_iter().find(|candidate| *candidate > date)
+                })
+            }
+            Frequency::Yearly => (1..=8).find_map(|step| {
+                // February 29th only comes back on leap years.
+                NaiveDate::from_ymd_opt(
+                    date.year() + (step * self.interval) as i32,
+                    date.month(),
+                    date.day(),
+                )
+            }),
+        }
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = RecurrenceError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+
+        let mut frequency = None;
+        let mut interval = None;
+        let mut weekdays = None;
+        let mut month_days = None;
+        let mut until = None;
+
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| RecurrenceError(format!("Malformed rule part: {}", part)))?;
+
+            let is_repeated = match key.to_uppercase().as_str() {
+                "FREQ" => frequency.replace(parse_frequency(value)?).is_some(),
+                "INTERVAL" => interval.replace(parse_interval(value)?).is_some(),
+                "BYDAY" => weekdays.replace(parse_weekdays(value)?).is_some(),
+                "BYMONTHDAY" => month_days.replace(parse_month_days(value)?).is_some(),
+                "UNTIL" => until.replace(parse_until(value)?).is_some(),
+                other => return Err(RecurrenceError(format!("Unsupported rule part: {}", other))),
+            };
+
+            if is_repeated {
+                return Err(RecurrenceError(format!("Repeated rule part: {}", key)));
+            }
+        }
+
+        let frequency = frequency.ok_or_else(|| RecurrenceError("FREQ is required".to_string()))?;
+
+        if weekdays.is_some() && frequency != Frequency::Weekly {
+            return Err(RecurrenceError(
+                "BYDAY is only supported with FREQ=WEEKLY".to_string(),
+            ));
+        }
+        if month_days.is_some() && frequency != Frequency::Monthly {
+            return Err(RecurrenceError(
+                "BYMONTHDAY is only supported with FREQ=MONTHLY".to_string(),
+            ));
+        }
+
+        Ok(Recurrence {
+            frequency,
+            interval: interval.unwrap_or(1),
+            weekdays: weekdays.unwrap_or_default(),
+            month_days: month_days.unwrap_or_default(),
+            until,
+        })
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.weekdays.is_empty() {
+            let weekdays: Vec<&str> = self.weekdays.iter().map(|day| weekday_code(*day)).collect();
+            write!(f, ";BYDAY={}", weekdays.join(","))?;
+        }
+        if !self.month_days.is_empty() {
+            let month_days: Vec<String> =
+                self.month_days.iter().map(|day| day.to_string()).collect();
+            write!(f, ";BYMONTHDAY={}", month_days.join(","))?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+
+        Ok(())
+    }
+}
+
+/****** Helpers ******/
+
+fn parse_frequency(value: &str) -> Result<Frequency, RecurrenceError> {
+    match value.to_uppercase().as_str() {
+        "DAILY" => Ok(Frequency::Daily),
+        "WEEKLY" => Ok(Frequency::Weekly),
+        "MONTHLY" => Ok(Frequency::Monthly),
+        "YEARLY" => Ok(Frequency::Yearly),
+        other => Err(RecurrenceError(format!("Unsupported FREQ: {}", other))),
+    }
+}
+
+fn parse_interval(value: &str) -> Result<u32, RecurrenceError> {
+    match value.parse::<u32>() {
+        Ok(interval) if (1..=999).contains(&interval) => Ok(interval),
+        _ => Err(RecurrenceError(format!(
+            "INTERVAL must be a number from 1 to 999, got: {}",
+            value
+        ))),
+    }
+}
+
+fn parse_weekdays(value: &str) -> Result<Vec<Weekday>, RecurrenceError> {
+    value
+        .split(',')
+        .map(|day| match day.to_uppercase().as_str() {
+            "MO" => Ok(Weekday::Mon),
+            "TU" => Ok(Weekday::Tue),
+            "WE" => Ok(Weekday::Wed),
+            "TH" => Ok(Weekday::Thu),
+            "FR" => Ok(Weekday::Fri),
+            "SA" => Ok(Weekday::Sat),
+            "SU" => Ok(Weekday::Sun),
+            other => Err(RecurrenceError(format!("Unsupported BYDAY day: {}", other))),
+        })
+        .collect()
+}
+
+fn parse_month_days(value: &str) -> Result<Vec<i32>, RecurrenceError> {
+    value
+        .split(',')
+        .map(|day| match day.parse::<i32>() {
+            Ok(day) if day != 0 && (-31..=31).contains(&day) => Ok(day),
+            _ => Err(RecurrenceError(format!(
+                "BYMONTHDAY days must be from 1 to 31 or -31 to -1, got: {}",
+                day
+            ))),
+        })
+        .collect()
+}
+
+/// Accepts both the date form (`20241231`) and the date-time form (`20241231T235959Z`).
+fn parse_until(value: &str) -> Result<NaiveDate, RecurrenceError> {
+    let date = value.split('T').next().unwrap_or_default();
+
+    NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| {
+        RecurrenceError(format!(
+            "UNTIL must be a date like 20241231, got: {}",
+            value
+        ))
+    })
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+/// The date of a day of the month, counting from the end when negative,
+/// or `None` when the month is too short to have it.
+fn resolve_month_day(month_start: NaiveDate, day: i32) -> Option<NaiveDate> {
+    let next_month = month_start.checked_add_months(Months::new(1))?;
+    let days_in_month = (next_month - month_start).num_days() as i32;
+
+    let day = if day < 0 {
+        days_in_month + day + 1
+    } else {
+        day
+    };
+    if day < 1 || day > days_in_month {
+        return None;
+    }
+
+    month_start.with_day(day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn next(rule: &str, after: NaiveDate) -> Option<NaiveDate> {
+        rule.parse::<Recurrence>().unwrap().next_date(after)
+    }
+
+    #[test]
+    fn repeats_daily_every_interval() {
+        assert_eq!(
+            next("FREQ=DAILY", date(2024, 2, 28)),
+            Some(date(2024, 2, 29))
+        );
+        assert_eq!(
+            next("FREQ=DAILY;INTERVAL=3", date(2024, 12, 30)),
+            Some(date(2025, 1, 2))
+        );
+    }
+
+    #[test]
+    fn repeats_weekly_on_the_given_days() {
+        // 2024-06-03 is a Monday.
+        assert_eq!(
+            next("FREQ=WEEKLY", date(2024, 6, 3)),
+            Some(date(2024, 6, 10))
+        );
+        assert_eq!(
+            next("FREQ=WEEKLY;BYDAY=MO,TH", date(2024, 6, 3)),
+            Some(date(2024, 6, 6))
+        );
+        assert_eq!(
+            next("FREQ=WEEKLY;BYDAY=MO,TH", date(2024, 6, 6)),
+            Some(date(2024, 6, 10))
+        );
+        assert_eq!(
+            next("FREQ=WEEKLY;INTERVAL=2;BYDAY=TH,MO", date(2024, 6, 6)),
+            Some(date(2024, 6, 17))
+        );
+    }
+
+    #[test]
+    fn repeats_monthly_skipping_the_months_without_the_day() {
+        assert_eq!(
+            next("FREQ=MONTHLY", date(2024, 1, 15)),
+            Some(date(2024, 2, 15))
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY", date(2024, 1, 31)),
+            Some(date(2024, 3, 31))
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=-1", date(2024, 1, 31)),
+            Some(date(2024, 2, 29))
+        );
+        assert_eq!(
+            next("FREQ=MONTHLY;BYMONTHDAY=1,15", date(2024, 1, 1)),
+            Some(date(2024, 1, 15))
+        );
+    }
+
+    #[test]
+    fn repeats_yearly_with_leap_days_on_leap_years() {
+        assert_eq!(
+            next("FREQ=YEARLY", date(2023, 7, 1)),
+            Some(date(2024, 7, 1))
+        );
+        assert_eq!(
+            next("FREQ=YEARLY", date(2024, 2, 29)),
+            Some(date(2028, 2, 29))
+        );
+    }
+
+    #[test]
+    fn keeps_the_time_and_stops_after_until() {
+        let rule: Recurrence = "FREQ=DAILY;UNTIL=20240602".parse().unwrap();
+        let due = Local.with_ymd_and_hms(2024, 6, 1, 12, 30, 0).unwrap();
+
+        let next = rule.next_after(due).unwrap();
+        assert_eq!(next, Local.with_ymd_and_hms(2024, 6, 2, 12, 30, 0).unwrap());
+        assert_eq!(rule.next_after(next), None);
+        assert_eq!(rule.occurrences(due, 10), vec![next]);
+    }
+
+    #[test]
+    fn parses_and_writes_rules_in_canonical_form() {
+        let rule: Recurrence = "RRULE:freq=weekly;byday=mo,th;interval=1".parse().unwrap();
+        assert_eq!(rule.to_string(), "FREQ=WEEKLY;BYDAY=MO,TH");
+
+        let rule: Recurrence = "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=-1;UNTIL=20241231T235959Z"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            rule.to_string(),
+            "FREQ=MONTHLY;INTERVAL=2;BYMONTHDAY=-1;UNTIL=20241231"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for rule in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;BYDAY=MO",
+            "FREQ=WEEKLY;BYMONTHDAY=1",
+            "FREQ=MONTHLY;BYMONTHDAY=32",
+            "FREQ=DAILY;FREQ=WEEKLY",
+            "FREQ=DAILY;COUNT=3",
+        ] {
+            assert!(rule.parse::<Recurrence>().is_err(), "{}", rule);
+        }
+    }
+}
@@ -1,3 +1,18 @@
 pub mod route_healthchecker;
+pub mod routes_attachments;
+pub mod routes_calendar;
+pub mod routes_comments;
+pub mod routes_events;
+pub mod routes_export;
+pub mod routes_import;
+pub mod routes_projects;
+pub mod routes_revisions;
+pub mod routes_shares;
+pub mod routes_static;
+pub mod routes_statuses;
+pub mod routes_tags;
+pub mod routes_task_items;
 pub mod routes_tasks;
+pub mod routes_time_entries;
 pub mod routes_users;
+pub mod routes_webhooks;
@@ -1,5 +1,10 @@
-use axum::{response::IntoResponse, routing::get, Json, Router};
+use std::sync::Arc;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
 use serde_json::json;
+use tokio::sync::RwLock;
+
+use crate::AppState;
 
 /// Axum Route Handler to check server status.
 async fn health_checker_handler() -> impl IntoResponse {
@@ -14,6 +19,23 @@ async fn health_checker_handler() -> impl IntoResponse {
     Json(json_response)
 }
 
-pub fn healthchecker_router() -> Router {
-    Router::new().route("/api/healthchecker", get(health_checker_handler))
+/// Axum Route Handler to check whether the server takes new requests: `503`
+/// with the status `draining` once it is shutting down, so that load balancers
+/// stop sending it requests.
+async fn readiness_handler(State(data): State<Arc<RwLock<AppState>>>) -> impl IntoResponse {
+    if data.read().await.shutdown.is_draining() {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({ "status": "draining" })),
+        )
+    } else {
+        (StatusCode::OK, Json(json!({ "status": "ready" })))
+    }
+}
+
+pub fn healthchecker_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route("/api/healthchecker", get(health_checker_handler))
+        .route("/api/readiness", get(readiness_handler))
+        .with_state(app_state)
 }
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use axum::{extract::DefaultBodyLimit, middleware, routing::get, Router};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::attachments_handlers::{
+        delete_attachment_handler, download_attachment_handler, get_attachments_handler,
+        upload_attachment_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn attachments_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/tasks/:id/attachments",
+            get(get_attachments_handler)
+                .post(upload_attachment_handler)
+                // The upload handler enforces the configured size limit itself.
+                .layer(DefaultBodyLimit::disable())
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/attachments/:attachment_id",
+            get(download_attachment_handler)
+                .delete(delete_attachment_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
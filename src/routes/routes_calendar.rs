@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::get, Router};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::calendar_handlers::{
+        calendar_feed_handler, delete_calendar_token_handler, get_calendar_token_handler,
+        regenerate_calendar_token_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn calendar_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/calendar/token",
+            get(get_calendar_token_handler)
+                .post(regenerate_calendar_token_handler)
+                .delete(delete_calendar_token_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        // The token in the address takes the place of the login.
+        .route("/api/calendar/:feed", get(calendar_feed_handler))
+        .with_state(app_state)
+}
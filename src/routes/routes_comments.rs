@@ -0,0 +1,34 @@
+use std::sync::Arc;
+
+use axum::{
+    middleware,
+    routing::{get, patch},
+    Router,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::comments_handlers::{
+        create_comment_handler, delete_comment_handler, get_comments_handler,
+        update_comment_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn comments_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/tasks/:id/comments",
+            get(get_comments_handler)
+                .post(create_comment_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/comments/:comment_id",
+            patch(update_comment_handler)
+                .delete(delete_comment_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::get, Router};
+use tokio::sync::RwLock;
+
+use crate::{handlers::events_handlers::events_handler, middleware::auth, AppState};
+
+pub fn events_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/events",
+            get(events_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
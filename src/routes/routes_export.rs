@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::get, Router};
+use tokio::sync::RwLock;
+
+use crate::{handlers::export_handlers::export_tasks_handler, middleware::auth, AppState};
+
+pub fn export_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/tasks/export",
+            get(export_tasks_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
@@ -0,0 +1,16 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::post, Router};
+use tokio::sync::RwLock;
+
+use crate::{handlers::import_handlers::import_tasks_handler, middleware::auth, AppState};
+
+pub fn import_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/tasks/import",
+            post(import_tasks_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
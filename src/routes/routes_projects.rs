@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::get, Router};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::projects_handlers::{
+        create_project_handler, delete_project_handler, get_projects_handler,
+        get_single_project_handler, update_project_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn projects_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/projects",
+            get(get_projects_handler)
+                .post(create_project_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/projects/:id",
+            get(get_single_project_handler)
+                .patch(update_project_handler)
+                .delete(delete_project_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
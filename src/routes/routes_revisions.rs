@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::revisions_handlers::{get_history_handler, revert_revision_handler},
+    middleware::auth,
+    AppState,
+};
+
+pub fn revisions_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/tasks/:id/history",
+            get(get_history_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/history/:revision_id/revert",
+            post(revert_revision_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use axum::{
+    middleware,
+    routing::{get, patch},
+    Router,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::shares_handlers::{
+        delete_share_handler, get_project_shares_handler, get_shared_with_me_handler,
+        get_task_shares_handler, share_project_handler, share_task_handler, update_share_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn shares_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/shares",
+            get(get_shared_with_me_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/shares/:id",
+            patch(update_share_handler)
+                .delete(delete_share_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/shares",
+            get(get_task_shares_handler)
+                .post(share_task_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/projects/:id/shares",
+            get(get_project_shares_handler)
+                .post(share_project_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use axum::Router;
+use tokio::sync::RwLock;
+use tower_http::compression::CompressionLayer;
+
+use crate::{handlers::static_handlers::static_file_handler, AppState};
+
+/// Serves the frontend for every path that no other router matched.
+pub fn static_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .fallback(static_file_handler)
+        .layer(CompressionLayer::new())
+        .with_state(app_state)
+}
@@ -0,0 +1,33 @@
+use std::sync::Arc;
+
+use axum::{
+    middleware,
+    routing::{get, patch},
+    Router,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::statuses_handlers::{
+        create_status_handler, delete_status_handler, get_statuses_handler, update_status_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn statuses_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/statuses",
+            get(get_statuses_handler)
+                .post(create_status_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/statuses/:id",
+            patch(update_status_handler)
+                .delete(delete_status_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use axum::{middleware, routing::get, Router};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::tags_handlers::{
+        create_tag_handler, delete_tag_handler, get_single_tag_handler, get_tags_handler,
+        update_tag_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn tags_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/tags",
+            get(get_tags_handler)
+                .post(create_tag_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tags/:id",
+            get(get_single_tag_handler)
+                .patch(update_tag_handler)
+                .delete(delete_tag_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use axum::{
+    middleware,
+    routing::{get, patch, post},
+    Router,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::task_items_handlers::{
+        create_task_item_handler, delete_task_item_handler, get_task_items_handler,
+        reorder_task_items_handler, update_task_item_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn task_items_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/tasks/:id/items",
+            get(get_task_items_handler)
+                .post(create_task_item_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/items/reorder",
+            post(reorder_task_items_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/items/:item_id",
+            patch(update_task_item_handler)
+                .delete(delete_task_item_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
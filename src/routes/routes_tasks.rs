@@ -1,12 +1,18 @@
 use std::sync::Arc;
 
-use axum::{middleware, routing::get, Router};
+use axum::{
+    middleware,
+    routing::{delete, get, post},
+    Router,
+};
 use tokio::sync::RwLock;
 
 use crate::{
     handlers::tasks_handlers::{
-        create_task_handler, delete_task_handler, get_single_task_handler, get_tasks_handler,
-        update_task_handler,
+        bulk_tasks_handler, clear_completed_handler, create_task_handler, delete_task_handler,
+        delete_task_permanently_handler, empty_trash_handler, get_single_task_handler,
+        get_task_changes_handler, get_tasks_handler, get_trash_handler, move_task_handler,
+        preview_recurrence_handler, restore_task_handler, update_task_handler,
     },
     middleware::auth,
     AppState,
@@ -20,6 +26,27 @@ pub fn tasks_router(app_state: Arc<RwLock<AppState>>) -> Router {
                 .post(create_task_handler)
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
+        .route(
+            "/api/tasks/trash",
+            get(get_trash_handler)
+                .delete(empty_trash_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/bulk",
+            post(bulk_tasks_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/changes",
+            get(get_task_changes_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/completed",
+            delete(clear_completed_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
         .route(
             "/api/tasks/:id",
             get(get_single_task_handler)
@@ -27,6 +54,26 @@ pub fn tasks_router(app_state: Arc<RwLock<AppState>>) -> Router {
                 .delete(delete_task_handler)
                 .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
         )
+        .route(
+            "/api/tasks/:id/move",
+            post(move_task_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/restore",
+            post(restore_task_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/permanent",
+            delete(delete_task_permanently_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/recurrence/preview",
+            post(preview_recurrence_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
         .with_state(app_state)
 }
 
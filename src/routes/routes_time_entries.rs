@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use axum::{
+    middleware,
+    routing::{delete, get, post},
+    Router,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::time_entries_handlers::{
+        create_time_entry_handler, delete_time_entry_handler, get_time_entries_handler,
+        get_timer_handler, start_timer_handler, stop_timer_handler, time_report_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn time_entries_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/timer",
+            get(get_timer_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/timer/stop",
+            post(stop_timer_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/timer/start",
+            post(start_timer_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/time-entries",
+            get(get_time_entries_handler)
+                .post(create_time_entry_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/tasks/:id/time-entries/:entry_id",
+            delete(delete_time_entry_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/time/report",
+            get(time_report_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
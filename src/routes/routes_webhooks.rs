@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use axum::{
+    middleware,
+    routing::{get, patch, post},
+    Router,
+};
+use tokio::sync::RwLock;
+
+use crate::{
+    handlers::webhooks_handlers::{
+        create_webhook_handler, delete_webhook_handler, get_deliveries_handler,
+        get_webhooks_handler, ping_webhook_handler, redeliver_handler, update_webhook_handler,
+    },
+    middleware::auth,
+    AppState,
+};
+
+pub fn webhooks_router(app_state: Arc<RwLock<AppState>>) -> Router {
+    Router::new()
+        .route(
+            "/api/webhooks",
+            get(get_webhooks_handler)
+                .post(create_webhook_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/webhooks/:id",
+            patch(update_webhook_handler)
+                .delete(delete_webhook_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/webhooks/:id/ping",
+            post(ping_webhook_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/webhooks/:id/deliveries",
+            get(get_deliveries_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .route(
+            "/api/webhooks/:id/deliveries/:delivery_id/redeliver",
+            post(redeliver_handler)
+                .route_layer(middleware::from_fn_with_state(app_state.clone(), auth)),
+        )
+        .with_state(app_state)
+}
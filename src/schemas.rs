@@ -2,7 +2,17 @@
 //! Define structures that allow us to deserialize and validate the request bodies.
 //! Validation ensures that the necessary fields are present in the request body and that they have the correct data types.
 
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local, NaiveDate};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    import::ImportedTask,
+    models::{FieldChange, Role},
+};
 
 /****** Tasks entities ******/
 
@@ -19,22 +29,354 @@ pub struct CreateTaskSchema {
     pub title: String,
     pub description: String,
     pub user_id: String,
+    pub project_id: Option<String>,
+    pub auto_complete: bool,
+    pub due_date: Option<DateTime<Local>>,
+    pub recurrence: Option<String>,
 }
 
-/// Used as the type for an extractor through which data is sent to update a task.
-#[derive(Debug, Serialize, Deserialize)]
-pub struct UpdateTaskBody {
+/// Used as the template type through which data is sent to the database to update a task.
+/// Only the fields that are set are sent, so the others keep their current values;
+/// `Some(None)` sends a `null`.
+#[derive(Debug, Serialize)]
+pub struct UpdateTaskSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub completed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<Option<Uuid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_complete: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_date: Option<Option<DateTime<Local>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Option<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_id: Option<Option<Uuid>>,
+    pub updated_at: DateTime<Local>,
 }
 
-/// Used as the template type through which data is sent to the database to update a task.
+/// Used as the type for the query string extractor that filters the task list.
+/// `tags` is a comma-separated list of tag ids and `tag_mode` is either
+/// `or` (the task has any of the tags, the default) or `and` (it has all of them).
+/// `project` is a project id, or `inbox` for the tasks that are not in any project.
+#[derive(Debug, Deserialize)]
+pub struct FilterTasksQuery {
+    pub tags: Option<String>,
+    pub tag_mode: Option<String>,
+    pub project: Option<String>,
+}
+
+/// Used as the type for the query string extractor of a task export.
+/// `format` is `json` (the default), `csv`, `md` or `ics`; the task list filters
+/// (see `FilterTasksQuery`) apply too, plus `completed` to keep only tasks that are or are not done.
+#[derive(Debug, Deserialize)]
+pub struct ExportTasksQuery {
+    pub format: Option<String>,
+    pub completed: Option<bool>,
+}
+
+/// Used as the type for the query string extractor of a task import.
+/// `format` is `csv`, `json`, `todotxt` or `ics`, by default the extension of the file;
+/// `project_id` is the project the tasks go to, and `dry_run` only reports what would be imported.
+#[derive(Debug, Deserialize)]
+pub struct ImportTasksQuery {
+    pub format: Option<String>,
+    pub project_id: Option<Uuid>,
+    pub dry_run: Option<bool>,
+}
+
+/// Used as the template type of the arguments of the `import_tasks` database function.
+#[derive(Debug, Serialize)]
+pub struct ImportTasksSchema {
+    pub p_user_id: String,
+    pub p_project_id: Option<String>,
+    pub p_tasks: Vec<ImportedTask>,
+}
+
+/// Used as the type for the query string extractor of a calendar feed.
+/// `items` is `events` (the default, shown by every calendar app) or `todos`;
+/// completed tasks are left out unless `completed` is true, and `project` limits
+/// the feed to a project (or `inbox`).
+#[derive(Debug, Deserialize)]
+pub struct CalendarFeedQuery {
+    pub items: Option<String>,
+    pub completed: Option<bool>,
+    pub project: Option<String>,
+}
+
+/// Used as the type for the query string extractor of the changes to the tasks since `since`,
+/// or since the change of the task `after` at that moment; the task list filters
+/// (see `FilterTasksQuery`) apply too.
+#[derive(Debug, Deserialize)]
+pub struct TaskChangesQuery {
+    pub since: DateTime<Local>,
+    pub after: Option<Uuid>,
+}
+
+/// Used as the type for the query string extractor that picks the completed tasks to clear.
+/// `project` is a project id, or `inbox` for the tasks that are not in any project.
+#[derive(Debug, Deserialize)]
+pub struct ClearCompletedQuery {
+    pub project: Option<String>,
+}
+
+/// Used as the template type through which a task is associated with a tag in the database.
 #[derive(Debug, Deserialize, Serialize)]
-pub struct UpdateTaskSchema {
-    pub title: String,
-    pub description: String,
-    pub completed: bool,
+pub struct TaskTagSchema {
+    pub task_id: String,
+    pub tag_id: String,
+}
+
+/****** Checklist items entities ******/
+
+/// Used as the template type through which data is sent to the database to create a checklist item.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateTaskItemSchema {
+    pub text: String,
+    pub position: i32,
+    pub task_id: String,
+}
+
+/// Used as the template type through which data is sent to the database to update a checklist item.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateTaskItemSchema {
+    pub text: String,
+    pub done: bool,
+}
+
+/// Used as the type for an extractor through which the new order of the checklist items is sent.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReorderTaskItemsBody {
+    pub item_ids: Vec<Uuid>,
+}
+
+/****** Tags entities ******/
+
+/// Used as the template type through which data is sent to the database to create a tag.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateTagSchema {
+    pub name: String,
+    pub color: String,
+    pub user_id: String,
+}
+
+/// Used as the template type through which data is sent to the database to update a tag.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateTagSchema {
+    pub name: String,
+    pub color: String,
+}
+
+/****** Projects entities ******/
+
+/// Used as the template type through which data is sent to the database to create a project.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateProjectSchema {
+    pub name: String,
+    pub color: String,
+    pub icon: String,
+    pub sort_order: i32,
+    pub user_id: String,
+}
+
+/// Used as the template type through which data is sent to the database to update a project.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateProjectSchema {
+    pub name: String,
+    pub color: String,
+    pub icon: String,
+    pub archived: bool,
+    pub sort_order: i32,
+}
+
+/// Used as the type for the query string extractor of the project list.
+/// Archived projects are only listed when `archived=true`.
+#[derive(Debug, Deserialize)]
+pub struct ProjectsQuery {
+    pub archived: Option<bool>,
+}
+
+/// Used as the type for the query string extractor when deleting a project.
+/// `tasks` is either `inbox` (the default: its tasks are kept without a project)
+/// or `cascade` (its tasks are deleted as well).
+#[derive(Debug, Deserialize)]
+pub struct DeleteProjectQuery {
+    pub tasks: Option<String>,
+}
+
+/****** Statuses entities ******/
+
+/// Used as the template type through which data is sent to the database to create a status.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateStatusSchema {
+    pub name: String,
+    pub color: String,
+    pub done: bool,
+    pub sort_order: i32,
+    pub user_id: String,
+    pub project_id: Option<String>,
+}
+
+/// Used as the template type through which data is sent to the database to update a status.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateStatusSchema {
+    pub name: String,
+    pub color: String,
+    pub done: bool,
+    pub sort_order: i32,
+}
+
+/// Used as the type for the query string extractor of the status list.
+/// Without `project` the columns are those of the personal board of the user.
+#[derive(Debug, Deserialize)]
+pub struct StatusesQuery {
+    pub project: Option<Uuid>,
+}
+
+/****** Shares entities ******/
+
+/// Used as the template type through which data is sent to the database to share
+/// a task or a project: exactly one of `task_id` and `project_id` is set.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateShareSchema {
+    pub task_id: Option<String>,
+    pub project_id: Option<String>,
+    pub user_id: String,
+    pub role: Role,
+    pub shared_by: String,
+}
+
+/// Used as the template type through which data is sent to the database to change a share.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateShareSchema {
+    pub role: Role,
+}
+
+/****** Comments entities ******/
+
+/// Used as the template type through which data is sent to the database to create a comment.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateCommentSchema {
+    pub body: String,
+    pub task_id: String,
+    pub user_id: String,
+}
+
+/// Used as the template type through which data is sent to the database to edit a comment.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateCommentSchema {
+    pub body: String,
+    pub updated_at: DateTime<Local>,
+}
+
+/// Used as the type for the query string extractor of the comment list.
+/// Pages start at 1; `limit` is the number of comments per page.
+#[derive(Debug, Deserialize)]
+pub struct CommentsQuery {
+    pub page: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+/****** Attachments entities ******/
+
+/// Used as the template type through which data is sent to the database to record an
+/// uploaded attachment. The id is chosen up front since it is part of the storage key.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateAttachmentSchema {
+    pub id: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    pub storage_key: String,
+    pub task_id: String,
+    pub user_id: String,
+}
+
+/// Used as the template type of the arguments of the `insert_attachment` database function.
+#[derive(Debug, Serialize)]
+pub struct InsertAttachmentSchema<'a> {
+    pub p_attachment: &'a CreateAttachmentSchema,
+    pub p_quota: i64,
+}
+
+/****** Revisions entities ******/
+
+/// Used as the template type through which data is sent to the database to record a revision.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateRevisionSchema {
+    pub task_id: String,
+    pub user_id: String,
+    pub changes: BTreeMap<String, FieldChange>,
+}
+
+/****** Time entries entities ******/
+
+/// Used as the template type through which data is sent to the database to record
+/// time spent on a task, or to start a timer (`ended_at` is `None`).
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateTimeEntrySchema {
+    pub started_at: DateTime<Local>,
+    pub ended_at: Option<DateTime<Local>>,
+    pub note: Option<String>,
+    pub task_id: String,
+    pub user_id: String,
+}
+
+/// Used as the type for the query string extractor of the time report.
+/// `from` and `to` are the first and last days of the report (the last week by default)
+/// and `group_by` is `day` (the default), `project` or `tag`.
+#[derive(Debug, Deserialize)]
+pub struct TimeReportQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub group_by: Option<String>,
+}
+
+/****** Webhooks entities ******/
+
+/// Used as the template type through which data is sent to the database to register a webhook.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateWebhookSchema {
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub user_id: String,
+}
+
+/// Used as the template type through which a webhook is updated in the database.
+/// Only the fields that are set are sent, so the others keep their current values.
+#[derive(Debug, Serialize)]
+pub struct UpdateWebhookSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+}
+
+/// Used as the template type through which an event is queued for a webhook in the database.
+#[derive(Debug, Serialize)]
+pub struct CreateWebhookDeliverySchema {
+    pub webhook_id: String,
+    pub event: String,
+    pub payload: Value,
+}
+
+/// Used as the template type through which the outcome of an attempt
+/// to deliver an event is recorded in the database.
+#[derive(Debug, Serialize)]
+pub struct UpdateWebhookDeliverySchema {
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Local>,
+    pub last_attempt_at: DateTime<Local>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
 }
 
 /****** Users entities ******/
@@ -54,6 +396,13 @@ pub struct LoginUserBody {
     pub password: String,
 }
 
+/// Used as the template type through which the calendar feed token of a user
+/// is set (or, with `None`, removed) in the database.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CalendarTokenSchema {
+    pub calendar_token: Option<String>,
+}
+
 /****** Token Data ******/
 
 /// It is used as the template type through which the authentication token is sent using a cookieo.
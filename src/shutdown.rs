@@ -0,0 +1,80 @@
+//! Graceful shutdown. On SIGINT or SIGTERM the server reports itself as draining,
+//! keeps accepting requests for the configured delay (so that load balancers stop
+//! sending it new ones), then stops accepting them and lets those in flight finish
+//! within the drain timeout. The live event streams and the background jobs end
+//! as soon as the shutdown begins; the task events of the requests that went
+//! through are still sent out before the server exits.
+
+use std::time::Duration;
+
+use tokio::sync::watch;
+use tokio_stream::{wrappers::WatchStream, Stream, StreamExt};
+
+/// Whether the server is shutting down, for whatever has to stop then.
+#[derive(Debug, Clone)]
+pub struct Shutdown(watch::Receiver<bool>);
+
+impl Shutdown {
+    pub fn is_draining(&self) -> bool {
+        *self.0.borrow()
+    }
+
+    /// Resolves once the shutdown has begun.
+    pub async fn draining(&mut self) {
+        // The sender only goes away with the server itself.
+        let _ = self.0.wait_for(|draining| *draining).await;
+    }
+
+    /// Yields once the shutdown has begun, to end a stream with.
+    pub fn stream(&self) -> impl Stream<Item = ()> {
+        WatchStream::new(self.0.clone())
+            .filter(|draining| *draining)
+            .map(|_| ())
+    }
+}
+
+pub fn channel() -> (watch::Sender<bool>, Shutdown) {
+    let (sender, receiver) = watch::channel(false);
+    (sender, Shutdown(receiver))
+}
+
+/// Waits for SIGINT (Ctrl+C) or SIGTERM, then begins the shutdown. A signal that
+/// cannot be listened to is reported and left out, as the server runs fine without it.
+pub async fn listen(sender: watch::Sender<bool>) {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            println!("->> ❌ Failed to listen for SIGINT: {}\n", err);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                terminate.recv().await;
+            }
+            Err(err) => {
+                println!("->> ❌ Failed to listen for SIGTERM: {}\n", err);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    println!("->> 🛑 Shutting down: draining the requests in flight...\n");
+    sender.send_replace(true);
+}
+
+/// Resolves when the server has to stop accepting connections: the delay
+/// after the shutdown has begun.
+pub async fn stop_accepting(mut shutdown: Shutdown, delay: Duration) {
+    shutdown.draining().await;
+    tokio::time::sleep(delay).await;
+}
@@ -6,8 +6,12 @@ use axum::{
     Json, RequestExt,
 };
 
-use serde::Deserialize;
-use validator::Validate;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{models::Role, recurrence::Recurrence, webhooks::TaskEvent};
 
 #[derive(Debug, Deserialize, Validate)]
 pub struct RegisterEntry {
@@ -40,11 +44,350 @@ pub struct LoginEntry {
 }
 
 #[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_task_schedule", skip_on_field_errors = false))]
 pub struct TaskEntry {
     #[validate(length(min = 1, message = "Title is required"))]
     pub title: String,
     #[validate(length(min = 1, message = "Description is required"))]
     pub description: String,
+    #[serde(default)]
+    pub tag_ids: Vec<Uuid>,
+    pub project_id: Option<Uuid>,
+    #[serde(default)]
+    pub auto_complete: bool,
+    pub due_date: Option<DateTime<Local>>,
+    #[validate(custom = "validate_recurrence")]
+    pub recurrence: Option<String>,
+}
+
+/// The fields of a task to change; the missing ones are left as they are.
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(
+    function = "validate_task_update_schedule",
+    skip_on_field_errors = false
+))]
+pub struct UpdateTaskEntry {
+    #[validate(length(min = 1, message = "Title is required"))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, message = "Description is required"))]
+    pub description: Option<String>,
+    pub completed: Option<bool>,
+    pub tag_ids: Option<Vec<Uuid>>,
+    /// `null` moves the task back to the inbox, a missing field leaves it where it is.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub project_id: Option<Option<Uuid>>,
+    pub auto_complete: Option<bool>,
+    /// `null` clears the due date, a missing field leaves it as it is.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub due_date: Option<Option<DateTime<Local>>>,
+    /// `null` stops the task from repeating, a missing field leaves it as it is.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    #[validate(custom = "validate_recurrence")]
+    pub recurrence: Option<Option<String>>,
+    /// Moving a task to a column also sets `completed`; `null` takes it out of its column.
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub status_id: Option<Option<Uuid>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecurrencePreviewEntry {
+    #[validate(custom = "validate_recurrence")]
+    pub rule: String,
+    /// Due date of the current occurrence; now when missing.
+    pub start: Option<DateTime<Local>>,
+    #[validate(range(min = 1, max = 100, message = "Count must be between 1 and 100"))]
+    pub count: Option<usize>,
+}
+
+/// Where a task is dropped: between the task that ends up right above it (`before`)
+/// and the one right below it (`after`). One of them is missing at either end of the list.
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_task_move"))]
+pub struct MoveTaskEntry {
+    pub before: Option<Uuid>,
+    pub after: Option<Uuid>,
+}
+
+/// One action applied to many tasks at once.
+#[derive(Debug, Deserialize, Validate)]
+pub struct BulkTaskEntry {
+    #[validate(length(
+        min = 1,
+        max = 100,
+        message = "Between 1 and 100 task IDs are required"
+    ))]
+    pub ids: Vec<Uuid>,
+    #[serde(flatten)]
+    pub action: BulkAction,
+}
+
+/// The `action` of a bulk request, along with the fields it needs.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BulkAction {
+    Complete,
+    Uncomplete,
+    Delete,
+    /// A missing or `null` project moves the tasks back to the inbox.
+    Move {
+        project_id: Option<Uuid>,
+    },
+    AddTag {
+        tag_id: Uuid,
+    },
+    RemoveTag {
+        tag_id: Uuid,
+    },
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TaskItemEntry {
+    #[validate(
+        length(min = 1, message = "Text is required"),
+        length(max = 255, message = "Text cannot be longer than 255 characters")
+    )]
+    pub text: String,
+    pub position: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTaskItemEntry {
+    #[validate(
+        length(min = 1, message = "Text is required"),
+        length(max = 255, message = "Text cannot be longer than 255 characters")
+    )]
+    pub text: Option<String>,
+    pub done: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct TagEntry {
+    #[validate(
+        length(min = 1, message = "Name is required"),
+        length(max = 30, message = "Name cannot have more than 30 characters")
+    )]
+    pub name: String,
+    #[validate(custom = "validate_color")]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateTagEntry {
+    #[validate(
+        length(min = 1, message = "Name is required"),
+        length(max = 30, message = "Name cannot have more than 30 characters")
+    )]
+    pub name: Option<String>,
+    #[validate(custom = "validate_color")]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ProjectEntry {
+    #[validate(
+        length(min = 1, message = "Name is required"),
+        length(max = 50, message = "Name cannot have more than 50 characters")
+    )]
+    pub name: String,
+    #[validate(custom = "validate_color")]
+    pub color: Option<String>,
+    #[validate(length(min = 1, max = 8, message = "Icon must be a short symbol or emoji"))]
+    pub icon: Option<String>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateProjectEntry {
+    #[validate(
+        length(min = 1, message = "Name is required"),
+        length(max = 50, message = "Name cannot have more than 50 characters")
+    )]
+    pub name: Option<String>,
+    #[validate(custom = "validate_color")]
+    pub color: Option<String>,
+    #[validate(length(min = 1, max = 8, message = "Icon must be a short symbol or emoji"))]
+    pub icon: Option<String>,
+    pub archived: Option<bool>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct StatusEntry {
+    #[validate(
+        length(min = 1, message = "Name is required"),
+        length(max = 30, message = "Name cannot have more than 30 characters")
+    )]
+    pub name: String,
+    #[validate(custom = "validate_color")]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub done: bool,
+    pub sort_order: Option<i32>,
+    /// The project whose board gets the column; the personal board of the user when missing.
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateStatusEntry {
+    #[validate(
+        length(min = 1, message = "Name is required"),
+        length(max = 30, message = "Name cannot have more than 30 characters")
+    )]
+    pub name: Option<String>,
+    #[validate(custom = "validate_color")]
+    pub color: Option<String>,
+    pub done: Option<bool>,
+    pub sort_order: Option<i32>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ShareEntry {
+    #[validate(
+        length(min = 1, message = "Email is required"),
+        email(message = "Email is invalid")
+    )]
+    pub email: String,
+    pub role: Role,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateShareEntry {
+    pub role: Role,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CommentEntry {
+    #[validate(
+        length(min = 1, message = "Comment is required"),
+        length(max = 2000, message = "Comment cannot be longer than 2000 characters")
+    )]
+    pub body: String,
+}
+
+/// Time spent on a task that was not tracked with the timer.
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_time_entry_range"))]
+pub struct TimeEntryEntry {
+    pub started_at: DateTime<Local>,
+    pub ended_at: DateTime<Local>,
+    #[validate(length(max = 255, message = "Note cannot be longer than 255 characters"))]
+    pub note: Option<String>,
+}
+
+/// An address to send task events to, and which events.
+#[derive(Debug, Deserialize, Validate)]
+pub struct WebhookEntry {
+    #[validate(url(message = "URL is invalid"))]
+    pub url: String,
+    #[validate(
+        length(min = 1, message = "At least one event is required"),
+        custom = "validate_webhook_events"
+    )]
+    pub events: Vec<String>,
+}
+
+/// The fields of a webhook to change; the missing ones are left as they are.
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateWebhookEntry {
+    #[validate(url(message = "URL is invalid"))]
+    pub url: Option<String>,
+    #[validate(
+        length(min = 1, message = "At least one event is required"),
+        custom = "validate_webhook_events"
+    )]
+    pub events: Option<Vec<String>>,
+    pub active: Option<bool>,
+}
+
+/// Colors are stored as hex strings in the `#rrggbb` form.
+fn validate_color(color: &str) -> Result<(), ValidationError> {
+    let is_hex = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+
+    if !is_hex {
+        let mut error = ValidationError::new("color");
+        error.message = Some("Color must be in the #rrggbb format".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+fn validate_recurrence(rule: &str) -> Result<(), ValidationError> {
+    if let Err(err) = rule.parse::<Recurrence>() {
+        let mut error = ValidationError::new("recurrence");
+        error.message = Some(err.to_string().into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+fn validate_webhook_events(events: &[String]) -> Result<(), ValidationError> {
+    if let Some(event) = events
+        .iter()
+        .find(|event| TaskEvent::from_name(event).is_none())
+    {
+        let mut error = ValidationError::new("events");
+        error.message = Some(format!("Unknown event: {}", event).into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// A repeating task needs a due date to move forward on each occurrence.
+fn validate_task_schedule(task: &TaskEntry) -> Result<(), ValidationError> {
+    if task.recurrence.is_some() && task.due_date.is_none() {
+        let mut error = ValidationError::new("due_date");
+        error.message = Some("A recurring task needs a due date".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// The part of the schedule rule that can be told from the update alone:
+/// a task cannot be given a rule while its due date is being cleared.
+fn validate_task_update_schedule(task: &UpdateTaskEntry) -> Result<(), ValidationError> {
+    if matches!(task.recurrence, Some(Some(_))) && matches!(task.due_date, Some(None)) {
+        let mut error = ValidationError::new("due_date");
+        error.message = Some("A recurring task needs a due date".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+fn validate_task_move(task_move: &MoveTaskEntry) -> Result<(), ValidationError> {
+    if task_move.before.is_none() && task_move.after.is_none() {
+        let mut error = ValidationError::new("before");
+        error.message = Some("A task to move before or after is required".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+fn validate_time_entry_range(entry: &TimeEntryEntry) -> Result<(), ValidationError> {
+    if entry.ended_at < entry.started_at {
+        let mut error = ValidationError::new("ended_at");
+        error.message = Some("A time entry cannot end before it starts".into());
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// Lets an `Option<Option<T>>` field tell a missing value (`None`)
+/// apart from an explicit `null` (`Some(None)`).
+fn deserialize_nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
 }
 
 /// Use this to encapsulate fields that require validation
@@ -0,0 +1,319 @@
+//! Outgoing webhooks: the task events that users can subscribe to, how their
+//! payloads are signed and sent, and when a failed delivery is tried again.
+//!
+//! Every request carries the headers `X-Webhook-Event`, `X-Webhook-Delivery`,
+//! `X-Webhook-Timestamp` (Unix seconds) and `X-Webhook-Signature`, which is
+//! `sha256=` followed by the hex HMAC-SHA256, keyed with the secret of the webhook,
+//! of `<timestamp>.<body>`. Receivers should recompute it and reject old timestamps.
+//!
+//! Webhooks only reach public `https` receivers, checked when they are registered and
+//! again before each delivery, so that users cannot make the server call into its own
+//! network; `WEBHOOK_ALLOW_LOCAL` lifts this for receivers run locally while testing.
+
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+use ring::hmac;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Longest part of the body of a response kept in the delivery log, in bytes;
+/// the rest is not even read.
+const MAX_LOGGED_RESPONSE: usize = 1000;
+/// Wait before the first retry of a failed delivery; it doubles with each attempt.
+const RETRY_BASE_SECONDS: i64 = 30;
+/// Longest wait between two attempts.
+const MAX_RETRY_SECONDS: i64 = 6 * 60 * 60;
+
+/// The events a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskEvent {
+    Created,
+    Updated,
+    Completed,
+    Deleted,
+}
+
+impl TaskEvent {
+    pub const ALL: [TaskEvent; 4] = [
+        TaskEvent::Created,
+        TaskEvent::Updated,
+        TaskEvent::Completed,
+        TaskEvent::Deleted,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            TaskEvent::Created => "task.created",
+            TaskEvent::Updated => "task.updated",
+            TaskEvent::Completed => "task.completed",
+            TaskEvent::Deleted => "task.deleted",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        TaskEvent::ALL
+            .into_iter()
+            .find(|event| event.name() == name)
+    }
+}
+
+/// Event sent on demand to check that a webhook is reachable.
+pub const PING_EVENT: &str = "ping";
+
+/// The body sent for an event: `data` is what it is about, e.g. `{ "task": ... }`.
+pub fn payload(event: &str, actor_id: &Uuid, data: Value) -> Value {
+    json!({
+        "event": event,
+        "created_at": Local::now(),
+        "actor_id": actor_id,
+        "data": data
+    })
+}
+
+/// The value of the `X-Webhook-Signature` header of a request.
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let tag = hmac::sign(&key, format!("{}.{}", timestamp, body).as_bytes());
+    let hex: String = tag
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    format!("sha256={}", hex)
+}
+
+/// When to try a delivery again after its `attempts`-th attempt failed.
+pub fn next_attempt_at(attempts: i32) -> DateTime<Local> {
+    let exponent = attempts.clamp(1, 20) as u32 - 1;
+    let wait = RETRY_BASE_SECONDS
+        .saturating_mul(2_i64.saturating_pow(exponent))
+        .min(MAX_RETRY_SECONDS);
+
+    Local::now() + chrono::Duration::seconds(wait)
+}
+
+/// Checks that the server may send requests to a webhook URL: `https` only, to a host
+/// whose every address is public. Both rules are lifted by `allow_local`.
+pub async fn check_url(url: &str, allow_local: bool) -> Result<(), String> {
+    let url = reqwest::Url::parse(url).map_err(|_| "URL is invalid".to_string())?;
+    if allow_local {
+        return Ok(());
+    }
+    if url.scheme() != "https" {
+        return Err("The webhook URL must use https".to_string());
+    }
+
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return Err("The webhook URL must have a host".to_string());
+    };
+    // An IPv6 host keeps its brackets in the URL.
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addresses: Vec<IpAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| {
+            format!(
+                "The host of the webhook URL could not be resolved: {}",
+                host
+            )
+        })?
+        .map(|address| address.ip())
+        .collect();
+
+    if addresses.is_empty() || !addresses.iter().all(|address| is_public(*address)) {
+        return Err(
+            "The webhook URL must not point to a private, loopback or link-local address"
+                .to_string(),
+        );
+    }
+
+    Ok(())
+}
+
+/// Whether an address is reachable on the internet, rather than on the network
+/// of the server or on the server itself.
+fn is_public(address: IpAddr) -> bool {
+    match address {
+        IpAddr::V4(address) => is_public_v4(address),
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => is_public_v6(address),
+        },
+    }
+}
+
+fn is_public_v4(address: Ipv4Addr) -> bool {
+    let [first, second, ..] = address.octets();
+    // 0.0.0.0/8 is "this network", and 100.64.0.0/10 the shared space of carrier NATs.
+    let this_network = first == 0;
+    let shared = first == 100 && (64..128).contains(&second);
+
+    !(this_network
+        || shared
+        || address.is_loopback()
+        || address.is_private()
+        || address.is_link_local()
+        || address.is_broadcast()
+        || address.is_documentation()
+        || address.is_multicast())
+}
+
+fn is_public_v6(address: Ipv6Addr) -> bool {
+    !(address.is_unspecified()
+        || address.is_loopback()
+        || address.is_multicast()
+        || address.is_unique_local()
+        || address.is_unicast_link_local())
+}
+
+/// What came of an attempt to deliver an event.
+pub struct DeliveryOutcome {
+    pub succeeded: bool,
+    pub response_status: Option<i32>,
+    /// The body of an error response, or why there was no response.
+    pub error: Option<String>,
+}
+
+/// Sends an event to a webhook; any 2xx response counts as delivered.
+pub async fn send(
+    http: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    delivery_id: &Uuid,
+    event: &str,
+    payload: &Value,
+    timeout: Duration,
+) -> DeliveryOutcome {
+    let body = payload.to_string();
+    let timestamp = Local::now().timestamp();
+
+    let response = http
+        .post(url)
+        .timeout(timeout)
+        .header("Content-Type", "application/json")
+        .header("User-Agent", "rust-axum-supabase-api-webhooks")
+        .header("X-Webhook-Event", event)
+        .header("X-Webhook-Delivery", delivery_id.to_string())
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header("X-Webhook-Signature", signature(secret, timestamp, &body))
+        .body(body)
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            let error = if status.is_success() {
+                None
+            } else {
+                Some(read_start(response, MAX_LOGGED_RESPONSE).await)
+            };
+
+            DeliveryOutcome {
+                succeeded: status.is_success(),
+                response_status: Some(i32::from(status.as_u16())),
+                error,
+            }
+        }
+        Err(err) => DeliveryOutcome {
+            succeeded: false,
+            response_status: None,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+/// Reads at most `limit` bytes of the body of a response, chunk by chunk, so that
+/// a webhook answering with a huge body cannot make the server buffer all of it.
+async fn read_start(mut response: reqwest::Response, limit: usize) -> String {
+    let mut body = Vec::new();
+    while body.len() < limit {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    body.truncate(limit);
+
+    String::from_utf8_lossy(&body).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_takes_public_addresses_as_receivers() {
+        for address in ["93.184.216.34", "2606:2800:220:1:248:1893:25c8:1946"] {
+            assert!(is_public(address.parse().unwrap()), "{}", address);
+        }
+        for address in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+        ] {
+            assert!(!is_public(address.parse().unwrap()), "{}", address);
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_urls_that_reach_into_the_local_network() {
+        assert!(check_url("https://127.0.0.1/hook", false).await.is_err());
+        assert!(check_url("https://[::1]:8443/hook", false).await.is_err());
+        assert!(check_url("https://169.254.169.254/latest", false)
+            .await
+            .is_err());
+        assert!(check_url("http://93.184.216.34/hook", false).await.is_err());
+        assert!(check_url("https://93.184.216.34/hook", false).await.is_ok());
+        assert!(check_url("http://127.0.0.1:9000/hook", true).await.is_ok());
+    }
+
+    #[test]
+    fn signs_the_timestamp_and_the_body() {
+        // As computed by any other HMAC-SHA256, e.g. Python's `hmac` module.
+        assert_eq!(
+            signature("whsec_test", 1700000000, r#"{"event":"ping"}"#),
+            "sha256=aa8efe37b751e71157c508c5ac4acb1e9fe5225db98355dfc00f4b680afbc447"
+        );
+    }
+
+    #[test]
+    fn changes_the_signature_with_any_part() {
+        let signed = signature("whsec_test", 1700000000, "{}");
+
+        assert_ne!(signed, signature("whsec_other", 1700000000, "{}"));
+        assert_ne!(signed, signature("whsec_test", 1700000001, "{}"));
+        assert_ne!(signed, signature("whsec_test", 1700000000, "{ }"));
+    }
+
+    #[test]
+    fn names_the_events() {
+        for event in TaskEvent::ALL {
+            assert_eq!(TaskEvent::from_name(event.name()), Some(event));
+        }
+        assert_eq!(TaskEvent::from_name(PING_EVENT), None);
+    }
+
+    #[test]
+    fn backs_off_exponentially_up_to_a_limit() {
+        let wait = |attempts| (next_attempt_at(attempts) - Local::now()).num_seconds();
+
+        assert!((RETRY_BASE_SECONDS - 1..=RETRY_BASE_SECONDS).contains(&wait(1)));
+        assert!((4 * RETRY_BASE_SECONDS - 1..=4 * RETRY_BASE_SECONDS).contains(&wait(3)));
+        assert!((MAX_RETRY_SECONDS - 1..=MAX_RETRY_SECONDS).contains(&wait(50)));
+    }
+}